@@ -0,0 +1,316 @@
+//! Proofs of knowledge of an AES-128 key.
+//!
+//! The circuit works over boolean wires, with each byte represented by its 8
+//! bits (least significant first), and the key and blocks by their 16 bytes in
+//! order. Bytes are elements of $\textsf{GF}(2^8)$ reduced over
+//! $x^8 + x^4 + x^3 + x + 1$, as in FIPS-197.
+//!
+//! The S-box inverts its input as `x^254`, computed with 4 multiplications of
+//! 64 `AND` gates each. All the other steps of AES are linear over `F2` and
+//! only require additions.
+
+use crate::backend_trait::BackendT;
+use eyre::{eyre, Result};
+use scuttlebutt::{field::F2, ring::FiniteRing};
+
+// The round constants of the key schedule.
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+// Multiply `x` by the generator `x` of GF(2^8).
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ if x & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+// Return `x^k` for `k` in `0..n`, reduced in GF(2^8).
+fn powers(n: usize) -> Vec<u8> {
+    let mut power = 1;
+    let mut out = Vec::with_capacity(n);
+    for _ in 0..n {
+        out.push(power);
+        power = xtime(power);
+    }
+    out
+}
+
+fn bit(x: u8, i: usize) -> F2 {
+    if (x >> i) & 1 == 1 {
+        F2::ONE
+    } else {
+        F2::ZERO
+    }
+}
+
+// Return the 8 wires `out[i] = sum of x[j] such that bit `i` of columns[j] is set`.
+fn linear_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    columns: &[u8],
+    x: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    let mut out = Vec::with_capacity(8);
+    for i in 0..8 {
+        let mut acc = backend.constant(F2::ZERO)?;
+        for (column, x) in columns.iter().zip(x) {
+            if bit(*column, i) == F2::ONE {
+                acc = backend.add(&acc, x)?;
+            }
+        }
+        out.push(acc);
+    }
+    Ok(out)
+}
+
+fn xor_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+    b: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    a.iter().zip(b).map(|(a, b)| backend.add(a, b)).collect()
+}
+
+fn xor_constant_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+    c: u8,
+) -> Result<Vec<B::Wire>> {
+    a.iter()
+        .enumerate()
+        .map(|(i, a)| backend.add_constant(a, bit(c, i)))
+        .collect()
+}
+
+fn square_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    // In characteristic two, the square of `sum a_i x^i` is `sum a_i x^(2i)`.
+    let columns: Vec<_> = powers(15).into_iter().step_by(2).collect();
+    linear_circuit(backend, &columns, a)
+}
+
+fn mul_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+    b: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    // Schoolbook product of the polynomials, followed by the reduction.
+    let mut coeffs = Vec::with_capacity(15);
+    for _ in 0..15 {
+        coeffs.push(backend.constant(F2::ZERO)?);
+    }
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            let ab = backend.mul(a, b)?;
+            coeffs[i + j] = backend.add(&coeffs[i + j], &ab)?;
+        }
+    }
+    linear_circuit(backend, &powers(15), &coeffs)
+}
+
+fn sbox_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    x: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    // The inverse is `x^254`, which maps zero to zero as required.
+    let x2 = square_circuit(backend, x)?;
+    let x3 = mul_circuit(backend, &x2, x)?;
+    let x6 = square_circuit(backend, &x3)?;
+    let x12 = square_circuit(backend, &x6)?;
+    let x15 = mul_circuit(backend, &x12, &x3)?;
+    let mut x240 = x15;
+    for _ in 0..4 {
+        x240 = square_circuit(backend, &x240)?;
+    }
+    let x252 = mul_circuit(backend, &x240, &x12)?;
+    let inverse = mul_circuit(backend, &x252, &x2)?;
+    // Affine transformation: bit `i` is the sum of the bits `i, i + 4, ..., i + 7`
+    // modulo 8 of the inverse, plus the bit `i` of `0x63`.
+    let columns: Vec<u8> = (0..8).map(|j| 0x1fu8.rotate_left(j)).collect();
+    let affine = linear_circuit(backend, &columns, &inverse)?;
+    xor_constant_circuit(backend, &affine, 0x63)
+}
+
+fn xtime_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    let columns: Vec<_> = powers(9).into_iter().skip(1).collect();
+    linear_circuit(backend, &columns, a)
+}
+
+// Return the round keys of `key`, each given as 16 bytes.
+fn key_schedule_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    key: Vec<Vec<B::Wire>>,
+) -> Result<Vec<Vec<Vec<B::Wire>>>> {
+    let mut words: Vec<Vec<Vec<B::Wire>>> = Vec::with_capacity(44);
+    let mut key = key.into_iter();
+    for _ in 0..4 {
+        words.push(key.by_ref().take(4).collect());
+    }
+    for i in 4..44 {
+        let mut word = Vec::with_capacity(4);
+        for k in 0..4 {
+            let byte = if i % 4 == 0 {
+                // `w[i - 4] + SubWord(RotWord(w[i - 1])) + Rcon[i / 4]`.
+                let sub = sbox_circuit(backend, &words[i - 1][(k + 1) % 4])?;
+                let rcon = if k == 0 { RCON[i / 4 - 1] } else { 0 };
+                let temp = xor_constant_circuit(backend, &sub, rcon)?;
+                xor_circuit(backend, &words[i - 4][k], &temp)?
+            } else {
+                xor_circuit(backend, &words[i - 4][k], &words[i - 1][k])?
+            };
+            word.push(byte);
+        }
+        words.push(word);
+    }
+    let mut words = words.into_iter();
+    Ok((0..11)
+        .map(|_| words.by_ref().take(4).flatten().collect())
+        .collect())
+}
+
+fn mix_columns_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    state: &[Vec<B::Wire>],
+) -> Result<Vec<Vec<B::Wire>>> {
+    let mut out = Vec::with_capacity(16);
+    for column in state.chunks(4) {
+        for i in 0..4 {
+            // `2 a_i + 3 a_{i+1} + a_{i+2} + a_{i+3}`, with `3 a = 2 a + a`.
+            let [a0, a1, a2, a3] = [0, 1, 2, 3].map(|k| &column[(i + k) % 4]);
+            let sum = xor_circuit(backend, a0, a1)?;
+            let mut byte = xtime_circuit(backend, &sum)?;
+            for a in [a1, a2, a3] {
+                byte = xor_circuit(backend, &byte, a)?;
+            }
+            out.push(byte);
+        }
+    }
+    Ok(out)
+}
+
+/// Encrypt the public `plaintext` with AES-128 under the key given by its 128
+/// bit wires, and return the 128 bit wires of the ciphertext.
+pub fn aes128_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    key_bits: Vec<B::Wire>,
+    plaintext: [u8; 16],
+) -> Result<Vec<B::Wire>> {
+    if key_bits.len() != 128 {
+        return Err(eyre!("An AES-128 key has 128 bits, got {}", key_bits.len()));
+    }
+    let mut key_bits = key_bits.into_iter();
+    let key = (0..16)
+        .map(|_| key_bits.by_ref().take(8).collect())
+        .collect();
+    let round_keys = key_schedule_circuit(backend, key)?;
+
+    let mut state = Vec::with_capacity(16);
+    for (p, k) in plaintext.iter().zip(&round_keys[0]) {
+        state.push(xor_constant_circuit(backend, k, *p)?);
+    }
+    for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+        let mut sub = Vec::with_capacity(16);
+        for byte in state.iter() {
+            sub.push(Some(sbox_circuit(backend, byte)?));
+        }
+        // The byte in row `r` and column `c` is at index `r + 4c`, and row `r`
+        // is rotated left by `r` columns.
+        let shifted: Vec<_> = (0..16)
+            .map(|i| {
+                let (r, c) = (i % 4, i / 4);
+                sub[r + 4 * ((c + r) % 4)].take().unwrap()
+            })
+            .collect();
+        let mixed = if round < 10 {
+            mix_columns_circuit(backend, &shifted)?
+        } else {
+            shifted
+        };
+        state = Vec::with_capacity(16);
+        for (byte, k) in mixed.iter().zip(round_key) {
+            state.push(xor_circuit(backend, byte, k)?);
+        }
+    }
+    Ok(state.into_iter().flatten().collect())
+}
+
+/// Prove knowledge of the AES-128 key given by its 128 bit wires, mapping the
+/// public `plaintext` to the public `expected_ciphertext`.
+///
+/// Bit `j` of byte `i` of the key is `key_bits[8 * i + j]`. The key schedule
+/// and the 10 rounds are evaluated in the circuit, for a total of 200 S-boxes
+/// and 51200 multiplications.
+pub fn prove_aes128<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    key_bits: Vec<B::Wire>,
+    plaintext: [u8; 16],
+    expected_ciphertext: [u8; 16],
+) -> Result<()> {
+    let out = aes128_circuit(backend, key_bits, plaintext)?;
+    for (i, wire) in out.iter().enumerate() {
+        let diff = backend.add_constant(wire, bit(expected_ciphertext[i / 8], i % 8))?;
+        backend.assert_zero(&diff)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bit, prove_aes128};
+    use crate::{backend_trait::BackendT, plaintext::PlaintextBackend, test_utils::run};
+    use eyre::Result;
+    use scuttlebutt::field::{F40b, F2};
+
+    // The example vector of Appendix C.1 of FIPS-197.
+    const KEY: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f,
+    ];
+    const PLAINTEXT: [u8; 16] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+        0xff,
+    ];
+    const CIPHERTEXT: [u8; 16] = [
+        0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5,
+        0x5a,
+    ];
+
+    fn circuit<B: BackendT<FieldElement = F2>>(
+        backend: &mut B,
+        key: Option<[u8; 16]>,
+    ) -> Result<()> {
+        let key_bits = (0..128)
+            .map(|i| backend.input_private(key.map(|k| bit(k[i / 8], i % 8))))
+            .collect::<Result<Vec<_>>>()?;
+        prove_aes128(backend, key_bits, PLAINTEXT, CIPHERTEXT)
+    }
+
+    #[test]
+    fn test_aes128_plaintext() {
+        circuit(&mut PlaintextBackend::<F2>::new(), Some(KEY)).unwrap();
+        let mut wrong_key = KEY;
+        wrong_key[15] ^= 1;
+        assert!(circuit(&mut PlaintextBackend::<F2>::new(), Some(wrong_key)).is_err());
+    }
+
+    #[test]
+    fn test_aes128() {
+        let (prover, verifier) =
+            run::<F40b, _, _>(|dmc| circuit(dmc, Some(KEY)), |dmc| circuit(dmc, None));
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_aes128_wrong_key_fails() {
+        let mut wrong_key = KEY;
+        wrong_key[0] ^= 0x80;
+        let (prover, verifier) = run::<F40b, _, _>(
+            move |dmc| circuit(dmc, Some(wrong_key)),
+            |dmc| circuit(dmc, None),
+        );
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! Proving a circuit in a larger field when a small field is not sound enough.
+//!
+//! The soundness of the multiplication checks decreases with the number of
+//! multiplications, so that a large circuit may not reach a target soundness in
+//! a small field. [`prove_with_auto_field`] and [`verify_with_auto_field`] run a
+//! proof in a small field, and run it again in a larger field with the same prime
+//! subfield when the estimated soundness is below a target. Both parties compute
+//! the same estimate from the number of multiplications, so they agree on
+//! whether to rerun the proof without communicating.
+
+use crate::{
+    backend::{DietMacAndCheeseProver, DietMacAndCheeseVerifier},
+    backend_trait::BackendT,
+};
+use eyre::Result;
+use ocelot::svole::wykw::LpnParams;
+use scuttlebutt::{
+    field::{FiniteField, IsSubFieldOf},
+    AbstractChannel, AesRng,
+};
+
+/// A circuit which can be proven with any backend over the field `F`.
+///
+/// Wires of the prover and the verifier differ with the field of the MACs, so
+/// the circuit is generic over the backend.
+pub trait AutoFieldCircuit<F> {
+    /// Evaluate the circuit on `backend`.
+    ///
+    /// The prover provides the values of the private inputs, and the verifier
+    /// provides `None` for each of them.
+    fn run<B: BackendT<FieldElement = F>>(&mut self, backend: &mut B) -> Result<()>;
+}
+
+/// The outcome of a proof with [`prove_with_auto_field`] or
+/// [`verify_with_auto_field`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AutoFieldOutcome {
+    /// Whether the proof was run again in the larger field.
+    pub upgraded: bool,
+    /// The estimated bits of soundness of the multiplication checks of the proof.
+    pub soundness_bits: f64,
+}
+
+/// Prove `circuit` in `Small`, and again in `Large` when the soundness of the
+/// multiplication checks is less than `target_bits`.
+///
+/// This fails if the circuit or any of the proofs fails, without running the
+/// proof in `Large` when the proof in `Small` fails.
+pub fn prove_with_auto_field<Small, Large, C>(
+    channel: &mut C,
+    rng: &mut AesRng,
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    no_batching: bool,
+    target_bits: f64,
+    circuit: &mut impl AutoFieldCircuit<Small::PrimeField>,
+) -> Result<AutoFieldOutcome>
+where
+    Small: FiniteField,
+    Large: FiniteField<PrimeField = Small::PrimeField>,
+    Small::PrimeField: IsSubFieldOf<Large>,
+    C: AbstractChannel,
+{
+    let soundness_bits = {
+        let mut dmc = DietMacAndCheeseProver::<Small, _, _>::init(
+            channel,
+            rng.fork(),
+            lpn_setup,
+            lpn_extend,
+            no_batching,
+        )?;
+        run(&mut dmc, circuit)?;
+        dmc.mult_check_soundness_bits()
+    };
+    if soundness_bits >= target_bits {
+        return Ok(AutoFieldOutcome {
+            upgraded: false,
+            soundness_bits,
+        });
+    }
+
+    let mut dmc = DietMacAndCheeseProver::<Large, _, _>::init(
+        channel,
+        rng.fork(),
+        lpn_setup,
+        lpn_extend,
+        no_batching,
+    )?;
+    run(&mut dmc, circuit)?;
+    Ok(AutoFieldOutcome {
+        upgraded: true,
+        soundness_bits: dmc.mult_check_soundness_bits(),
+    })
+}
+
+/// Verify `circuit` in `Small`, and again in `Large` when the soundness of the
+/// multiplication checks is less than `target_bits`.
+///
+/// This must be run against [`prove_with_auto_field`] with the same fields,
+/// parameters and target.
+pub fn verify_with_auto_field<Small, Large, C>(
+    channel: &mut C,
+    rng: &mut AesRng,
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    no_batching: bool,
+    target_bits: f64,
+    circuit: &mut impl AutoFieldCircuit<Small::PrimeField>,
+) -> Result<AutoFieldOutcome>
+where
+    Small: FiniteField,
+    Large: FiniteField<PrimeField = Small::PrimeField>,
+    Small::PrimeField: IsSubFieldOf<Large>,
+    C: AbstractChannel,
+{
+    let soundness_bits = {
+        let mut dmc = DietMacAndCheeseVerifier::<Small, _, _>::init(
+            channel,
+            rng.fork(),
+            lpn_setup,
+            lpn_extend,
+            no_batching,
+        )?;
+        run(&mut dmc, circuit)?;
+        dmc.mult_check_soundness_bits()
+    };
+    if soundness_bits >= target_bits {
+        return Ok(AutoFieldOutcome {
+            upgraded: false,
+            soundness_bits,
+        });
+    }
+
+    let mut dmc = DietMacAndCheeseVerifier::<Large, _, _>::init(
+        channel,
+        rng.fork(),
+        lpn_setup,
+        lpn_extend,
+        no_batching,
+    )?;
+    run(&mut dmc, circuit)?;
+    Ok(AutoFieldOutcome {
+        upgraded: true,
+        soundness_bits: dmc.mult_check_soundness_bits(),
+    })
+}
+
+// Run `circuit` followed by `finalize`, resetting the backend on failure so
+// that it can be dropped.
+fn run<B: BackendT>(
+    backend: &mut B,
+    circuit: &mut impl AutoFieldCircuit<B::FieldElement>,
+) -> Result<()> {
+    let out = circuit.run(backend).and_then(|_| backend.finalize());
+    if out.is_err() {
+        backend.reset();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove_with_auto_field, verify_with_auto_field, AutoFieldCircuit};
+    use crate::{backend_trait::BackendT, test_utils::run_channels};
+    use eyre::Result;
+    use ocelot::svole::wykw::{LPN_EXTEND_SMALL, LPN_SETUP_SMALL};
+    use scuttlebutt::{
+        field::{F128b, F40b, F2},
+        ring::FiniteRing,
+    };
+
+    // Prove that `x` is one by squaring it `nb_mults` times.
+    struct Squares {
+        x: Option<F2>,
+        nb_mults: usize,
+    }
+
+    impl AutoFieldCircuit<F2> for Squares {
+        fn run<B: BackendT<FieldElement = F2>>(&mut self, backend: &mut B) -> Result<()> {
+            let mut x = backend.input_private(self.x)?;
+            for _ in 0..self.nb_mults {
+                x = backend.mul(&x, &x)?;
+            }
+            let zero = backend.add_constant(&x, F2::ONE)?;
+            backend.assert_zero(&zero)
+        }
+    }
+
+    // Return the outcomes of the prover and the verifier.
+    fn auto_field(nb_mults: usize, target_bits: f64) -> (bool, f64) {
+        let (prover, verifier) = run_channels(
+            move |channel, mut rng| {
+                prove_with_auto_field::<F40b, F128b, _>(
+                    channel,
+                    &mut rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                    target_bits,
+                    &mut Squares {
+                        x: Some(F2::ONE),
+                        nb_mults,
+                    },
+                )
+                .unwrap()
+            },
+            |channel, mut rng| {
+                verify_with_auto_field::<F40b, F128b, _>(
+                    channel,
+                    &mut rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                    target_bits,
+                    &mut Squares { x: None, nb_mults },
+                )
+                .unwrap()
+            },
+        );
+        assert_eq!(prover, verifier);
+        (verifier.upgraded, verifier.soundness_bits)
+    }
+
+    #[test]
+    fn test_small_circuit_stays_in_small_field() {
+        let (upgraded, bits) = auto_field(10, 30.0);
+        assert!(!upgraded);
+        assert!((30.0..40.0).contains(&bits));
+    }
+
+    #[test]
+    fn test_large_circuit_upgrades_field() {
+        // `40 - log2(5003)` is less than 28 bits.
+        let (upgraded, bits) = auto_field(5000, 30.0);
+        assert!(upgraded);
+        assert!(bits >= 100.0);
+    }
+}
@@ -1,13 +1,29 @@
 use crate::edabits::RcRefCell;
+use crate::error::DmcError;
+#[cfg(feature = "debug-graph")]
+use crate::gate_graph::GateGraph;
 use crate::homcom::{
     FComProver, FComVerifier, MacProver, MacVerifier, StateMultCheckProver, StateMultCheckVerifier,
 };
-use eyre::{eyre, Context, Result};
+use crate::zero_check::{Batched, ZeroCheckSchedule};
+use eyre::{eyre, Result};
 use generic_array::{typenum::Unsigned, GenericArray};
 use log::{debug, info, warn};
 use ocelot::svole::wykw::LpnParams;
 use rand::{CryptoRng, Rng};
-use scuttlebutt::{field::FiniteField, AbstractChannel};
+use scuttlebutt::{
+    field::FiniteField, ring::FiniteRing, serialization::CanonicalSerialize, AbstractChannel,
+};
+use std::{
+    fmt::Write,
+    marker::PhantomData,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 // Some design decisions:
 // * There is one queue for the multiplication check and another queue for `assert_zero`s.
@@ -26,14 +42,14 @@ fn padded_read<FE: FiniteField>(mut x: &[u8]) -> Result<FE> {
         x = &x[0..x.len() - 1];
     }
     if x.len() > FE::ByteReprLen::USIZE {
-        Err(eyre!("Invalid field element"))
+        Err(DmcError::InvalidFieldElement.into())
     } else {
         let mut out = GenericArray::default();
         let size = x.len().min(FE::ByteReprLen::USIZE);
         out[0..size].copy_from_slice(&x[0..size]);
         // NOTE: the FE type doesn't require that from_bytes be little-endian. However, we
         // currently implement it that way for all fields.
-        FE::from_bytes(&out).context("Invalid field element")
+        FE::from_bytes(&out).map_err(|_| DmcError::InvalidFieldElement.into())
     }
 }
 
@@ -42,59 +58,387 @@ pub fn from_bytes_le<FE: FiniteField>(val: &[u8]) -> Result<FE> {
     padded_read(val)
 }
 
+// Compare two prime field elements as integers, using their little-endian byte representation.
+fn int_less_than<F: FiniteField>(a: &F, b: &F) -> bool {
+    a.to_bytes().iter().rev().lt(b.to_bytes().iter().rev())
+}
+
+// Return the value of `x` if it is public, i.e., if its MAC is zero.
+//
+// Public values have a zero MAC, and affine operations on them keep it zero, so
+// that they can be computed in the clear. A private value may also have a zero
+// MAC, in which case computing in the clear gives the same result.
+fn public_value<FE: FiniteField>(x: &MacProver<FE>) -> Option<FieldClear<FE>> {
+    if x.mac() == FE::ZERO {
+        Some(x.value())
+    } else {
+        None
+    }
+}
+
+// An estimate of the bits of soundness of the multiplication checks of
+// `nb_mults` multiplications over `FE`.
+//
+// The checks combine the triples with the powers of a random challenge, and a
+// cheating prover succeeds with probability about `(nb_mults + 3) / |FE|`.
+fn mult_check_soundness_bits<FE: FiniteField>(nb_mults: usize) -> f64 {
+    let field_bits = FE::NumberOfBitsInBitDecomposition::USIZE as f64;
+    field_bits - ((nb_mults + 3) as f64).log2()
+}
+
+// An estimate of the number of round trips checking `nb_check_zero` values
+// asserted to be zero and `nb_mults` multiplications, up to `finalize`.
+//
+// Each batch of zero checks and each multiplication check takes one round trip,
+// and `finalize` always checks both queues. The sVOLE extensions are not counted.
+fn estimated_round_trips(
+    nb_check_zero: usize,
+    nb_mults: usize,
+    no_batching: bool,
+    zero_check_schedule: &dyn ZeroCheckSchedule,
+    mult_check_interval: Option<usize>,
+) -> usize {
+    let chunks = |queued: usize| {
+        // An empty queue is still checked, as a single empty chunk.
+        let chunk = zero_check_schedule.chunk_size(queued).max(1);
+        queued.saturating_sub(1) / chunk + 1
+    };
+    let mut round_trips = 0;
+    let mut queued = 0;
+    for _ in 0..nb_check_zero {
+        queued += 1;
+        if no_batching || zero_check_schedule.should_check(queued) {
+            round_trips += chunks(queued);
+            queued = 0;
+        }
+    }
+    round_trips += chunks(queued);
+    round_trips += match mult_check_interval {
+        Some(interval) => nb_mults / interval + 1,
+        None => 1,
+    };
+    round_trips
+}
+
+/// Return the number of the next `remaining` values asserted to be zero that
+/// fill a queue of `queued` values up to its next check under `schedule`, or
+/// `remaining` if they do not trigger a check.
+fn values_until_check(schedule: &dyn ZeroCheckSchedule, queued: usize, remaining: usize) -> usize {
+    (1..remaining)
+        .find(|n| schedule.should_check(queued + n))
+        .unwrap_or(remaining)
+}
+
+/// The cancellation flag of the checks which are not cancellable.
+static NOT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Fail with [`DmcError::Cancelled`] if `cancelled` is set, marking the party
+/// as failed through its `is_ok`.
+fn check_cancelled(is_ok: &mut bool, cancelled: &AtomicBool) -> Result<()> {
+    if cancelled.load(Ordering::Relaxed) {
+        *is_ok = false;
+        return Err(DmcError::Cancelled.into());
+    }
+    Ok(())
+}
+
+/// The kind of check performed by a flush of a party.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushKind {
+    /// The values asserted to be zero were checked.
+    CheckZero,
+    /// The multiplication triples were checked.
+    MultCheck,
+}
+
+/// A flush of the queue of a party, reported to the hook set with
+/// `set_flush_hook`.
+#[derive(Clone, Copy, Debug)]
+pub struct FlushEvent {
+    /// The kind of check.
+    pub kind: FlushKind,
+    /// The number of values or multiplication triples checked.
+    pub batch_len: usize,
+    /// The number of bytes sent and received by the party during the check, if
+    /// its channel counts them (see [`AbstractChannel::comm_bytes`]).
+    pub bytes: Option<usize>,
+    /// The time taken by the check, including its communication.
+    pub duration: Duration,
+}
+
+/// A callback receiving each [`FlushEvent`] of a party.
+pub type FlushHook = Box<dyn FnMut(FlushEvent) + Send>;
+
+impl FlushEvent {
+    /// Return the event of a check which started at `start`, when the channel
+    /// had counted `comm_start` bytes, and now counts `comm_end` bytes.
+    fn new(
+        kind: FlushKind,
+        batch_len: usize,
+        start: Instant,
+        comm_start: Option<(usize, usize)>,
+        comm_end: Option<(usize, usize)>,
+    ) -> Self {
+        let bytes = match (comm_start, comm_end) {
+            (Some((sent, received)), Some((sent_end, received_end))) => {
+                Some(sent_end - sent + received_end - received)
+            }
+            _ => None,
+        };
+        Self {
+            kind,
+            batch_len,
+            bytes,
+            duration: start.elapsed(),
+        }
+    }
+}
+
+/// A callback receiving the [`CircuitStats`] of a party periodically, set with
+/// `set_progress_callback`.
+pub type ProgressCallback = Box<dyn FnMut(&CircuitStats) + Send>;
+
+/// The outcome of finalizing a party when it is dropped, as set up by
+/// `finalize_on_drop`.
+///
+/// A dropped party is gone, so the error of that finalization is kept in this
+/// handle, shared with the party, rather than in the party itself.
+#[derive(Clone, Debug, Default)]
+pub struct DropError(Arc<Mutex<Option<eyre::Report>>>);
+
+impl DropError {
+    /// Take the error of the finalization on drop, if it failed.
+    ///
+    /// This is `None` until the party is dropped, and after the error is taken.
+    pub fn take_drop_error(&self) -> Option<eyre::Report> {
+        self.0.lock().unwrap().take()
+    }
+
+    fn set(&self, e: eyre::Report) {
+        *self.0.lock().unwrap() = Some(e);
+    }
+}
+
+/// A source of private values for the prover, pulled one at a time by
+/// `input_private_stream`.
+///
+/// This is implemented by every iterator over the values, e.g., one decoding
+/// them from a file as they are read. Its method is not named `next`, so that
+/// calling `next` on an iterator stays unambiguous with this trait in scope.
+pub trait WitnessSource<F> {
+    /// Return the next value, or `None` once the source is exhausted.
+    fn next_value(&mut self) -> Option<F>;
+}
+
+impl<F, I: Iterator<Item = F>> WitnessSource<F> for I {
+    fn next_value(&mut self) -> Option<F> {
+        self.next()
+    }
+}
+
+/// The bytes communicated by a party, as counted by its channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommStats {
+    /// The number of bytes sent.
+    pub bytes_sent: usize,
+    /// The number of bytes received.
+    pub bytes_received: usize,
+}
+
+impl CommStats {
+    fn from_channel<C: AbstractChannel>(channel: &C) -> Option<Self> {
+        channel
+            .comm_bytes()
+            .map(|(bytes_sent, bytes_received)| Self {
+                bytes_sent,
+                bytes_received,
+            })
+    }
+
+    fn log(&self) {
+        info!("bytes sent:     {:>11}", self.bytes_sent);
+        info!("bytes received: {:>11}", self.bytes_received);
+    }
+}
+
 const QUEUE_CAPACITY: usize = 3_000_000;
+// The number of private values input at once by `input_private_stream`.
+const STREAM_BLOCK: usize = 1 << 16;
 const TICK_TIMER: usize = 5_000_000;
 
-#[derive(Default)]
+/// A snapshot of the gate counts and checks of a party of Diet Mac'n'Cheese.
+///
+/// The counts accumulate over all the proofs of the party, as for
+/// `reset_proof_state`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CircuitStats {
+    /// The number of public inputs.
+    pub public_inputs: usize,
+    /// The number of private inputs.
+    pub private_inputs: usize,
+    /// The number of additions.
+    pub add: usize,
+    /// The number of subtractions.
+    pub sub: usize,
+    /// The number of additions by a constant.
+    pub add_constant: usize,
+    /// The number of multiplications.
+    pub mul: usize,
+    /// The number of multiplications by a constant.
+    pub mul_constant: usize,
+    /// The number of additions and multiplications by a constant on public
+    /// values, which the prover folds into a public constant.
+    ///
+    /// They are also counted in `add_constant` and `mul_constant`, so that the
+    /// other counts of both parties agree. The verifier cannot tell public values
+    /// apart and always reports 0.
+    pub folded: usize,
+    /// The number of assertions that a value is zero.
+    pub assert_zero: usize,
+    /// The number of values checked by the zero checks.
+    pub checked_zero: usize,
+    /// The number of multiplications checked by the multiplication checks.
+    pub checked_mul: usize,
+    /// The number of zero checks.
+    pub flushes_check_zero: usize,
+    /// The number of multiplication checks.
+    pub flushes_mult_check: usize,
+    /// The time spent in the zero checks.
+    pub flush_time_check_zero: Duration,
+    /// The time spent in the multiplication checks.
+    pub flush_time_mult_check: Duration,
+}
+
 struct Monitor {
     tick: usize,
+    // The number of gates between two reports of the counts.
+    tick_interval: usize,
+    // The total number of gates.
+    gates: usize,
+    start: Instant,
+    // Called instead of logging the counts every `tick_interval` gates.
+    progress: Option<ProgressCallback>,
     monitor_instance: usize,
     monitor_witness: usize,
     monitor_mul: usize,
     monitor_mulc: usize,
     monitor_add: usize,
+    monitor_sub: usize,
     monitor_addc: usize,
+    // Affine operations on public values folded into a public constant.
+    monitor_folded: usize,
     monitor_check_zero: usize,
     monitor_zk_check_zero: usize,
     monitor_zk_mult_check: usize,
+    flushes_check_zero: usize,
+    flushes_mult_check: usize,
+    flush_time_check_zero: Duration,
+    flush_time_mult_check: Duration,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self {
+            tick: 0,
+            tick_interval: TICK_TIMER,
+            gates: 0,
+            start: Instant::now(),
+            progress: None,
+            monitor_instance: 0,
+            monitor_witness: 0,
+            monitor_mul: 0,
+            monitor_mulc: 0,
+            monitor_add: 0,
+            monitor_sub: 0,
+            monitor_addc: 0,
+            monitor_folded: 0,
+            monitor_check_zero: 0,
+            monitor_zk_check_zero: 0,
+            monitor_zk_mult_check: 0,
+            flushes_check_zero: 0,
+            flushes_mult_check: 0,
+            flush_time_check_zero: Duration::ZERO,
+            flush_time_mult_check: Duration::ZERO,
+        }
+    }
 }
 
 impl Monitor {
+    // Reset the counts and the clock, keeping the progress reporting settings.
+    fn reset(&mut self) {
+        *self = Self {
+            tick_interval: self.tick_interval,
+            progress: self.progress.take(),
+            ..Self::default()
+        };
+    }
+
     fn tick(&mut self) {
         self.tick += 1;
-        if self.tick >= TICK_TIMER {
-            self.tick %= TICK_TIMER;
-            self.log_monitor();
+        self.gates += 1;
+        if self.tick >= self.tick_interval {
+            self.tick = 0;
+            let stats = self.stats();
+            match self.progress.as_mut() {
+                Some(progress) => progress(&stats),
+                None => self.log_monitor(),
+            }
+        }
+    }
+
+    // Count `n` gates at once, reporting progress at most once.
+    fn tick_many(&mut self, n: usize) {
+        self.tick += n;
+        self.gates += n;
+        if self.tick >= self.tick_interval {
+            self.tick %= self.tick_interval;
+            let stats = self.stats();
+            match self.progress.as_mut() {
+                Some(progress) => progress(&stats),
+                None => self.log_monitor(),
+            }
         }
     }
 
     fn incr_monitor_instance(&mut self) {
-        self.tick();
         self.monitor_instance += 1;
+        self.tick();
     }
     fn incr_monitor_mul(&mut self) {
-        self.tick();
         self.monitor_mul += 1;
+        self.tick();
     }
     fn incr_monitor_mulc(&mut self) {
-        self.tick();
         self.monitor_mulc += 1;
+        self.tick();
     }
     fn incr_monitor_add(&mut self) {
-        self.tick();
         self.monitor_add += 1;
+        self.tick();
     }
-    fn incr_monitor_addc(&mut self) {
+    fn incr_monitor_sub(&mut self) {
+        self.monitor_sub += 1;
         self.tick();
+    }
+    fn incr_monitor_addc(&mut self) {
         self.monitor_addc += 1;
+        self.tick();
+    }
+    // Folded operations are also counted as an addc or a mulc, which ticks.
+    fn incr_monitor_folded(&mut self) {
+        self.monitor_folded += 1;
     }
     fn incr_monitor_check_zero(&mut self) {
-        self.tick();
         self.monitor_check_zero += 1;
+        self.tick();
+    }
+    fn incr_monitor_check_zero_many(&mut self, n: usize) {
+        self.monitor_check_zero += n;
+        self.tick_many(n);
     }
     fn incr_monitor_witness(&mut self) {
-        self.tick();
         self.monitor_witness += 1;
+        self.tick();
     }
 
     fn incr_zk_mult_check(&mut self, n: usize) {
@@ -104,15 +448,136 @@ impl Monitor {
         self.monitor_zk_check_zero += n;
     }
 
+    fn record_flush(&mut self, event: &FlushEvent) {
+        match event.kind {
+            FlushKind::CheckZero => {
+                self.flushes_check_zero += 1;
+                self.flush_time_check_zero += event.duration;
+            }
+            FlushKind::MultCheck => {
+                self.flushes_mult_check += 1;
+                self.flush_time_mult_check += event.duration;
+            }
+        }
+    }
+
+    fn stats(&self) -> CircuitStats {
+        CircuitStats {
+            public_inputs: self.monitor_instance,
+            private_inputs: self.monitor_witness,
+            add: self.monitor_add,
+            sub: self.monitor_sub,
+            add_constant: self.monitor_addc,
+            mul: self.monitor_mul,
+            mul_constant: self.monitor_mulc,
+            folded: self.monitor_folded,
+            assert_zero: self.monitor_check_zero,
+            checked_zero: self.monitor_zk_check_zero,
+            checked_mul: self.monitor_zk_mult_check,
+            flushes_check_zero: self.flushes_check_zero,
+            flushes_mult_check: self.flushes_mult_check,
+            flush_time_check_zero: self.flush_time_check_zero,
+            flush_time_mult_check: self.flush_time_mult_check,
+        }
+    }
+
+    // Render the counts as metrics in the Prometheus text format, labeled by `party`.
+    fn prometheus(&self, party: &str) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, help: &str, label: &str, samples: &[(&str, String)]| {
+            writeln!(out, "# HELP {} {}", name, help).unwrap();
+            writeln!(out, "# TYPE {} counter", name).unwrap();
+            for (value_label, value) in samples {
+                writeln!(
+                    out,
+                    "{}{{party=\"{}\",{}=\"{}\"}} {}",
+                    name, party, label, value_label, value
+                )
+                .unwrap();
+            }
+        };
+        metric(
+            "dmc_gates_total",
+            "Number of gates evaluated, by gate.",
+            "gate",
+            &[
+                ("input_public", self.monitor_instance.to_string()),
+                ("input_private", self.monitor_witness.to_string()),
+                ("add", self.monitor_add.to_string()),
+                ("sub", self.monitor_sub.to_string()),
+                ("addc", self.monitor_addc.to_string()),
+                ("mul", self.monitor_mul.to_string()),
+                ("mulc", self.monitor_mulc.to_string()),
+                ("folded", self.monitor_folded.to_string()),
+                ("assert_zero", self.monitor_check_zero.to_string()),
+            ],
+        );
+        metric(
+            "dmc_checked_total",
+            "Number of values and multiplications checked, by kind of check.",
+            "kind",
+            &[
+                ("check_zero", self.monitor_zk_check_zero.to_string()),
+                ("mult_check", self.monitor_zk_mult_check.to_string()),
+            ],
+        );
+        metric(
+            "dmc_flushes_total",
+            "Number of flushes, by kind of check.",
+            "kind",
+            &[
+                ("check_zero", self.flushes_check_zero.to_string()),
+                ("mult_check", self.flushes_mult_check.to_string()),
+            ],
+        );
+        metric(
+            "dmc_flush_seconds_total",
+            "Time spent in flushes, by kind of check.",
+            "kind",
+            &[
+                (
+                    "check_zero",
+                    self.flush_time_check_zero.as_secs_f64().to_string(),
+                ),
+                (
+                    "mult_check",
+                    self.flush_time_mult_check.as_secs_f64().to_string(),
+                ),
+            ],
+        );
+        out
+    }
+
+    // The number of multiplications minus the number of multiplications
+    // covered by the multiplication checks.
+    fn mult_check_discrepancy(&self) -> isize {
+        self.monitor_mul as isize - self.monitor_zk_mult_check as isize
+    }
+
+    // The number of gates per second since the monitor was created, or zero
+    // if no time has elapsed.
+    fn gate_rate(&self) -> f64 {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            self.gates as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+
     fn log_monitor(&self) {
         info!(
-            "inp:{:<11} witn:{:<11} mul:{:<11} czero:{:<11}",
-            self.monitor_instance, self.monitor_witness, self.monitor_mul, self.monitor_check_zero,
+            "inp:{:<11} witn:{:<11} mul:{:<11} czero:{:<11} rate:{:.0} gates/s",
+            self.monitor_instance,
+            self.monitor_witness,
+            self.monitor_mul,
+            self.monitor_check_zero,
+            self.gate_rate(),
         );
     }
 
     fn log_final_monitor(&self) {
-        if self.monitor_mul != self.monitor_zk_mult_check {
+        if self.mult_check_discrepancy() != 0 {
             warn!(
                 "diff numb of mult gates {} and mult_check {}",
                 self.monitor_mul, self.monitor_zk_mult_check
@@ -123,7 +588,9 @@ impl Monitor {
         info!("nb witn:   {:>11}", self.monitor_witness);
         info!("nb addc:   {:>11}", self.monitor_addc);
         info!("nb add:    {:>11}", self.monitor_add);
+        info!("nb sub:    {:>11}", self.monitor_sub);
         info!("nb multc:  {:>11}", self.monitor_mulc);
+        info!("nb folded: {:>11}", self.monitor_folded);
         info!("nb mult:   {:>11}", self.monitor_mul);
         info!("nb czero:  {:>11}", self.monitor_check_zero);
     }
@@ -134,16 +601,118 @@ impl Monitor {
 // the underlying prime field `FE::PrimeField`.
 type FieldClear<FE> = <FE as FiniteField>::PrimeField;
 
+/// Configuration of a [`DietMacAndCheeseProver`], independent of its channel and state.
+///
+/// The configuration records the field, the LPN parameters, whether batching is
+/// disabled, the zero check schedule, the multiplication check interval,
+/// whether the multiplication check is strict and the budget of private inputs.
+/// Both parties must use the same settings, so a configuration also builds the
+/// verifiers of the provers built from it.
+#[derive(Clone)]
+pub struct DmcConfig<FE: FiniteField> {
+    lpn: Option<(LpnParams, LpnParams)>,
+    no_batching: bool,
+    zero_check_schedule: Arc<dyn ZeroCheckSchedule>,
+    mult_check_interval: Option<usize>,
+    strict_mult_check: bool,
+    input_budget: Option<usize>,
+    field: PhantomData<FE>,
+}
+
+impl<FE: FiniteField> DmcConfig<FE> {
+    /// Create a configuration from a pair of LPN parameters as defined by svole.
+    pub fn new(lpn_setup: LpnParams, lpn_extend: LpnParams, no_batching: bool) -> Self {
+        Self {
+            lpn: Some((lpn_setup, lpn_extend)),
+            no_batching,
+            zero_check_schedule: Arc::new(Batched {
+                capacity: QUEUE_CAPACITY,
+            }),
+            mult_check_interval: None,
+            strict_mult_check: false,
+            input_budget: None,
+            field: PhantomData,
+        }
+    }
+
+    /// Check the values asserted to be zero each time `capacity` of them are
+    /// queued, instead of the default of 3,000,000.
+    ///
+    /// See [`DietMacAndCheeseProver::set_queue_capacity`]. The capacity does not
+    /// change the messages of a check, only when the checks happen, so the
+    /// provers and verifiers of a proof must be built with the same capacity.
+    pub fn with_queue_capacity(mut self, capacity: NonZeroUsize) -> Self {
+        self.zero_check_schedule = Arc::new(Batched {
+            capacity: capacity.get(),
+        });
+        self
+    }
+
+    /// Initialize a new prover with this configuration.
+    ///
+    /// This fails for the configuration of a prover initialized with `init_with_fcom`,
+    /// whose LPN parameters are unknown.
+    pub fn build<C: AbstractChannel, RNG: CryptoRng + Rng>(
+        &self,
+        channel: &mut C,
+        rng: RNG,
+    ) -> Result<DietMacAndCheeseProver<FE, C, RNG>> {
+        let (lpn_setup, lpn_extend) = self
+            .lpn
+            .ok_or_else(|| eyre!("The configuration does not have LPN parameters"))?;
+        let mut prover =
+            DietMacAndCheeseProver::init(channel, rng, lpn_setup, lpn_extend, self.no_batching)?;
+        prover.zero_check_schedule = self.zero_check_schedule.clone();
+        prover.set_mult_check_interval(self.mult_check_interval);
+        prover.set_strict_mult_check(self.strict_mult_check);
+        prover.set_input_budget(self.input_budget);
+        Ok(prover)
+    }
+
+    /// Initialize a new verifier with this configuration.
+    ///
+    /// This fails for the configuration of a prover initialized with `init_with_fcom`,
+    /// whose LPN parameters are unknown.
+    pub fn build_verifier<C: AbstractChannel, RNG: CryptoRng + Rng>(
+        &self,
+        channel: &mut C,
+        rng: RNG,
+    ) -> Result<DietMacAndCheeseVerifier<FE, C, RNG>> {
+        let (lpn_setup, lpn_extend) = self
+            .lpn
+            .ok_or_else(|| eyre!("The configuration does not have LPN parameters"))?;
+        let mut verifier =
+            DietMacAndCheeseVerifier::init(channel, rng, lpn_setup, lpn_extend, self.no_batching)?;
+        verifier.zero_check_schedule = self.zero_check_schedule.clone();
+        verifier.set_mult_check_interval(self.mult_check_interval);
+        verifier.set_strict_mult_check(self.strict_mult_check);
+        verifier.set_input_budget(self.input_budget);
+        Ok(verifier)
+    }
+}
+
 /// Prover for Diet Mac'n'Cheese.
 pub struct DietMacAndCheeseProver<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> {
     is_ok: bool,
+    finalized: bool,
     prover: RcRefCell<FComProver<FE>>,
     pub channel: C,
     pub rng: RNG,
     check_zero_list: Vec<MacProver<FE>>,
+    zero_check_schedule: Arc<dyn ZeroCheckSchedule>,
     monitor: Monitor,
     state_mult_check: StateMultCheckProver<FE>,
     no_batching: bool,
+    mult_check_interval: Option<usize>,
+    max_witness: FieldClear<FE>,
+    track_max_witness: bool,
+    lpn: Option<(LpnParams, LpnParams)>,
+    flush_hook: Option<FlushHook>,
+    strict_mult_check: bool,
+    drop_error: Option<DropError>,
+    input_budget: Option<usize>,
+    #[cfg(feature = "debug-graph")]
+    graph: GateGraph,
 }
 
 impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheeseProver<FE, C, RNG> {
@@ -158,13 +727,27 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
         let state_mult_check = StateMultCheckProver::init(channel)?;
         Ok(Self {
             is_ok: true,
+            finalized: false,
             prover: RcRefCell::new(FComProver::init(channel, &mut rng, lpn_setup, lpn_extend)?),
             channel: channel.clone(),
             rng,
             check_zero_list: Vec::new(),
+            zero_check_schedule: Arc::new(Batched {
+                capacity: QUEUE_CAPACITY,
+            }),
             monitor: Monitor::default(),
             state_mult_check,
             no_batching,
+            mult_check_interval: None,
+            max_witness: FieldClear::<FE>::ZERO,
+            track_max_witness: false,
+            lpn: Some((lpn_setup, lpn_extend)),
+            flush_hook: None,
+            strict_mult_check: false,
+            drop_error: None,
+            input_budget: None,
+            #[cfg(feature = "debug-graph")]
+            graph: GateGraph::default(),
         })
     }
 
@@ -178,13 +761,27 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
         let state_mult_check = StateMultCheckProver::init(channel)?;
         Ok(Self {
             is_ok: true,
+            finalized: false,
             prover: fcom.clone(),
             channel: channel.clone(),
             rng,
             check_zero_list: Vec::new(),
+            zero_check_schedule: Arc::new(Batched {
+                capacity: QUEUE_CAPACITY,
+            }),
             monitor: Monitor::default(),
             state_mult_check,
             no_batching,
+            mult_check_interval: None,
+            max_witness: FieldClear::<FE>::ZERO,
+            track_max_witness: false,
+            lpn: None,
+            flush_hook: None,
+            strict_mult_check: false,
+            drop_error: None,
+            input_budget: None,
+            #[cfg(feature = "debug-graph")]
+            graph: GateGraph::default(),
         })
     }
 
@@ -193,12 +790,222 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
         &self.prover
     }
 
+    /// Set the schedule of the checks of the values asserted to be zero.
+    ///
+    /// Both parties must use the same schedule. Setting `no_batching` at
+    /// initialization overrides the schedule with an eager check.
+    pub fn set_zero_check_schedule(&mut self, schedule: Box<dyn ZeroCheckSchedule>) {
+        self.zero_check_schedule = schedule.into();
+    }
+
+    /// Check the values asserted to be zero each time `capacity` of them are
+    /// queued, instead of the default of 3,000,000.
+    ///
+    /// This is the schedule `Batched { capacity }`. A smaller capacity bounds the
+    /// memory of the queue, and a larger one saves rounds of interaction. Each
+    /// check is a round of interaction, so both parties must use the same capacity.
+    /// The capacity is recorded in the configuration returned by [`Self::config`].
+    pub fn set_queue_capacity(&mut self, capacity: NonZeroUsize) {
+        self.zero_check_schedule = Arc::new(Batched {
+            capacity: capacity.get(),
+        });
+    }
+
+    /// Set a hook called after each check of the queued values asserted to
+    /// be zero and each multiplication check, with the size and duration of
+    /// the check.
+    ///
+    /// Unlike the monitor, which aggregates counts over the whole proof, the
+    /// hook reports every flush as it happens.
+    pub fn set_flush_hook(&mut self, f: FlushHook) {
+        self.flush_hook = Some(f);
+    }
+
+    /// Finalize the party when it is dropped without having been finalized,
+    /// instead of only logging a warning.
+    ///
+    /// Any error of that finalization is logged and recorded in the returned
+    /// [`DropError`]. The party is not finalized if it failed earlier or if it
+    /// is dropped while panicking. Both parties must opt in for a proof missing
+    /// its `finalize` calls to complete.
+    pub fn finalize_on_drop(&mut self) -> DropError {
+        let drop_error = DropError::default();
+        self.drop_error = Some(drop_error.clone());
+        drop_error
+    }
+
+    /// Set a callback receiving the statistics of the party every
+    /// `set_progress_interval` gates, in place of the periodic log line.
+    pub fn set_progress_callback(&mut self, f: ProgressCallback) {
+        self.monitor.progress = Some(f);
+    }
+
+    /// Set the number of gates between two progress reports, which defaults to
+    /// 5 million.
+    ///
+    /// # Panics
+    /// This function will panic if `interval` is zero.
+    pub fn set_progress_interval(&mut self, interval: usize) {
+        assert_ne!(interval, 0, "progress interval must be nonzero");
+        self.monitor.tick_interval = interval;
+        self.monitor.tick = 0;
+    }
+
+    fn report_flush(&mut self, event: FlushEvent) {
+        self.monitor.record_flush(&event);
+        if let Some(hook) = self.flush_hook.as_mut() {
+            hook(event);
+        }
+    }
+
+    /// Set the maximum number of private inputs of the party, or `None` for no
+    /// limit, which is the default.
+    ///
+    /// Each private input consumes a sVOLE correlation, so this protects a shared
+    /// party from a circuit requesting an absurd number of inputs. An input which
+    /// would exceed the budget fails with [`DmcError::BudgetExceeded`], before
+    /// any communication. The inputs already made count towards the budget, and
+    /// both parties must use the same budget.
+    pub fn set_input_budget(&mut self, budget: Option<usize>) {
+        self.input_budget = budget;
+    }
+
+    fn check_input_budget(&self, n: usize) -> Result<()> {
+        if let Some(budget) = self.input_budget {
+            if self.monitor.monitor_witness + n > budget {
+                return Err(DmcError::BudgetExceeded.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Make `finalize` fail with [`DmcError::UncheckedMultiplications`] when
+    /// [`Self::mult_check_discrepancy`] is nonzero, instead of only logging a
+    /// warning.
+    pub fn set_strict_mult_check(&mut self, strict: bool) {
+        self.strict_mult_check = strict;
+    }
+
+    /// Return the gate counts, the flush counts and the time spent in flushes
+    /// as metrics in the Prometheus text format, labeled with `party="prover"`.
+    ///
+    /// The counts accumulate over all the proofs of the party, as for
+    /// [`Self::reset_proof_state`].
+    pub fn stats_prometheus(&self) -> String {
+        self.monitor.prometheus("prover")
+    }
+
+    /// Return the gate counts and checks so far, which can be read at any point
+    /// of the evaluation.
+    pub fn stats(&self) -> CircuitStats {
+        self.monitor.stats()
+    }
+
+    /// Return the bytes communicated by the party, including the sVOLE setup,
+    /// if its channel counts them, e.g., a [`TrackChannel`].
+    ///
+    /// [`TrackChannel`]: scuttlebutt::TrackChannel
+    pub fn comm_stats(&self) -> Option<CommStats> {
+        CommStats::from_channel(&self.channel)
+    }
+
+    /// Return the number of multiplications minus the number of multiplications
+    /// covered by the multiplication checks so far.
+    ///
+    /// After `finalize`, a nonzero value denotes multiplications which were not
+    /// checked, and thus a soundness bug.
+    pub fn mult_check_discrepancy(&self) -> isize {
+        self.monitor.mult_check_discrepancy()
+    }
+
+    fn check_mult_check_discrepancy(&mut self) -> Result<()> {
+        let discrepancy = self.mult_check_discrepancy();
+        if self.strict_mult_check && discrepancy != 0 {
+            self.is_ok = false;
+            return Err(DmcError::UncheckedMultiplications(discrepancy).into());
+        }
+        Ok(())
+    }
+
+    /// Set the number of sVOLE extensions performed each time the voles run
+    /// out, for a predictable pattern of extension rounds.
+    ///
+    /// Each extension produces a number of voles fixed by the LPN parameters.
+    /// Larger chunks mean fewer but longer pauses to extend the voles. Both
+    /// parties must use the same value, which also applies to the parties
+    /// sharing the same functionality through `init_with_fcom`.
+    pub fn set_vole_chunk(&mut self, n: NonZeroUsize) {
+        self.prover.get_refmut().set_vole_chunk(n);
+    }
+
+    /// Set the number of multiplications after which the multiplication check is
+    /// performed, instead of deferring it to `finalize`.
+    ///
+    /// The multiplication triples are folded into a running aggregate as they are
+    /// produced, and each check verifies and resets the aggregate. After each
+    /// periodic check, the verifier sends a fresh challenge for folding the next
+    /// triples, so that each check is the check of `finalize` over fewer triples,
+    /// at the cost of the extra communication of each check. Both parties must use
+    /// the same interval.
+    pub fn set_mult_check_interval(&mut self, interval: Option<usize>) {
+        assert_ne!(
+            interval,
+            Some(0),
+            "multiplication check interval must be nonzero"
+        );
+        self.mult_check_interval = interval;
+    }
+
+    /// Initialize a prover for a sub-proof sharing the functionality of this prover.
+    ///
+    /// The wires of both provers are authenticated with the same key, so a wire
+    /// of one prover can be used as is in the gates of the other, without being
+    /// input again. The `finalize` of each sub-proof checks the gates it evaluated,
+    /// which binds a shared wire to the same value in every sub-proof. The
+    /// verifier must call [`DietMacAndCheeseVerifier::sub_proof`] at the same point.
+    pub fn sub_proof(&mut self, rng: RNG) -> Result<Self> {
+        let mut prover =
+            Self::init_with_fcom(&mut self.channel, rng, &self.prover, self.no_batching)?;
+        prover.set_mult_check_interval(self.mult_check_interval);
+        Ok(prover)
+    }
+
+    /// Return the configuration of the prover, to build new provers with the same settings.
+    pub fn config(&self) -> DmcConfig<FE> {
+        DmcConfig {
+            lpn: self.lpn,
+            no_batching: self.no_batching,
+            zero_check_schedule: self.zero_check_schedule.clone(),
+            mult_check_interval: self.mult_check_interval,
+            strict_mult_check: self.strict_mult_check,
+            input_budget: self.input_budget,
+            field: PhantomData,
+        }
+    }
+
+    fn push_mult_check(&mut self) -> Result<()> {
+        if let Some(interval) = self.mult_check_interval {
+            if self.state_mult_check.count() >= interval {
+                let r = self
+                    .do_mult_check()
+                    .and_then(|_| self.state_mult_check.refresh_challenge(&mut self.channel));
+                if r.is_err() {
+                    warn!("mult_check fails");
+                    self.is_ok = false;
+                }
+                r?;
+            }
+        }
+        Ok(())
+    }
+
     // this function should be called before every function exposed publicly by the API.
     fn check_is_ok(&self) -> Result<()> {
         if !self.is_ok {
-            return Err(eyre!(
-                "An error occurred earlier. This functionality should not be used further"
-            ));
+            return Err(DmcError::UsedAfterError.into());
+        }
+        if self.finalized {
+            return Err(DmcError::AlreadyFinalized.into());
         }
         Ok(())
     }
@@ -216,6 +1023,8 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
 
     fn do_mult_check(&mut self) -> Result<usize> {
         debug!("do mult_check");
+        let start = Instant::now();
+        let comm_start = self.channel.comm_bytes();
         self.channel.flush()?;
         let cnt = self.prover.get_refmut().quicksilver_finalize(
             &mut self.channel,
@@ -223,30 +1032,67 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
             &mut self.state_mult_check,
         )?;
         self.monitor.incr_zk_mult_check(cnt);
+        self.report_flush(FlushEvent::new(
+            FlushKind::MultCheck,
+            cnt,
+            start,
+            comm_start,
+            self.channel.comm_bytes(),
+        ));
         Ok(cnt)
     }
 
-    fn do_check_zero(&mut self) -> Result<()> {
+    fn do_check_zero(&mut self, cancelled: &AtomicBool) -> Result<()> {
         // debug!("do check_zero");
+        let start = Instant::now();
+        let comm_start = self.channel.comm_bytes();
         self.channel.flush()?;
-        let r = self
-            .prover
-            .get_refmut()
-            .check_zero(&mut self.channel, &self.check_zero_list);
+        let chunk_size = self
+            .zero_check_schedule
+            .chunk_size(self.check_zero_list.len())
+            .max(1);
+        let mut r = Ok(());
+        let queue = std::mem::take(&mut self.check_zero_list);
+        // An empty queue is still checked, as a single empty chunk.
+        let empty = queue.is_empty().then_some(&queue[..]);
+        for chunk in queue.chunks(chunk_size).chain(empty) {
+            // Each chunk is a round of interaction, so a cancellation takes
+            // effect before the next chunk.
+            check_cancelled(&mut self.is_ok, cancelled)?;
+            r = r.and(
+                self.prover
+                    .get_refmut()
+                    .check_zero(&mut self.channel, chunk),
+            );
+        }
         if r.is_err() {
             warn!("check_zero fails");
             self.is_ok = false;
         }
-        self.monitor.incr_zk_check_zero(self.check_zero_list.len());
+        let batch_len = queue.len();
+        self.monitor.incr_zk_check_zero(batch_len);
+        // The queue is put back empty, to keep its allocation.
+        self.check_zero_list = queue;
         self.check_zero_list.clear();
+        self.report_flush(FlushEvent::new(
+            FlushKind::CheckZero,
+            batch_len,
+            start,
+            comm_start,
+            self.channel.comm_bytes(),
+        ));
         r
     }
 
     fn push_check_zero_list(&mut self, e: MacProver<FE>) -> Result<()> {
         self.check_zero_list.push(e);
 
-        if self.check_zero_list.len() == QUEUE_CAPACITY || self.no_batching {
-            self.do_check_zero()?;
+        if self.no_batching
+            || self
+                .zero_check_schedule
+                .should_check(self.check_zero_list.len())
+        {
+            self.do_check_zero(&NOT_CANCELLED)?;
         }
         Ok(())
     }
@@ -255,14 +1101,72 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
     pub(crate) fn assert_zero(&mut self, value: &MacProver<FE>) -> Result<()> {
         self.check_is_ok()?;
         self.monitor.incr_monitor_check_zero();
+        self.record_gate("assert_zero", &[value], None);
         self.push_check_zero_list(*value)
     }
 
+    /// Assert that all of `values` are zero.
+    ///
+    /// This is equivalent to calling `assert_zero` on each value, but the values
+    /// are queued a slice at a time, up to the next check of the queue, so that
+    /// the queue stays within its capacity. Values are still checked one by one
+    /// without batching or when the zero-check schedule checks every value.
+    pub(crate) fn assert_zero_many(&mut self, values: &[MacProver<FE>]) -> Result<()> {
+        self.check_is_ok()?;
+        if self.no_batching || self.zero_check_schedule.should_check(1) {
+            return values.iter().try_for_each(|value| self.assert_zero(value));
+        }
+        self.monitor.incr_monitor_check_zero_many(values.len());
+        for value in values {
+            self.record_gate("assert_zero", &[value], None);
+        }
+        let mut values = values;
+        while !values.is_empty() {
+            let n = values_until_check(
+                self.zero_check_schedule.as_ref(),
+                self.check_zero_list.len(),
+                values.len(),
+            );
+            let (chunk, rest) = values.split_at(n);
+            self.check_zero_list.extend_from_slice(chunk);
+            values = rest;
+            if self
+                .zero_check_schedule
+                .should_check(self.check_zero_list.len())
+            {
+                self.do_check_zero(&NOT_CANCELLED)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assert two values are equal.
+    ///
+    /// Their difference is checked to be zero, and counted as a single assertion.
+    pub(crate) fn assert_eq(&mut self, a: &MacProver<FE>, b: &MacProver<FE>) -> Result<()> {
+        self.check_is_ok()?;
+        self.monitor.incr_monitor_check_zero();
+        self.record_gate("assert_eq", &[a, b], None);
+        let diff = self.prover.get_refmut().sub(*a, *b);
+        self.push_check_zero_list(diff)
+    }
+
     /// Add two values.
     pub(crate) fn add(&mut self, a: &MacProver<FE>, b: &MacProver<FE>) -> Result<MacProver<FE>> {
         self.check_is_ok()?;
         self.monitor.incr_monitor_add();
-        Ok(self.prover.get_refmut().add(*a, *b))
+        let out = self.prover.get_refmut().add(*a, *b);
+        self.record_gate("add", &[a, b], Some(&out));
+        Ok(out)
+    }
+
+    /// Subtract `b` from `a`.
+    pub(crate) fn sub(&mut self, a: &MacProver<FE>, b: &MacProver<FE>) -> Result<MacProver<FE>> {
+        self.check_is_ok()?;
+        self.monitor.incr_monitor_sub();
+        let out = self.prover.get_refmut().sub(*a, *b);
+        self.record_gate("sub", &[a, b], Some(&out));
+        Ok(out)
     }
 
     /// Multiply two values.
@@ -273,10 +1177,75 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
         let b_clr = b.value();
         let product = a_clr * b_clr;
 
+        self.track_witness(product);
         let out = self.input(product)?;
         self.prover
             .get_refmut()
             .quicksilver_push(&mut self.state_mult_check, &(*a, *b, out))?;
+        self.push_mult_check()?;
+        self.record_gate("mul", &[a, b], Some(&out));
+        Ok(out)
+    }
+
+    /// Return the dot product of `a` and `b`.
+    ///
+    /// The result is input as a fresh witness and checked as a single entry of
+    /// the multiplication check, so this costs one multiplication whatever the
+    /// length of the vectors.
+    pub(crate) fn dot_product(
+        &mut self,
+        a: &[MacProver<FE>],
+        b: &[MacProver<FE>],
+    ) -> Result<MacProver<FE>> {
+        self.check_is_ok()?;
+        if a.len() != b.len() {
+            return Err(eyre!(
+                "dot_product of vectors of lengths {} and {}",
+                a.len(),
+                b.len()
+            ));
+        }
+        self.monitor.incr_monitor_mul();
+        let product = a.iter().zip(b).fold(FieldClear::<FE>::ZERO, |sum, (a, b)| {
+            sum + a.value() * b.value()
+        });
+
+        self.track_witness(product);
+        let out = self.input(product)?;
+        self.prover.get_refmut().quicksilver_push_dot_product(
+            &mut self.state_mult_check,
+            a,
+            b,
+            &out,
+        )?;
+        self.push_mult_check()?;
+        let inputs: Vec<_> = a.iter().chain(b).collect();
+        self.record_gate("dot_product", &inputs, Some(&out));
+        Ok(out)
+    }
+
+    /// Divide `a` by `b`.
+    ///
+    /// The quotient `q = a / b` is input as a fresh witness and `q * b == a` is
+    /// added to the multiplication check, so this costs one multiplication.
+    /// This fails if `b` is zero, as no quotient would pass the check.
+    pub(crate) fn div(&mut self, a: &MacProver<FE>, b: &MacProver<FE>) -> Result<MacProver<FE>> {
+        self.check_is_ok()?;
+        let b_clr = b.value();
+        if b_clr == FieldClear::<FE>::ZERO {
+            self.is_ok = false;
+            return Err(DmcError::DivisionByZero.into());
+        }
+        self.monitor.incr_monitor_mul();
+        let quotient = a.value() * b_clr.inverse();
+
+        self.track_witness(quotient);
+        let out = self.input(quotient)?;
+        self.prover
+            .get_refmut()
+            .quicksilver_push(&mut self.state_mult_check, &(out, *b, *a))?;
+        self.push_mult_check()?;
+        self.record_gate("div", &[a, b], Some(&out));
         Ok(out)
     }
 
@@ -284,7 +1253,12 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
     pub(crate) fn addc(&mut self, a: &MacProver<FE>, b: FE::PrimeField) -> Result<MacProver<FE>> {
         self.check_is_ok()?;
         self.monitor.incr_monitor_addc();
-        Ok(self.prover.get_refmut().affine_add_cst(b, *a))
+        if let Some(value) = public_value(a) {
+            return Ok(self.fold_public(value + b));
+        }
+        let out = self.prover.get_refmut().affine_add_cst(b, *a);
+        self.record_gate("addc", &[a], Some(&out));
+        Ok(out)
     }
 
     /// Multiply a value and a constant.
@@ -295,56 +1269,434 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> DietMacAndCheese
     ) -> Result<MacProver<FE>> {
         self.check_is_ok()?;
         self.monitor.incr_monitor_mulc();
-        Ok(self.prover.get_refmut().affine_mult_cst(constant, *value))
+        if let Some(v) = public_value(value) {
+            return Ok(self.fold_public(v * constant));
+        }
+        let out = self.prover.get_refmut().affine_mult_cst(constant, *value);
+        self.record_gate("mulc", &[value], Some(&out));
+        Ok(out)
+    }
+
+    // Return the public value resulting from an affine operation on public values.
+    fn fold_public(&mut self, value: FieldClear<FE>) -> MacProver<FE> {
+        self.monitor.incr_monitor_folded();
+        let out = MacProver::new(value, FE::ZERO);
+        self.record_gate("public", &[], Some(&out));
+        out
     }
 
     /// Input a public value.
     pub(crate) fn input_public(&mut self, value: FieldClear<FE>) -> MacProver<FE> {
         self.monitor.incr_monitor_instance();
-        MacProver::new(value, FE::ZERO)
+        let out = MacProver::new(value, FE::ZERO);
+        self.record_gate("public", &[], Some(&out));
+        out
     }
 
-    /// Input a private value.
-    pub(crate) fn input_private(&mut self, value: FieldClear<FE>) -> Result<MacProver<FE>> {
-        self.check_is_ok()?;
-        self.monitor.incr_monitor_witness();
-        self.input(value)
+    /// Return a random challenge chosen by the verifier.
+    ///
+    /// The channel is flushed before the challenge is received, so the prover
+    /// is committed to every value input so far. The verifier obtains the same
+    /// value from [`DietMacAndCheeseVerifier::challenge_value`], provided both
+    /// parties request their challenges in the same order.
+    pub fn challenge_value(&mut self) -> Result<FieldClear<FE>> {
+        Ok(self.challenge_values(1)?[0])
     }
 
-    /// `finalize` execute its queued multiplication and zero checks.
-    /// It can be called at any time and it is also called when the functionality is dropped.
-    pub fn finalize(&mut self) -> Result<()> {
-        debug!("finalize");
+    /// Return `n` random challenges chosen by the verifier, in a single
+    /// interaction.
+    pub fn challenge_values(&mut self, n: usize) -> Result<Vec<FieldClear<FE>>> {
         self.check_is_ok()?;
         self.channel.flush()?;
-        let zero_len = self.check_zero_list.len();
-        self.do_check_zero()?;
+        (0..n)
+            .map(|_| Ok(self.channel.read_serializable()?))
+            .collect()
+    }
 
-        let mult_len = self.do_mult_check()?;
-        debug!("ERASE ME:  mult_len {:?}", mult_len);
-        debug!(
-            "finalize: mult_check:{:?}, check_zero:{:?} ",
-            mult_len, zero_len
-        );
-        self.log_final_monitor();
-        Ok(())
+    /// Return a random challenge chosen by the verifier, as a public wire.
+    ///
+    /// See [`Self::challenge_value`] for the value of the wire.
+    pub fn challenge(&mut self) -> Result<MacProver<FE>> {
+        let value = self.challenge_value()?;
+        Ok(MacProver::new(value, FE::ZERO))
     }
 
-    pub(crate) fn reset(&mut self) {
-        self.prover.get_refmut().reset(&mut self.state_mult_check);
-        self.is_ok = true;
+    /// Return `n` random challenges chosen by the verifier, as public wires.
+    pub fn challenges(&mut self, n: usize) -> Result<Vec<MacProver<FE>>> {
+        Ok(self
+            .challenge_values(n)?
+            .into_iter()
+            .map(|value| MacProver::new(value, FE::ZERO))
+            .collect())
     }
 
-    fn log_final_monitor(&self) {
-        info!("field largest value: {:?}", (FE::ZERO - FE::ONE).to_bytes());
-        self.monitor.log_final_monitor();
+    /// Input a private value.
+    pub(crate) fn input_private(&mut self, value: FieldClear<FE>) -> Result<MacProver<FE>> {
+        self.check_is_ok()?;
+        self.check_input_budget(1)?;
+        self.monitor.incr_monitor_witness();
+        self.track_witness(value);
+        let out = self.input(value)?;
+        self.record_gate("private", &[], Some(&out));
+        Ok(out)
     }
-}
 
-impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> Drop
+    /// Input a batch of private values.
+    ///
+    /// This is equivalent to calling `input_private` on each value, with the
+    /// verifier receiving the batch with [`DietMacAndCheeseVerifier::input_private_batch`].
+    pub fn input_private_batch(&mut self, values: &[FieldClear<FE>]) -> Result<Vec<MacProver<FE>>> {
+        self.check_is_ok()?;
+        self.check_input_budget(values.len())?;
+        let mut tags = Vec::with_capacity(values.len());
+        let r = self.prover.get_refmut().input_low_level(
+            &mut self.channel,
+            &mut self.rng,
+            values,
+            &mut tags,
+        );
+        if r.is_err() {
+            self.is_ok = false;
+        }
+        r?;
+        let mut out = Vec::with_capacity(values.len());
+        for (value, tag) in values.iter().zip(tags) {
+            self.monitor.incr_monitor_witness();
+            self.track_witness(*value);
+            let wire = MacProver::new(*value, tag);
+            self.record_gate("private", &[], Some(&wire));
+            out.push(wire);
+        }
+        Ok(out)
+    }
+
+    /// Input `n` private values pulled from `src`, passing each of their wires
+    /// to `f` as soon as it is input.
+    ///
+    /// The values are input in blocks, so that neither the values nor their
+    /// wires are ever held in memory all at once. The gates evaluated by `f`
+    /// keep checking the queued values asserted to be zero whenever the queue
+    /// reaches its capacity, which keeps the memory of the proof bounded. The
+    /// verifier must call [`DietMacAndCheeseVerifier::input_private_stream`]
+    /// with the same `n` and mirror the gates of `f`.
+    ///
+    /// This fails if `src` runs out before `n` values, after inputting the
+    /// values of the previous blocks. The verifier then still expects the
+    /// remaining values, so the prover cannot be used afterwards.
+    pub fn input_private_stream(
+        &mut self,
+        src: &mut impl WitnessSource<FieldClear<FE>>,
+        n: usize,
+        mut f: impl FnMut(&mut Self, MacProver<FE>) -> Result<()>,
+    ) -> Result<()> {
+        self.check_is_ok()?;
+        self.check_input_budget(n)?;
+        let mut values = Vec::with_capacity(STREAM_BLOCK.min(n));
+        let mut done = 0;
+        while done < n {
+            values.clear();
+            for _ in 0..STREAM_BLOCK.min(n - done) {
+                match src.next_value() {
+                    Some(value) => values.push(value),
+                    None => {
+                        self.is_ok = false;
+                        return Err(eyre!(
+                            "The witness source ended after {} of {} values",
+                            done + values.len(),
+                            n
+                        ));
+                    }
+                }
+            }
+            for wire in self.input_private_batch(&values)? {
+                f(self, wire)?;
+            }
+            done += values.len();
+        }
+        Ok(())
+    }
+
+    /// Input `count` private values read from the file at `path`, each encoded
+    /// in little-endian over `elem_bytes` bytes as in [`from_bytes_le`].
+    ///
+    /// The file is memory-mapped and decoded in blocks, so that it is never read
+    /// into memory as a whole. This fails before inputting any value if the file
+    /// does not have exactly `count * elem_bytes` bytes. The verifier receives the
+    /// values with [`DietMacAndCheeseVerifier::input_private_batch`].
+    #[cfg(feature = "mmap")]
+    pub fn input_private_mmap(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        count: usize,
+        elem_bytes: usize,
+    ) -> Result<Vec<MacProver<FE>>> {
+        use eyre::Context;
+        const BLOCK: usize = 1 << 16;
+        self.check_is_ok()?;
+        let file = std::fs::File::open(path.as_ref())
+            .with_context(|| format!("Cannot open {}", path.as_ref().display()))?;
+        // SAFETY: the file must not be modified while it is mapped.
+        let mmap = unsafe { memmap2::Mmap::map(&file) }?;
+        if elem_bytes == 0 || count.checked_mul(elem_bytes) != Some(mmap.len()) {
+            return Err(eyre!(
+                "Expected {} elements of {} bytes, but the file has {} bytes",
+                count,
+                elem_bytes,
+                mmap.len()
+            ));
+        }
+        let mut out = Vec::with_capacity(count);
+        let mut values = Vec::with_capacity(BLOCK.min(count));
+        for block in mmap.chunks(BLOCK * elem_bytes) {
+            values.clear();
+            for bytes in block.chunks(elem_bytes) {
+                values.push(from_bytes_le(bytes)?);
+            }
+            out.extend(self.input_private_batch(&values)?);
+        }
+        Ok(out)
+    }
+
+    #[cfg(feature = "debug-graph")]
+    fn record_gate(
+        &mut self,
+        label: &str,
+        inputs: &[&MacProver<FE>],
+        output: Option<&MacProver<FE>>,
+    ) {
+        self.graph.gate(label, inputs, output);
+    }
+
+    #[cfg(not(feature = "debug-graph"))]
+    fn record_gate(
+        &mut self,
+        _label: &str,
+        _inputs: &[&MacProver<FE>],
+        _output: Option<&MacProver<FE>>,
+    ) {
+    }
+
+    /// Return the DAG of the gates evaluated so far in the Graphviz `dot` format.
+    ///
+    /// Each gate is a node, with an edge from the gates computing its inputs.
+    /// This is meant for small circuits only, as the whole graph is kept in
+    /// memory.
+    #[cfg(feature = "debug-graph")]
+    pub fn gate_graph_dot(&self) -> String {
+        self.graph.to_dot()
+    }
+
+    /// Open `value` to the verifier and return it as a public value.
+    ///
+    /// The returned MAC is the one of a public input: using it in further gates
+    /// is as cheap as using a public value. In particular, multiplying by its
+    /// value with `mulc` does not add a multiplication triple to check.
+    pub fn reveal_as_public(&mut self, value: &MacProver<FE>) -> Result<MacProver<FE>> {
+        self.reveal_slice(std::slice::from_ref(value))?;
+        Ok(self.input_public(value.value()))
+    }
+
+    /// Open `value` to the verifier and return it.
+    ///
+    /// The value is sent along with its MAC, and the verifier fails if they
+    /// don't match, see [`DietMacAndCheeseVerifier::reveal`].
+    pub fn reveal(&mut self, value: &MacProver<FE>) -> Result<FieldClear<FE>> {
+        Ok(self.reveal_slice(std::slice::from_ref(value))?[0])
+    }
+
+    /// Open `values` to the verifier and return them in order.
+    ///
+    /// The values are sent together and checked with a single random linear
+    /// combination of their MACs.
+    pub fn reveal_slice(&mut self, values: &[MacProver<FE>]) -> Result<Vec<FieldClear<FE>>> {
+        self.check_is_ok()?;
+        let r = self.prover.get_refmut().open(&mut self.channel, values);
+        if r.is_err() {
+            self.is_ok = false;
+        }
+        r?;
+        Ok(values.iter().map(|v| v.value()).collect())
+    }
+
+    fn track_witness(&mut self, value: FieldClear<FE>) {
+        if self.track_max_witness && int_less_than(&self.max_witness, &value) {
+            self.max_witness = value;
+        }
+    }
+
+    /// Track the largest value, as an integer, input as a witness or produced
+    /// by a multiplication, which is disabled by default.
+    ///
+    /// Tracking compares each of these values as integers through their byte
+    /// representation, which is too costly to do on every gate by default. When
+    /// enabled, `finalize` warns if the largest value is close to the modulus.
+    pub fn set_track_max_witness(&mut self, track: bool) {
+        self.track_max_witness = track;
+    }
+
+    /// Return the largest value, as an integer, input as a witness or produced
+    /// by a multiplication since tracking was enabled with
+    /// [`Self::set_track_max_witness`].
+    pub fn max_witness_value(&self) -> FieldClear<FE> {
+        self.max_witness
+    }
+
+    /// Return whether [`Self::max_witness_value`] is at least half the modulus
+    /// of the prime field.
+    ///
+    /// Values this large often denote a computation which wrapped around the
+    /// modulus, or a negative value. Always `false` for binary fields.
+    pub fn max_witness_near_modulus(&self) -> bool {
+        let two = FieldClear::<FE>::ONE + FieldClear::<FE>::ONE;
+        if two == FieldClear::<FE>::ZERO {
+            return false;
+        }
+        // (p - 1) / 2
+        let half = -two.inverse();
+        !int_less_than(&self.max_witness, &half)
+    }
+
+    /// Return an estimate of the bits of soundness of the multiplication checks
+    /// of the multiplications evaluated so far.
+    ///
+    /// This decreases with the number of multiplications, and is about the bit
+    /// length of `FE` minus the logarithm of that number.
+    pub fn mult_check_soundness_bits(&self) -> f64 {
+        mult_check_soundness_bits::<FE>(self.monitor.monitor_mul)
+    }
+
+    /// Return an estimate of the number of round trips between the parties
+    /// required to check the gates evaluated so far, including `finalize`.
+    ///
+    /// Each batch of values asserted to be zero and each multiplication check
+    /// takes one round trip, so the estimate grows with `no_batching`, the zero
+    /// check schedule and the multiplication check interval. The round trips of
+    /// challenges and of the sVOLE extensions are not counted.
+    pub fn estimated_round_trips(&self) -> usize {
+        estimated_round_trips(
+            self.monitor.monitor_check_zero,
+            self.monitor.monitor_mul,
+            self.no_batching,
+            self.zero_check_schedule.as_ref(),
+            self.mult_check_interval,
+        )
+    }
+
+    /// Return the number of bytes held by the state of the multiplication check.
+    ///
+    /// The multiplication triples are folded into a running aggregate as they
+    /// are produced, so this does not grow with the number of multiplications.
+    /// Use [`Self::set_mult_check_interval`] to bound the number of triples
+    /// covered by each check instead.
+    pub fn mult_state_bytes(&self) -> usize {
+        std::mem::size_of_val(&self.state_mult_check)
+    }
+
+    /// `finalize` execute its queued multiplication and zero checks.
+    /// It can be called at any time and it is also called when the functionality is dropped.
+    /// After `finalize`, gates fail with [`DmcError::AlreadyFinalized`] until
+    /// `reset` is called, and calling `finalize` again does nothing.
+    pub fn finalize(&mut self) -> Result<()> {
+        self.finalize_cancellable(&NOT_CANCELLED)
+    }
+
+    /// `finalize`, checking `cancelled` before each of its rounds of checks, i.e.,
+    /// before each chunk of the zero checks and before the multiplication check,
+    /// and failing with [`DmcError::Cancelled`] once it is set.
+    ///
+    /// A cancelled proof is not complete, and the party can only be used again
+    /// after `reset`. Each check is a round of communication, so both parties
+    /// must be cancelled at the same point, or the other party closes its
+    /// channel, for the other party not to block.
+    pub fn finalize_cancellable(&mut self, cancelled: &AtomicBool) -> Result<()> {
+        debug!("finalize");
+        if self.finalized {
+            return Ok(());
+        }
+        self.check_is_ok()?;
+        self.channel.flush()?;
+        let zero_len = self.check_zero_list.len();
+        self.do_check_zero(cancelled)?;
+
+        check_cancelled(&mut self.is_ok, cancelled)?;
+        let mult_len = self.do_mult_check()?;
+        debug!("ERASE ME:  mult_len {:?}", mult_len);
+        debug!(
+            "finalize: mult_check:{:?}, check_zero:{:?} ",
+            mult_len, zero_len
+        );
+        self.log_final_monitor();
+        self.check_mult_check_discrepancy()?;
+        self.finalized = true;
+        Ok(())
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.prover.get_refmut().reset(&mut self.state_mult_check);
+        self.is_ok = true;
+        self.finalized = false;
+    }
+
+    /// Reset the state of the current proof, to start a new proof with the same
+    /// parties.
+    ///
+    /// This discards the values queued for a zero check and the multiplication
+    /// triples accumulated since the last check, without checking them, and
+    /// clears any earlier error. The monitor is kept, so that its statistics
+    /// accumulate across all the proofs. Both parties must reset at the same point.
+    pub fn reset_proof_state(&mut self) {
+        self.reset();
+        self.check_zero_list.clear();
+    }
+
+    /// Reset the party to prove a new, independent statement over the same
+    /// connection, without running the sVOLE setup again.
+    ///
+    /// This resets the proof state as [`Self::reset_proof_state`], along with
+    /// the statistics of the monitor and the largest witness value. The progress
+    /// callback and interval are kept. The sVOLE correlations left over from the
+    /// previous proof are never reused, so they remain available. Both parties
+    /// must reset their session at the same point, with the verifier calling
+    /// [`DietMacAndCheeseVerifier::reset_session`].
+    pub fn reset_session(&mut self) {
+        self.reset_proof_state();
+        self.monitor.reset();
+        self.max_witness = FieldClear::<FE>::ZERO;
+    }
+
+    fn log_final_monitor(&self) {
+        info!("field largest value: {:?}", (FE::ZERO - FE::ONE).to_bytes());
+        if self.track_max_witness {
+            info!("largest witness value: {:?}", self.max_witness.to_bytes());
+        }
+        if self.max_witness_near_modulus() {
+            warn!(
+                "largest witness value {:?} is close to the field modulus, the circuit may wrap around",
+                self.max_witness.to_bytes()
+            );
+        }
+        self.monitor.log_final_monitor();
+        if let Some(comm) = self.comm_stats() {
+            comm.log();
+        }
+    }
+}
+
+impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> Drop
     for DietMacAndCheeseProver<FE, C, RNG>
 {
     fn drop(&mut self) {
+        if let Some(drop_error) = self.drop_error.take() {
+            if self.is_ok && !self.finalized && !std::thread::panicking() {
+                if let Err(e) = self.finalize() {
+                    warn!("Finalization on drop failed: {}", e);
+                    drop_error.set(e);
+                    // Discard the unchecked state, which must not be dropped.
+                    self.reset();
+                }
+            }
+            return;
+        }
         if self.is_ok && !self.check_zero_list.is_empty() {
             warn!("Dropped in unexpected state: either `finalize()` has not been called or an error occured earlier.");
         }
@@ -357,10 +1709,18 @@ pub struct DietMacAndCheeseVerifier<FE: FiniteField, C: AbstractChannel, RNG: Cr
     pub channel: C,
     pub rng: RNG,
     check_zero_list: Vec<MacVerifier<FE>>,
+    zero_check_schedule: Arc<dyn ZeroCheckSchedule>,
     monitor: Monitor,
     state_mult_check: StateMultCheckVerifier<FE>,
     is_ok: bool,
+    finalized: bool,
     no_batching: bool,
+    mult_check_interval: Option<usize>,
+    streaming_window: Option<usize>,
+    flush_hook: Option<FlushHook>,
+    strict_mult_check: bool,
+    drop_error: Option<DropError>,
+    input_budget: Option<usize>,
 }
 
 impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
@@ -382,10 +1742,20 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
             channel: channel.clone(),
             rng,
             check_zero_list: Vec::new(),
+            zero_check_schedule: Arc::new(Batched {
+                capacity: QUEUE_CAPACITY,
+            }),
             monitor: Monitor::default(),
             state_mult_check,
             is_ok: true,
+            finalized: false,
             no_batching,
+            mult_check_interval: None,
+            streaming_window: None,
+            flush_hook: None,
+            strict_mult_check: false,
+            drop_error: None,
+            input_budget: None,
         })
     }
 
@@ -399,13 +1769,23 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
         let state_mult_check = StateMultCheckVerifier::init(channel, &mut rng)?;
         Ok(Self {
             is_ok: true,
+            finalized: false,
             verifier: fcom.clone(),
             channel: channel.clone(),
             rng,
             check_zero_list: Vec::new(),
+            zero_check_schedule: Arc::new(Batched {
+                capacity: QUEUE_CAPACITY,
+            }),
             monitor: Monitor::default(),
             state_mult_check,
             no_batching,
+            mult_check_interval: None,
+            streaming_window: None,
+            flush_hook: None,
+            strict_mult_check: false,
+            drop_error: None,
+            input_budget: None,
         })
     }
 
@@ -414,12 +1794,266 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
         &self.verifier
     }
 
+    /// Set the schedule of the checks of the values asserted to be zero.
+    ///
+    /// Both parties must use the same schedule. Setting `no_batching` at
+    /// initialization overrides the schedule with an eager check.
+    pub fn set_zero_check_schedule(&mut self, schedule: Box<dyn ZeroCheckSchedule>) {
+        self.zero_check_schedule = schedule.into();
+    }
+
+    /// Check the values asserted to be zero each time `capacity` of them are
+    /// queued, instead of the default of 3,000,000.
+    ///
+    /// This is the schedule `Batched { capacity }`. A smaller capacity bounds the
+    /// memory of the queue, and a larger one saves rounds of interaction. Each
+    /// check is a round of interaction, so both parties must use the same capacity.
+    pub fn set_queue_capacity(&mut self, capacity: NonZeroUsize) {
+        self.zero_check_schedule = Arc::new(Batched {
+            capacity: capacity.get(),
+        });
+    }
+
+    /// Set a hook called after each check of the queued values asserted to
+    /// be zero and each multiplication check, with the size and duration of
+    /// the check.
+    ///
+    /// Unlike the monitor, which aggregates counts over the whole proof, the
+    /// hook reports every flush as it happens.
+    pub fn set_flush_hook(&mut self, f: FlushHook) {
+        self.flush_hook = Some(f);
+    }
+
+    /// Finalize the party when it is dropped without having been finalized.
+    ///
+    /// See [`DietMacAndCheeseProver::finalize_on_drop`].
+    pub fn finalize_on_drop(&mut self) -> DropError {
+        let drop_error = DropError::default();
+        self.drop_error = Some(drop_error.clone());
+        drop_error
+    }
+
+    /// Set a callback receiving the statistics of the party every
+    /// `set_progress_interval` gates, in place of the periodic log line.
+    pub fn set_progress_callback(&mut self, f: ProgressCallback) {
+        self.monitor.progress = Some(f);
+    }
+
+    /// Set the number of gates between two progress reports, which defaults to
+    /// 5 million.
+    ///
+    /// # Panics
+    /// This function will panic if `interval` is zero.
+    pub fn set_progress_interval(&mut self, interval: usize) {
+        assert_ne!(interval, 0, "progress interval must be nonzero");
+        self.monitor.tick_interval = interval;
+        self.monitor.tick = 0;
+    }
+
+    fn report_flush(&mut self, event: FlushEvent) {
+        self.monitor.record_flush(&event);
+        if let Some(hook) = self.flush_hook.as_mut() {
+            hook(event);
+        }
+    }
+
+    /// Set the maximum number of private inputs of the party, or `None` for no
+    /// limit, which is the default.
+    ///
+    /// Each private input consumes a sVOLE correlation, so this protects a shared
+    /// party from a circuit requesting an absurd number of inputs. An input which
+    /// would exceed the budget fails with [`DmcError::BudgetExceeded`], before
+    /// any communication. The inputs already made count towards the budget, and
+    /// both parties must use the same budget.
+    pub fn set_input_budget(&mut self, budget: Option<usize>) {
+        self.input_budget = budget;
+    }
+
+    fn check_input_budget(&self, n: usize) -> Result<()> {
+        if let Some(budget) = self.input_budget {
+            if self.monitor.monitor_witness + n > budget {
+                return Err(DmcError::BudgetExceeded.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Make `finalize` fail with [`DmcError::UncheckedMultiplications`] when
+    /// [`Self::mult_check_discrepancy`] is nonzero, instead of only logging a
+    /// warning.
+    pub fn set_strict_mult_check(&mut self, strict: bool) {
+        self.strict_mult_check = strict;
+    }
+
+    /// Return the gate counts, the flush counts and the time spent in flushes
+    /// as metrics in the Prometheus text format, labeled with `party="verifier"`.
+    ///
+    /// The counts accumulate over all the proofs of the party, as for
+    /// [`Self::reset_proof_state`].
+    pub fn stats_prometheus(&self) -> String {
+        self.monitor.prometheus("verifier")
+    }
+
+    /// Return the gate counts and checks so far, which can be read at any point
+    /// of the evaluation.
+    pub fn stats(&self) -> CircuitStats {
+        self.monitor.stats()
+    }
+
+    /// Return the bytes communicated by the party, including the sVOLE setup,
+    /// if its channel counts them, e.g., a [`TrackChannel`].
+    ///
+    /// [`TrackChannel`]: scuttlebutt::TrackChannel
+    pub fn comm_stats(&self) -> Option<CommStats> {
+        CommStats::from_channel(&self.channel)
+    }
+
+    /// Return the number of multiplications minus the number of multiplications
+    /// covered by the multiplication checks so far.
+    ///
+    /// After `finalize`, a nonzero value denotes multiplications which were not
+    /// checked, and thus a soundness bug.
+    pub fn mult_check_discrepancy(&self) -> isize {
+        self.monitor.mult_check_discrepancy()
+    }
+
+    fn check_mult_check_discrepancy(&mut self) -> Result<()> {
+        let discrepancy = self.mult_check_discrepancy();
+        if self.strict_mult_check && discrepancy != 0 {
+            self.is_ok = false;
+            return Err(DmcError::UncheckedMultiplications(discrepancy).into());
+        }
+        Ok(())
+    }
+
+    /// Set the number of sVOLE extensions performed each time the voles run
+    /// out, for a predictable pattern of extension rounds.
+    ///
+    /// Each extension produces a number of voles fixed by the LPN parameters.
+    /// Larger chunks mean fewer but longer pauses to extend the voles. Both
+    /// parties must use the same value, which also applies to the parties
+    /// sharing the same functionality through `init_with_fcom`.
+    pub fn set_vole_chunk(&mut self, n: NonZeroUsize) {
+        self.verifier.get_refmut().set_vole_chunk(n);
+    }
+
+    /// Set the number of multiplications after which the multiplication check is
+    /// performed, instead of deferring it to `finalize`.
+    ///
+    /// The multiplication triples are folded into a running aggregate as they are
+    /// produced, and each check verifies and resets the aggregate. After each
+    /// periodic check, the verifier sends a fresh challenge for folding the next
+    /// triples, so that each check is the check of `finalize` over fewer triples,
+    /// at the cost of the extra communication of each check. Both parties must use
+    /// the same interval.
+    pub fn set_mult_check_interval(&mut self, interval: Option<usize>) {
+        assert_ne!(
+            interval,
+            Some(0),
+            "multiplication check interval must be nonzero"
+        );
+        self.mult_check_interval = interval;
+    }
+
+    /// Initialize a verifier for a sub-proof sharing the functionality of this
+    /// verifier, so that wires can be shared with the sub-proof.
+    ///
+    /// See [`DietMacAndCheeseProver::sub_proof`].
+    pub fn sub_proof(&mut self, rng: RNG) -> Result<Self> {
+        let mut verifier =
+            Self::init_with_fcom(&mut self.channel, rng, &self.verifier, self.no_batching)?;
+        verifier.set_mult_check_interval(self.mult_check_interval);
+        Ok(verifier)
+    }
+
+    /// Verify the values asserted to be zero in batches of `window` values, as
+    /// they arrive.
+    ///
+    /// The queue of values asserted to be zero never holds more than `window`
+    /// values, and its memory is released after each batch. This bounds the
+    /// memory of a verifier processing a large streamed proof, at the cost of
+    /// one round of interaction per batch. The prover must use the schedule
+    /// `Batched { capacity: window }`.
+    pub fn set_streaming_window(&mut self, window: usize) {
+        assert_ne!(window, 0, "streaming window must be nonzero");
+        self.zero_check_schedule = Arc::new(Batched { capacity: window });
+        self.streaming_window = Some(window);
+        if self.check_zero_list.len() <= window {
+            self.check_zero_list.shrink_to(window);
+            self.check_zero_list
+                .reserve_exact(window - self.check_zero_list.len());
+        }
+    }
+
+    /// Return the number of values the queue of values asserted to be zero
+    /// can hold without reallocating.
+    pub fn zero_check_queue_capacity(&self) -> usize {
+        self.check_zero_list.capacity()
+    }
+
+    /// Return an estimate of the bits of soundness of the multiplication checks
+    /// of the multiplications evaluated so far.
+    ///
+    /// This decreases with the number of multiplications, and is about the bit
+    /// length of `FE` minus the logarithm of that number.
+    pub fn mult_check_soundness_bits(&self) -> f64 {
+        mult_check_soundness_bits::<FE>(self.monitor.monitor_mul)
+    }
+
+    /// Return an estimate of the number of round trips between the parties
+    /// required to check the gates evaluated so far, including `finalize`.
+    ///
+    /// Each batch of values asserted to be zero and each multiplication check
+    /// takes one round trip, so the estimate grows with `no_batching`, the zero
+    /// check schedule and the multiplication check interval. The round trips of
+    /// challenges and of the sVOLE extensions are not counted.
+    pub fn estimated_round_trips(&self) -> usize {
+        estimated_round_trips(
+            self.monitor.monitor_check_zero,
+            self.monitor.monitor_mul,
+            self.no_batching,
+            self.zero_check_schedule.as_ref(),
+            self.mult_check_interval,
+        )
+    }
+
+    /// Return the number of bytes held by the state of the multiplication check.
+    ///
+    /// The multiplication triples are folded into a running aggregate as they
+    /// are produced, so this does not grow with the number of multiplications.
+    /// Use [`Self::set_mult_check_interval`] to bound the number of triples
+    /// covered by each check instead.
+    pub fn mult_state_bytes(&self) -> usize {
+        std::mem::size_of_val(&self.state_mult_check)
+    }
+
+    fn push_mult_check(&mut self) -> Result<()> {
+        if let Some(interval) = self.mult_check_interval {
+            if self.state_mult_check.count() >= interval {
+                let r = self.do_mult_check();
+                // The fresh challenge is sent even when the check fails, so
+                // that the prover does not wait for it.
+                let refreshed = self
+                    .state_mult_check
+                    .refresh_challenge(&mut self.channel, &mut self.rng);
+                let r = r.and(refreshed);
+                if r.is_err() {
+                    warn!("mult_check fails");
+                    self.is_ok = false;
+                }
+                r?;
+            }
+        }
+        Ok(())
+    }
+
     // this function should be called before every function exposed publicly by the API.
     fn check_is_ok(&self) -> Result<()> {
         if !self.is_ok {
-            return Err(eyre!(
-                "An error occurred earlier. This functionality should not be used further"
-            ));
+            return Err(DmcError::UsedAfterError.into());
+        }
+        if self.finalized {
+            return Err(DmcError::AlreadyFinalized.into());
         }
         Ok(())
     }
@@ -437,6 +2071,8 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
 
     fn do_mult_check(&mut self) -> Result<usize> {
         debug!("do mult_check");
+        let start = Instant::now();
+        let comm_start = self.channel.comm_bytes();
         self.channel.flush()?;
         let cnt = self.verifier.get_refmut().quicksilver_finalize(
             &mut self.channel,
@@ -444,31 +2080,70 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
             &mut self.state_mult_check,
         )?;
         self.monitor.incr_zk_mult_check(cnt);
+        self.report_flush(FlushEvent::new(
+            FlushKind::MultCheck,
+            cnt,
+            start,
+            comm_start,
+            self.channel.comm_bytes(),
+        ));
         Ok(cnt)
     }
 
-    fn do_check_zero(&mut self) -> Result<()> {
+    fn do_check_zero(&mut self, cancelled: &AtomicBool) -> Result<()> {
         // debug!("do check_zero");
+        let start = Instant::now();
+        let comm_start = self.channel.comm_bytes();
         self.channel.flush()?;
-        let r = self.verifier.get_refmut().check_zero(
-            &mut self.channel,
-            &mut self.rng,
-            &self.check_zero_list,
-        );
+        let chunk_size = self
+            .zero_check_schedule
+            .chunk_size(self.check_zero_list.len())
+            .max(1);
+        let mut r = Ok(());
+        let queue = std::mem::take(&mut self.check_zero_list);
+        // An empty queue is still checked, as a single empty chunk.
+        let empty = queue.is_empty().then_some(&queue[..]);
+        for chunk in queue.chunks(chunk_size).chain(empty) {
+            // Each chunk is a round of interaction, so a cancellation takes
+            // effect before the next chunk.
+            check_cancelled(&mut self.is_ok, cancelled)?;
+            r = r.and(self.verifier.get_refmut().check_zero(
+                &mut self.channel,
+                &mut self.rng,
+                chunk,
+            ));
+        }
         if r.is_err() {
             warn!("check_zero fails");
             self.is_ok = false;
         }
-        self.monitor.incr_zk_check_zero(self.check_zero_list.len());
+        let batch_len = queue.len();
+        self.monitor.incr_zk_check_zero(batch_len);
+        // The queue is put back empty, to keep its allocation.
+        self.check_zero_list = queue;
         self.check_zero_list.clear();
+        self.report_flush(FlushEvent::new(
+            FlushKind::CheckZero,
+            batch_len,
+            start,
+            comm_start,
+            self.channel.comm_bytes(),
+        ));
+        if let Some(window) = self.streaming_window {
+            self.check_zero_list.shrink_to(window);
+        }
         r
     }
 
     fn push_check_zero_list(&mut self, e: MacVerifier<FE>) -> Result<()> {
         self.check_zero_list.push(e);
 
-        if self.check_zero_list.len() == QUEUE_CAPACITY || self.no_batching {
-            self.do_check_zero()?;
+        if self.no_batching
+            || self
+                .zero_check_schedule
+                .should_check(self.check_zero_list.len())
+        {
+            self.do_check_zero(&NOT_CANCELLED)?;
         }
         Ok(())
     }
@@ -480,6 +2155,43 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
         self.push_check_zero_list(*value)
     }
 
+    /// Assert that all of `values` are zero.
+    ///
+    /// See [`DietMacAndCheeseProver::assert_zero_many`].
+    pub(crate) fn assert_zero_many(&mut self, values: &[MacVerifier<FE>]) -> Result<()> {
+        self.check_is_ok()?;
+        if self.no_batching || self.zero_check_schedule.should_check(1) {
+            return values.iter().try_for_each(|value| self.assert_zero(value));
+        }
+        self.monitor.incr_monitor_check_zero_many(values.len());
+        let mut values = values;
+        while !values.is_empty() {
+            let n = values_until_check(
+                self.zero_check_schedule.as_ref(),
+                self.check_zero_list.len(),
+                values.len(),
+            );
+            let (chunk, rest) = values.split_at(n);
+            self.check_zero_list.extend_from_slice(chunk);
+            values = rest;
+            if self
+                .zero_check_schedule
+                .should_check(self.check_zero_list.len())
+            {
+                self.do_check_zero(&NOT_CANCELLED)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assert two values are equal.
+    pub(crate) fn assert_eq(&mut self, a: &MacVerifier<FE>, b: &MacVerifier<FE>) -> Result<()> {
+        self.check_is_ok()?;
+        self.monitor.incr_monitor_check_zero();
+        let diff = self.verifier.get_refmut().sub(*a, *b);
+        self.push_check_zero_list(diff)
+    }
+
     /// Add two values.
     pub(crate) fn add(
         &mut self,
@@ -491,6 +2203,17 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
         Ok(self.verifier.get_refmut().add(*a, *b))
     }
 
+    /// Subtract `b` from `a`.
+    pub(crate) fn sub(
+        &mut self,
+        a: &MacVerifier<FE>,
+        b: &MacVerifier<FE>,
+    ) -> Result<MacVerifier<FE>> {
+        self.check_is_ok()?;
+        self.monitor.incr_monitor_sub();
+        Ok(self.verifier.get_refmut().sub(*a, *b))
+    }
+
     /// Multiply two values.
     pub(crate) fn mul(
         &mut self,
@@ -503,6 +2226,55 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
         self.verifier
             .get_refmut()
             .quicksilver_push(&mut self.state_mult_check, &(*a, *b, tag))?;
+        self.push_mult_check()?;
+        Ok(tag)
+    }
+
+    /// Return the dot product of `a` and `b`.
+    ///
+    /// This receives the result from the prover and adds it to the
+    /// multiplication check as a single entry.
+    pub(crate) fn dot_product(
+        &mut self,
+        a: &[MacVerifier<FE>],
+        b: &[MacVerifier<FE>],
+    ) -> Result<MacVerifier<FE>> {
+        self.check_is_ok()?;
+        if a.len() != b.len() {
+            return Err(eyre!(
+                "dot_product of vectors of lengths {} and {}",
+                a.len(),
+                b.len()
+            ));
+        }
+        self.monitor.incr_monitor_mul();
+        let tag = self.input()?;
+        self.verifier.get_refmut().quicksilver_push_dot_product(
+            &mut self.state_mult_check,
+            a,
+            b,
+            &tag,
+        )?;
+        self.push_mult_check()?;
+        Ok(tag)
+    }
+
+    /// Divide `a` by `b`.
+    ///
+    /// This receives the quotient `q` from the prover and adds `q * b == a` to
+    /// the multiplication check.
+    pub(crate) fn div(
+        &mut self,
+        a: &MacVerifier<FE>,
+        b: &MacVerifier<FE>,
+    ) -> Result<MacVerifier<FE>> {
+        self.check_is_ok()?;
+        self.monitor.incr_monitor_mul();
+        let tag = self.input()?;
+        self.verifier
+            .get_refmut()
+            .quicksilver_push(&mut self.state_mult_check, &(tag, *b, *a))?;
+        self.push_mult_check()?;
         Ok(tag)
     }
 
@@ -534,68 +2306,380 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>
         MacVerifier::new(-val * self.get_party().get_refmut().get_delta())
     }
 
-    /// Input a private value and verifier value.
-    pub(crate) fn input_private(&mut self) -> Result<MacVerifier<FE>> {
-        self.check_is_ok()?;
-        self.monitor.incr_monitor_witness();
-        self.input()
+    /// Sample a random challenge and send it to the prover.
+    ///
+    /// The channel is flushed once the challenge is sent. The prover obtains
+    /// the same value from [`DietMacAndCheeseProver::challenge_value`].
+    pub fn challenge_value(&mut self) -> Result<FieldClear<FE>> {
+        Ok(self.challenge_values(1)?[0])
     }
 
-    /// `finalize` execute its internal queued multiplication and zero checks.
-    /// It can be called at any time and it is also be called when the functionality is dropped.
-    pub fn finalize(&mut self) -> Result<()> {
-        debug!("finalize");
+    /// Sample `n` random challenges and send them to the prover at once.
+    pub fn challenge_values(&mut self, n: usize) -> Result<Vec<FieldClear<FE>>> {
         self.check_is_ok()?;
+        let values = (0..n)
+            .map(|_| {
+                let value = FieldClear::<FE>::random(&mut self.rng);
+                self.channel.write_serializable(&value)?;
+                Ok(value)
+            })
+            .collect::<Result<Vec<_>>>()?;
         self.channel.flush()?;
-        let zero_len = self.check_zero_list.len();
-        self.do_check_zero()?;
+        Ok(values)
+    }
 
-        let mult_len = self.do_mult_check()?;
-        debug!(
-            "finalize: mult_check:{:?}, check_zero:{:?} ",
-            mult_len, zero_len
-        );
-        self.log_final_monitor();
-        Ok(())
+    /// Sample a random challenge, as a public wire.
+    ///
+    /// See [`Self::challenge_value`].
+    pub fn challenge(&mut self) -> Result<MacVerifier<FE>> {
+        let value = self.challenge_value()?;
+        Ok(self.public_wire(value))
     }
 
-    fn log_final_monitor(&self) {
-        info!("field largest value: {:?}", (FE::ZERO - FE::ONE).to_bytes());
-        self.monitor.log_final_monitor();
+    /// Sample `n` random challenges, as public wires.
+    pub fn challenges(&mut self, n: usize) -> Result<Vec<MacVerifier<FE>>> {
+        let values = self.challenge_values(n)?;
+        Ok(values.into_iter().map(|v| self.public_wire(v)).collect())
     }
 
-    pub(crate) fn reset(&mut self) {
-        self.verifier.get_refmut().reset(&mut self.state_mult_check);
-        self.is_ok = true;
+    // Return the wire of the public `value`, without counting it as an input.
+    fn public_wire(&mut self, value: FieldClear<FE>) -> MacVerifier<FE> {
+        MacVerifier::new(-value * self.get_party().get_refmut().get_delta())
     }
-}
 
-impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> Drop
-    for DietMacAndCheeseVerifier<FE, C, RNG>
-{
-    fn drop(&mut self) {
-        if self.is_ok && !self.check_zero_list.is_empty() {
-            warn!("Dropped in unexpected state: either `finalize()` has not been called or an error occured earlier.");
-        }
+    /// Input a private value and verifier value.
+    pub(crate) fn input_private(&mut self) -> Result<MacVerifier<FE>> {
+        self.check_is_ok()?;
+        self.check_input_budget(1)?;
+        self.monitor.incr_monitor_witness();
+        self.input()
     }
-}
 
-#[cfg(test)]
+    /// Input a batch of `num` private values.
+    ///
+    /// This mirrors [`DietMacAndCheeseProver::input_private_batch`]. The masked
+    /// values sent by the prover are received in blocks rather than one at a time.
+    pub fn input_private_batch(&mut self, num: usize) -> Result<Vec<MacVerifier<FE>>> {
+        self.check_is_ok()?;
+        self.check_input_budget(num)?;
+        let mut out = Vec::with_capacity(num);
+        let r = self.verifier.get_refmut().input_low_level(
+            &mut self.channel,
+            &mut self.rng,
+            num,
+            &mut out,
+        );
+        if r.is_err() {
+            self.is_ok = false;
+        }
+        r?;
+        for _ in 0..num {
+            self.monitor.incr_monitor_witness();
+        }
+        Ok(out)
+    }
+
+    /// Input `n` private values, passing each of their wires to `f` as soon as
+    /// it is input.
+    ///
+    /// This mirrors [`DietMacAndCheeseProver::input_private_stream`], receiving
+    /// the values in the same blocks.
+    pub fn input_private_stream(
+        &mut self,
+        n: usize,
+        mut f: impl FnMut(&mut Self, MacVerifier<FE>) -> Result<()>,
+    ) -> Result<()> {
+        self.check_is_ok()?;
+        self.check_input_budget(n)?;
+        let mut done = 0;
+        while done < n {
+            let block = STREAM_BLOCK.min(n - done);
+            for wire in self.input_private_batch(block)? {
+                f(self, wire)?;
+            }
+            done += block;
+        }
+        Ok(())
+    }
+
+    /// Receive the value of `value` opened by the prover, and return it along
+    /// with its MAC as a public value.
+    ///
+    /// This mirrors [`DietMacAndCheeseProver::reveal_as_public`], and fails if the
+    /// opened value does not match its MAC.
+    pub fn reveal_as_public(
+        &mut self,
+        value: &MacVerifier<FE>,
+    ) -> Result<(FieldClear<FE>, MacVerifier<FE>)> {
+        let out = self.reveal_slice(std::slice::from_ref(value))?;
+        Ok((out[0], self.input_public(out[0])))
+    }
+
+    /// Receive the value of `value` opened by the prover.
+    ///
+    /// This mirrors [`DietMacAndCheeseProver::reveal`]. If the opened value does
+    /// not match its MAC, this fails and the verifier cannot be used further.
+    pub fn reveal(&mut self, value: &MacVerifier<FE>) -> Result<FieldClear<FE>> {
+        Ok(self.reveal_slice(std::slice::from_ref(value))?[0])
+    }
+
+    /// Receive the values of `values` opened by the prover, in order.
+    ///
+    /// This mirrors [`DietMacAndCheeseProver::reveal_slice`], and fails if any
+    /// opened value does not match its MAC.
+    pub fn reveal_slice(&mut self, values: &[MacVerifier<FE>]) -> Result<Vec<FieldClear<FE>>> {
+        self.check_is_ok()?;
+        let mut out = Vec::with_capacity(values.len());
+        let r = self
+            .verifier
+            .get_refmut()
+            .open(&mut self.channel, values, &mut out);
+        if r.is_err() {
+            self.is_ok = false;
+        }
+        r?;
+        Ok(out)
+    }
+
+    /// `finalize` execute its internal queued multiplication and zero checks.
+    /// It can be called at any time and it is also be called when the functionality is dropped.
+    /// After `finalize`, gates fail with [`DmcError::AlreadyFinalized`] until
+    /// `reset` is called, and calling `finalize` again does nothing.
+    pub fn finalize(&mut self) -> Result<()> {
+        self.finalize_cancellable(&NOT_CANCELLED)
+    }
+
+    /// `finalize`, checking `cancelled` before each of its rounds of checks, and
+    /// failing with [`DmcError::Cancelled`] once it is set.
+    ///
+    /// See [`DietMacAndCheeseProver::finalize_cancellable`].
+    pub fn finalize_cancellable(&mut self, cancelled: &AtomicBool) -> Result<()> {
+        debug!("finalize");
+        if self.finalized {
+            return Ok(());
+        }
+        self.check_is_ok()?;
+        self.channel.flush()?;
+        let zero_len = self.check_zero_list.len();
+        self.do_check_zero(cancelled)?;
+
+        check_cancelled(&mut self.is_ok, cancelled)?;
+        let mult_len = self.do_mult_check()?;
+        debug!(
+            "finalize: mult_check:{:?}, check_zero:{:?} ",
+            mult_len, zero_len
+        );
+        self.log_final_monitor();
+        self.check_mult_check_discrepancy()?;
+        self.finalized = true;
+        Ok(())
+    }
+
+    fn log_final_monitor(&self) {
+        info!("field largest value: {:?}", (FE::ZERO - FE::ONE).to_bytes());
+        self.monitor.log_final_monitor();
+        if let Some(comm) = self.comm_stats() {
+            comm.log();
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.verifier.get_refmut().reset(&mut self.state_mult_check);
+        self.is_ok = true;
+        self.finalized = false;
+    }
+
+    /// Reset the state of the current proof, to start a new proof with the same
+    /// parties.
+    ///
+    /// This discards the values queued for a zero check and the multiplication
+    /// triples accumulated since the last check, without checking them, and
+    /// clears any earlier error. The monitor is kept, so that its statistics
+    /// accumulate across all the proofs. Both parties must reset at the same point.
+    pub fn reset_proof_state(&mut self) {
+        self.reset();
+        self.check_zero_list.clear();
+    }
+
+    /// Reset the party to verify a new, independent statement over the same
+    /// connection, without running the sVOLE setup again.
+    ///
+    /// This mirrors [`DietMacAndCheeseProver::reset_session`], and both parties
+    /// must reset their session at the same point.
+    pub fn reset_session(&mut self) {
+        self.reset_proof_state();
+        self.monitor.reset();
+    }
+}
+
+impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> Drop
+    for DietMacAndCheeseVerifier<FE, C, RNG>
+{
+    fn drop(&mut self) {
+        if let Some(drop_error) = self.drop_error.take() {
+            if self.is_ok && !self.finalized && !std::thread::panicking() {
+                if let Err(e) = self.finalize() {
+                    warn!("Finalization on drop failed: {}", e);
+                    drop_error.set(e);
+                    // Discard the unchecked state, which must not be dropped.
+                    self.reset();
+                }
+            }
+            return;
+        }
+        if self.is_ok && !self.check_zero_list.is_empty() {
+            warn!("Dropped in unexpected state: either `finalize()` has not been called or an error occured earlier.");
+        }
+    }
+}
+
+/// Prove the statement built by `build` on a new prover, and finalize it.
+///
+/// The prover is initialized as with [`DietMacAndCheeseProver::init`], with
+/// batching, and its gates are evaluated by `build`. If `build` fails, the
+/// prover is reset instead of finalized and the error is returned. The verifier
+/// must call [`verify`] with the same parameters and a matching `build`.
+pub fn prove<FE, C, RNG, T>(
+    channel: &mut C,
+    rng: RNG,
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    build: impl FnOnce(&mut DietMacAndCheeseProver<FE, C, RNG>) -> Result<T>,
+) -> Result<T>
+where
+    FE: FiniteField,
+    C: AbstractChannel,
+    RNG: CryptoRng + Rng,
+{
+    let mut prover = DietMacAndCheeseProver::init(channel, rng, lpn_setup, lpn_extend, false)?;
+    let result = build(&mut prover).and_then(|t| prover.finalize().map(|_| t));
+    if result.is_err() {
+        prover.reset();
+    }
+    result
+}
+
+/// Verify the statement built by `build` on a new verifier, and finalize it.
+///
+/// This is the verifier side of [`prove`].
+pub fn verify<FE, C, RNG, T>(
+    channel: &mut C,
+    rng: RNG,
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    build: impl FnOnce(&mut DietMacAndCheeseVerifier<FE, C, RNG>) -> Result<T>,
+) -> Result<T>
+where
+    FE: FiniteField,
+    C: AbstractChannel,
+    RNG: CryptoRng + Rng,
+{
+    let mut verifier = DietMacAndCheeseVerifier::init(channel, rng, lpn_setup, lpn_extend, false)?;
+    let result = build(&mut verifier).and_then(|t| verifier.finalize().map(|_| t));
+    if result.is_err() {
+        verifier.reset();
+    }
+    result
+}
+
+/// Insecure verifier for Diet Mac'n'Cheese which accepts any proof.
+///
+/// The verifier takes part in the protocol as a regular verifier, so that the
+/// prover can run unmodified against it, but it ignores the outcome of the zero
+/// and multiplication checks. It is only meant to measure the prover
+/// throughput in isolation, and must never be used to verify a proof.
+#[cfg(feature = "insecure-bench")]
+pub struct AcceptAllVerifier<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng>(
+    pub(crate) DietMacAndCheeseVerifier<FE, C, RNG>,
+);
+
+#[cfg(feature = "insecure-bench")]
+impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> AcceptAllVerifier<FE, C, RNG> {
+    /// Initialize the verifier by providing a channel, a random generator and a pair of LPN parameters as defined by svole.
+    pub fn init(
+        channel: &mut C,
+        rng: RNG,
+        lpn_setup: LpnParams,
+        lpn_extend: LpnParams,
+        no_batching: bool,
+    ) -> Result<Self> {
+        warn!("INSECURE: the accept-all verifier accepts any proof, only use it for benchmarking");
+        Ok(Self(DietMacAndCheeseVerifier::init(
+            channel,
+            rng,
+            lpn_setup,
+            lpn_extend,
+            no_batching,
+        )?))
+    }
+
+    /// Assert a value is zero, ignoring the outcome of any zero check it triggers.
+    pub(crate) fn assert_zero(&mut self, value: &MacVerifier<FE>) -> Result<()> {
+        if self.0.assert_zero(value).is_err() {
+            self.0.is_ok = true;
+        }
+        Ok(())
+    }
+
+    /// Assert that all of `values` are zero, ignoring the outcome of any zero
+    /// check it triggers.
+    pub(crate) fn assert_zero_many(&mut self, values: &[MacVerifier<FE>]) -> Result<()> {
+        if self.0.assert_zero_many(values).is_err() {
+            self.0.is_ok = true;
+        }
+        Ok(())
+    }
+
+    /// `finalize` execute its internal queued multiplication and zero checks, and
+    /// always reports success.
+    pub fn finalize(&mut self) -> Result<()> {
+        debug!("finalize");
+        self.0.channel.flush()?;
+        if self.0.do_check_zero(&NOT_CANCELLED).is_err() {
+            self.0.is_ok = true;
+        }
+        if self.0.do_mult_check().is_err() {
+            self.0.is_ok = true;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
 mod tests {
+    use crate::homcom::{MacProver, MacVerifier};
+    use crate::test_utils::{f61p, run, run_channels, run_lockstep, TestChannel};
+    use crate::zero_check::{Batched, Chunked, Eager, ZeroCheckSchedule};
     use crate::{
-        backend::{DietMacAndCheeseProver, DietMacAndCheeseVerifier},
+        backend::{
+            estimated_round_trips, from_bytes_le, prove, verify, CircuitStats,
+            DietMacAndCheeseProver, DietMacAndCheeseVerifier, DmcConfig, DropError, FlushEvent,
+            FlushHook, FlushKind, Monitor, ProgressCallback, WitnessSource, STREAM_BLOCK,
+        },
         backend_trait::BackendT,
+        error::{is_io_error, DmcError},
     };
+    use eyre::Result;
     use ocelot::svole::wykw::{LPN_EXTEND_SMALL, LPN_SETUP_SMALL};
     use rand::SeedableRng;
-    use scuttlebutt::{field::F40b, ring::FiniteRing};
+    use scuttlebutt::{
+        field::{F40b, F2},
+        ring::FiniteRing,
+    };
     use scuttlebutt::{
         field::{F61p, FiniteField},
-        AesRng, Channel,
+        AbstractChannel, AesRng, Channel, ChannelPool, TrackChannel,
     };
     use std::{
+        collections::HashMap,
         io::{BufReader, BufWriter},
+        marker::PhantomData,
+        num::NonZeroUsize,
         os::unix::net::UnixStream,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
     };
 
     fn test<FE: FiniteField>() {
@@ -645,6 +2729,7 @@ mod tests {
             let r_zero_priv = dmc.addc(&n24_priv, -(three * two * two * two)).unwrap();
             dmc.assert_zero(&r_zero_priv).unwrap();
             dmc.finalize().unwrap();
+            dmc.reset();
             dmc.assert_zero(&n24_priv).unwrap();
             assert!(dmc.finalize().is_err());
         });
@@ -677,13 +2762,114 @@ mod tests {
         let r_zero_priv = dmc.addc(&n24_priv, -(three * two * two * two)).unwrap();
         dmc.assert_zero(&r_zero_priv).unwrap();
         dmc.finalize().unwrap();
+        dmc.reset();
         dmc.assert_zero(&n24_priv).unwrap();
         assert!(dmc.finalize().is_err());
 
         handle.join().unwrap();
     }
 
+    // The arithmetic example of `test` with the witness `two`, through
+    // `prove` and `verify`.
+    fn test_prove_verify(two: u64) -> (Result<()>, Result<()>) {
+        let (sender, receiver) = UnixStream::pair().unwrap();
+        let handle = std::thread::spawn(move || {
+            let reader = BufReader::new(sender.try_clone().unwrap());
+            let writer = BufWriter::new(sender);
+            let mut channel = Channel::new(reader, writer);
+            prove::<F61p, _, _, _>(
+                &mut channel,
+                AesRng::from_seed(Default::default()),
+                LPN_SETUP_SMALL,
+                LPN_EXTEND_SMALL,
+                |dmc| {
+                    let three_pub = dmc.input_public(f61p(3));
+                    let two_priv = dmc.input_private(f61p(two))?;
+                    let six = dmc.mul(&two_priv, &three_pub)?;
+                    let twelve_priv = dmc.mulc(&six, f61p(2))?;
+                    let n24_priv = dmc.mul(&twelve_priv, &two_priv)?;
+                    let r_zero_priv = dmc.addc(&n24_priv, -f61p(24))?;
+                    dmc.assert_zero(&r_zero_priv)
+                },
+            )
+        });
+
+        let reader = BufReader::new(receiver.try_clone().unwrap());
+        let writer = BufWriter::new(receiver);
+        let mut channel = Channel::new(reader, writer);
+        let verifier = verify::<F61p, _, _, _>(
+            &mut channel,
+            AesRng::from_seed(Default::default()),
+            LPN_SETUP_SMALL,
+            LPN_EXTEND_SMALL,
+            |dmc| {
+                let three_pub = dmc.input_public(f61p(3));
+                let two_priv = dmc.input_private()?;
+                let six = dmc.mul(&two_priv, &three_pub)?;
+                let twelve_priv = dmc.mulc(&six, f61p(2))?;
+                let n24_priv = dmc.mul(&twelve_priv, &two_priv)?;
+                let r_zero_priv = dmc.addc(&n24_priv, -f61p(24))?;
+                dmc.assert_zero(&r_zero_priv)
+            },
+        );
+        drop(channel);
+        (handle.join().unwrap(), verifier)
+    }
+
+    #[test]
+    fn test_prove_verify_closure() {
+        let (prover, verifier) = test_prove_verify(2);
+        prover.unwrap();
+        verifier.unwrap();
+
+        let (prover, verifier) = test_prove_verify(3);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
     fn test_challenge<F: FiniteField>() {
+        let (prover, verifier) = run::<F, _, _>(
+            |dmc| {
+                let challenge = dmc.challenge()?;
+                let revealed = dmc.reveal(&challenge)?;
+                assert_eq!(revealed, challenge.value());
+                Ok((revealed, dmc.challenge_value()?))
+            },
+            |dmc| {
+                let challenge = dmc.challenge()?;
+                let revealed = dmc.reveal(&challenge)?;
+                Ok((revealed, dmc.challenge_value()?))
+            },
+        );
+        // The wires of the challenge commit both parties to the same value.
+        assert_eq!(prover.unwrap(), verifier.unwrap());
+    }
+
+    fn test_challenges<F: FiniteField>() -> Vec<F::PrimeField> {
+        let (prover, verifier) = run::<F, _, _>(
+            |dmc| {
+                let challenges = dmc.challenges(10)?;
+                dmc.reveal_slice(&challenges)
+            },
+            |dmc| {
+                let challenges = dmc.challenges(10)?;
+                dmc.reveal_slice(&challenges)
+            },
+        );
+        let prover = prover.unwrap();
+        assert_eq!(prover.len(), 10);
+        assert_eq!(prover, verifier.unwrap());
+        prover
+    }
+
+    // Prove `nb_mults` multiplications, checking them every `interval`
+    // multiplications, where the prover cheats on the multiplication `bad_mult`.
+    // Return the number of multiplications checked by the verifier.
+    fn test_mult_check_interval(
+        nb_mults: usize,
+        interval: Option<usize>,
+        bad_mult: Option<usize>,
+    ) -> Result<usize> {
         let (sender, receiver) = UnixStream::pair().unwrap();
         let handle = std::thread::spawn(move || {
             let rng = AesRng::from_seed(Default::default());
@@ -691,7 +2877,7 @@ mod tests {
             let writer = BufWriter::new(sender);
             let mut channel = Channel::new(reader, writer);
 
-            let mut dmc: DietMacAndCheeseProver<F, _, _> = DietMacAndCheeseProver::init(
+            let mut dmc: DietMacAndCheeseProver<F61p, _, _> = DietMacAndCheeseProver::init(
                 &mut channel,
                 rng,
                 LPN_SETUP_SMALL,
@@ -699,12 +2885,28 @@ mod tests {
                 false,
             )
             .unwrap();
+            dmc.set_mult_check_interval(interval);
 
-            let challenge = dmc.challenge().unwrap();
-
-            dmc.finalize().unwrap();
-
-            challenge
+            let mut run = || -> Result<()> {
+                let x = dmc.input_private(F61p::ONE + F61p::ONE)?;
+                let mut acc = x;
+                for i in 0..nb_mults {
+                    if bad_mult == Some(i) {
+                        let out = dmc.input(acc.value() * x.value() + F61p::ONE)?;
+                        dmc.prover
+                            .get_refmut()
+                            .quicksilver_push(&mut dmc.state_mult_check, &(acc, x, out))?;
+                        dmc.push_mult_check()?;
+                        acc = out;
+                    } else {
+                        acc = dmc.mul(&acc, &x)?;
+                    }
+                }
+                dmc.finalize()
+            };
+            if run().is_err() {
+                dmc.reset();
+            }
         });
 
         let rng = AesRng::from_seed(Default::default());
@@ -712,7 +2914,7 @@ mod tests {
         let writer = BufWriter::new(receiver);
         let mut channel = Channel::new(reader, writer);
 
-        let mut dmc: DietMacAndCheeseVerifier<F, _, _> = DietMacAndCheeseVerifier::init(
+        let mut dmc: DietMacAndCheeseVerifier<F61p, _, _> = DietMacAndCheeseVerifier::init(
             &mut channel,
             rng,
             LPN_SETUP_SMALL,
@@ -720,22 +2922,2189 @@ mod tests {
             false,
         )
         .unwrap();
+        dmc.set_mult_check_interval(interval);
 
-        let challenge = dmc.challenge().unwrap();
-        dmc.finalize().unwrap();
+        let mut run = || -> Result<usize> {
+            let x = dmc.input_private()?;
+            let mut acc = x;
+            for _ in 0..nb_mults {
+                acc = dmc.mul(&acc, &x)?;
+            }
+            dmc.finalize()?;
+            Ok(dmc.monitor.monitor_zk_mult_check)
+        };
+        let r = run();
+        if r.is_err() {
+            dmc.reset();
+        }
+        // Close the channel so that the prover does not wait on the verifier.
+        drop(dmc);
+        drop(channel);
 
-        let prover_challenge = handle.join().unwrap();
-        assert_eq!(prover_challenge.mac(), challenge.mac());
+        handle.join().unwrap();
+        r
     }
 
     #[test]
-    fn test_f61p() {
-        test::<F61p>();
-        test_challenge::<F61p>();
+    fn test_mult_check_interval_matches_batch() {
+        let nb_mults = 5000;
+        assert_eq!(
+            test_mult_check_interval(nb_mults, None, None).unwrap(),
+            nb_mults
+        );
+        assert_eq!(
+            test_mult_check_interval(nb_mults, Some(1000), None).unwrap(),
+            nb_mults
+        );
+        assert_eq!(
+            test_mult_check_interval(nb_mults, Some(333), None).unwrap(),
+            nb_mults
+        );
     }
 
     #[test]
-    fn test_f40b() {
-        test_challenge::<F40b>();
+    fn test_mult_check_interval_catches_bad_triple() {
+        assert!(test_mult_check_interval(5000, None, Some(1234)).is_err());
+        assert!(test_mult_check_interval(5000, Some(1000), Some(1234)).is_err());
+        assert!(test_mult_check_interval(5000, Some(1000), Some(4999)).is_err());
+    }
+
+    #[test]
+    fn test_pooled_channel_reuses_buffers() {
+        let nb_proofs = 3;
+        let (provers, verifiers): (Vec<_>, Vec<_>) =
+            (0..nb_proofs).map(|_| UnixStream::pair().unwrap()).unzip();
+
+        let handle = std::thread::spawn(move || {
+            let pool = ChannelPool::new(1 << 16);
+            for stream in provers {
+                let mut channel = pool.channel(stream.try_clone().unwrap(), stream);
+                let mut dmc: DietMacAndCheeseProver<F61p, _, _> = DietMacAndCheeseProver::init(
+                    &mut channel,
+                    AesRng::new(),
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                let x = dmc.input_private(F61p::ONE + F61p::ONE).unwrap();
+                let square = dmc.mul(&x, &x).unwrap();
+                let zero = dmc.addc(&square, -(x.value() * x.value())).unwrap();
+                dmc.assert_zero(&zero).unwrap();
+                dmc.finalize().unwrap();
+            }
+            pool.allocations()
+        });
+
+        let pool = ChannelPool::new(1 << 16);
+        for stream in verifiers {
+            let mut channel = pool.channel(stream.try_clone().unwrap(), stream);
+            let mut dmc: DietMacAndCheeseVerifier<F61p, _, _> = DietMacAndCheeseVerifier::init(
+                &mut channel,
+                AesRng::new(),
+                LPN_SETUP_SMALL,
+                LPN_EXTEND_SMALL,
+                false,
+            )
+            .unwrap();
+            let two = F61p::ONE + F61p::ONE;
+            let x = dmc.input_private().unwrap();
+            let square = dmc.mul(&x, &x).unwrap();
+            let zero = dmc.addc(&square, -(two * two)).unwrap();
+            dmc.assert_zero(&zero).unwrap();
+            dmc.finalize().unwrap();
+        }
+
+        assert_eq!(pool.allocations(), 1);
+        assert_eq!(handle.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_max_witness_value() {
+        let witness = |values: Vec<F61p>| {
+            let n = values.len();
+            let (prover, verifier) = run::<F61p, _, _>(
+                move |dmc| {
+                    let x = dmc.input_private(-F61p::ONE)?;
+                    dmc.mul(&x, &x)?;
+                    // Values are only tracked once enabled.
+                    assert_eq!(dmc.max_witness_value(), F61p::ZERO);
+                    dmc.set_track_max_witness(true);
+                    for v in values {
+                        let x = dmc.input_private(v)?;
+                        dmc.mul(&x, &x)?;
+                    }
+                    Ok((dmc.max_witness_value(), dmc.max_witness_near_modulus()))
+                },
+                move |dmc| {
+                    for _ in 0..n + 1 {
+                        let x = dmc.input_private()?;
+                        dmc.mul(&x, &x)?;
+                    }
+                    Ok(())
+                },
+            );
+            verifier.unwrap();
+            prover.unwrap()
+        };
+
+        let small = witness(vec![f61p(3), f61p(1000), f61p(7)]);
+        assert_eq!(small, (f61p(1000 * 1000), false));
+        let near_modulus = witness(vec![f61p(3), -F61p::ONE, f61p(7)]);
+        assert_eq!(near_modulus, (-F61p::ONE, true));
+    }
+
+    fn zero_check_schedule(i: usize) -> Box<dyn ZeroCheckSchedule> {
+        match i {
+            0 => Box::new(Eager),
+            1 => Box::new(Batched { capacity: 3 }),
+            2 => Box::new(Chunked {
+                capacity: 10,
+                chunk: 2,
+            }),
+            _ => Box::new(Chunked {
+                capacity: 1000,
+                chunk: 1,
+            }),
+        }
+    }
+
+    // Assert `x - i == 0` for `i` in `0..10`, with `x` set to `bad` by the prover.
+    fn test_zero_check_schedule(schedule: usize, bad: u64) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_zero_check_schedule(zero_check_schedule(schedule));
+                for i in 0..10 {
+                    let x = dmc.input_private(f61p(if i == 4 { bad } else { i }))?;
+                    let zero = dmc.addc(&x, -f61p(i))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(())
+            },
+            move |dmc| {
+                dmc.set_zero_check_schedule(zero_check_schedule(schedule));
+                for i in 0..10 {
+                    let x = dmc.input_private()?;
+                    let zero = dmc.addc(&x, -f61p(i))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_zero_check_schedules() {
+        for schedule in 0..4 {
+            let (prover, verifier) = test_zero_check_schedule(schedule, 4);
+            prover.unwrap();
+            verifier.unwrap();
+
+            let (prover, verifier) = test_zero_check_schedule(schedule, 5);
+            assert!(prover.is_err());
+            assert!(verifier.is_err());
+        }
+    }
+
+    // Prove `(x * 3 + x) * x == 4 * x^2` and return the wires computed by the prover.
+    fn config_circuit(
+        dmc: &mut DietMacAndCheeseProver<F61p, TestChannel, AesRng>,
+    ) -> Result<Vec<MacProver<F61p>>> {
+        let x = dmc.input_private(f61p(5))?;
+        let x3 = dmc.mulc(&x, f61p(3))?;
+        let x4 = dmc.add(&x3, &x)?;
+        let y = dmc.mul(&x4, &x)?;
+        let zero = dmc.addc(&y, -f61p(100))?;
+        dmc.assert_zero(&zero)?;
+        Ok(vec![x, x4, y])
+    }
+
+    fn config_circuit_verifier(
+        dmc: &mut DietMacAndCheeseVerifier<F61p, TestChannel, AesRng>,
+    ) -> Result<()> {
+        let x = dmc.input_private()?;
+        let x3 = dmc.mulc(&x, f61p(3))?;
+        let x4 = dmc.add(&x3, &x)?;
+        let y = dmc.mul(&x4, &x)?;
+        let zero = dmc.addc(&y, -f61p(100))?;
+        dmc.assert_zero(&zero)
+    }
+
+    fn test_streaming_window(bad: Option<usize>) -> (Result<()>, Result<usize>) {
+        const WINDOW: usize = 16;
+        const N: usize = 10_000;
+        run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_zero_check_schedule(Box::new(Batched { capacity: WINDOW }));
+                for i in 0..N {
+                    let x = dmc.input_private(f61p(i as u64))?;
+                    let y = dmc.addc(&x, -f61p(i as u64))?;
+                    let y = if bad == Some(i) {
+                        dmc.addc(&y, f61p(1))?
+                    } else {
+                        y
+                    };
+                    dmc.assert_zero(&y)?;
+                }
+                Ok(())
+            },
+            move |dmc| {
+                dmc.set_streaming_window(WINDOW);
+                let mut peak = dmc.zero_check_queue_capacity();
+                for i in 0..N {
+                    let x = dmc.input_private()?;
+                    let y = dmc.addc(&x, -f61p(i as u64))?;
+                    let y = if bad == Some(i) {
+                        dmc.addc(&y, f61p(1))?
+                    } else {
+                        y
+                    };
+                    dmc.assert_zero(&y)?;
+                    peak = peak.max(dmc.zero_check_queue_capacity());
+                }
+                Ok(peak)
+            },
+        )
+    }
+
+    #[test]
+    fn test_streaming_window_bounds_queue() {
+        let (prover, verifier) = test_streaming_window(None);
+        prover.unwrap();
+        assert_eq!(verifier.unwrap(), 16);
+    }
+
+    #[test]
+    fn test_streaming_window_detects_failure() {
+        let (prover, verifier) = test_streaming_window(Some(5000));
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn run_reveal_as_public(claimed: u64) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let x = dmc.input_private(f61p(6))?;
+                let y = dmc.input_private(f61p(7))?;
+                let revealed = dmc.reveal_as_public(&x)?;
+                let count = dmc.state_mult_check.count();
+                let xy = dmc.mulc(&y, revealed.value())?;
+                let xx = dmc.mulc(&revealed, revealed.value())?;
+                assert_eq!(dmc.state_mult_check.count(), count);
+                let sum = dmc.add(&xy, &xx)?;
+                let zero = dmc.addc(&sum, -f61p(78))?;
+                dmc.assert_zero(&zero)
+            },
+            move |dmc| {
+                let x = dmc.input_private()?;
+                let y = dmc.input_private()?;
+                let (value, revealed) = dmc.reveal_as_public(&x)?;
+                if value != f61p(claimed) {
+                    return Err(eyre::eyre!("unexpected revealed value"));
+                }
+                let count = dmc.state_mult_check.count();
+                let xy = dmc.mulc(&y, value)?;
+                let xx = dmc.mulc(&revealed, value)?;
+                assert_eq!(dmc.state_mult_check.count(), count);
+                let sum = dmc.add(&xy, &xx)?;
+                let zero = dmc.addc(&sum, -f61p(78))?;
+                dmc.assert_zero(&zero)
+            },
+        )
+    }
+
+    #[test]
+    fn test_reveal_as_public() {
+        let (prover, verifier) = run_reveal_as_public(6);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_reveal_as_public_unexpected_value() {
+        let (_, verifier) = run_reveal_as_public(5);
+        assert!(verifier.is_err());
+    }
+
+    #[cfg(feature = "debug-graph")]
+    #[test]
+    fn test_gate_graph_dot() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                config_circuit(dmc)?;
+                Ok(dmc.gate_graph_dot())
+            },
+            config_circuit_verifier,
+        );
+        verifier.unwrap();
+        let dot = prover.unwrap();
+        assert!(dot.starts_with("digraph gates {"));
+        // private, mulc, add, mul, addc and assert_zero, with edges
+        // x -> mulc, mulc -> add, x -> add, add -> mul, x -> mul, mul -> addc
+        // and addc -> assert_zero.
+        assert_eq!(dot.matches("[label=").count(), 6);
+        assert_eq!(dot.matches(" -> ").count(), 7);
+    }
+
+    #[cfg(feature = "debug-graph")]
+    #[test]
+    fn test_gate_graph_dot_equal_public_values() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let a = dmc.input_public(f61p(1));
+                let b = dmc.input_public(f61p(1));
+                dmc.add(&a, &b)?;
+                Ok(dmc.gate_graph_dot())
+            },
+            |dmc| {
+                let a = dmc.input_public(f61p(1));
+                let b = dmc.input_public(f61p(1));
+                dmc.add(&a, &b)?;
+                Ok(())
+            },
+        );
+        verifier.unwrap();
+        let dot = prover.unwrap();
+        // Both public wires have a node of their own.
+        assert!(dot.contains("n0 -> n2;"));
+        assert!(dot.contains("n1 -> n2;"));
+    }
+
+    // Input `N` private values, batched or one at a time, assert their values
+    // and return the MACs of both parties.
+    fn run_input_batch(batch: bool) -> (Vec<MacProver<F61p>>, Vec<MacVerifier<F61p>>) {
+        const N: u64 = 10_000;
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                let values: Vec<_> = (0..N).map(f61p).collect();
+                let wires = if batch {
+                    dmc.input_private_batch(&values)?
+                } else {
+                    let mut wires = Vec::new();
+                    for v in values.iter() {
+                        wires.push(dmc.input_private(*v)?);
+                    }
+                    wires
+                };
+                for (i, w) in wires.iter().enumerate() {
+                    let zero = dmc.addc(w, -f61p(i as u64))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(wires)
+            },
+            move |dmc| {
+                let wires = if batch {
+                    dmc.input_private_batch(N as usize)?
+                } else {
+                    let mut wires = Vec::new();
+                    for _ in 0..N {
+                        wires.push(dmc.input_private()?);
+                    }
+                    wires
+                };
+                for (i, w) in wires.iter().enumerate() {
+                    let zero = dmc.addc(w, -f61p(i as u64))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(wires)
+            },
+        );
+        (prover.unwrap(), verifier.unwrap())
+    }
+
+    #[test]
+    fn test_input_private_batch() {
+        let (batched_prover, batched) = run_input_batch(true);
+        let (single_prover, single) = run_input_batch(false);
+        assert_eq!(batched_prover, single_prover);
+        assert_eq!(batched, single);
+    }
+
+    // Stream `N` private values from a source of `available` values, checking
+    // each wire as it is input with a queue of capacity 1000, and return the
+    // number of zero assertions of both parties.
+    fn run_input_stream(available: usize) -> (Result<usize>, Result<usize>) {
+        const N: usize = STREAM_BLOCK + 3;
+        const CAPACITY: usize = 1000;
+        run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_queue_capacity(NonZeroUsize::new(CAPACITY).unwrap());
+                let mut src = (0..available as u64).map(f61p);
+                let mut i = 0;
+                let r = dmc.input_private_stream(&mut src, N, |dmc, wire| {
+                    let zero = dmc.addc(&wire, -f61p(i))?;
+                    dmc.assert_zero(&zero)?;
+                    assert!(dmc.check_zero_list.len() < CAPACITY);
+                    i += 1;
+                    Ok(())
+                });
+                if r.is_err() {
+                    // The prover is out of step with the verifier.
+                    assert!(dmc.input_private(f61p(0)).is_err());
+                }
+                r?;
+                Ok(dmc.stats().assert_zero)
+            },
+            |dmc| {
+                dmc.set_queue_capacity(NonZeroUsize::new(CAPACITY).unwrap());
+                let mut i = 0;
+                dmc.input_private_stream(N, |dmc, wire| {
+                    let zero = dmc.addc(&wire, -f61p(i))?;
+                    dmc.assert_zero(&zero)?;
+                    assert!(dmc.check_zero_list.len() < CAPACITY);
+                    i += 1;
+                    Ok(())
+                })?;
+                Ok(dmc.stats().assert_zero)
+            },
+        )
+    }
+
+    #[test]
+    fn test_input_private_stream() {
+        // Iterators keep their `next` with `WitnessSource` in scope.
+        let mut src = (0..2).map(f61p);
+        assert_eq!(src.next(), Some(f61p(0)));
+        assert_eq!(src.next_value(), Some(f61p(1)));
+        assert_eq!(src.next_value(), None);
+
+        let (prover, verifier) = run_input_stream(STREAM_BLOCK + 3);
+        assert_eq!(prover.unwrap(), STREAM_BLOCK + 3);
+        assert_eq!(verifier.unwrap(), STREAM_BLOCK + 3);
+
+        let (prover, verifier) = run_input_stream(STREAM_BLOCK + 1);
+        assert!(prover
+            .unwrap_err()
+            .to_string()
+            .contains("ended after 65537 of 65539 values"));
+        assert!(verifier.is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_input_private_mmap() {
+        use scuttlebutt::serialization::CanonicalSerialize;
+        use std::io::Write;
+
+        const N: u64 = 100;
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for i in 0..N {
+            file.write_all(&f61p(i * i).to_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+        let path = file.path().to_owned();
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                // A length which does not match the file fails before any input.
+                assert!(dmc.input_private_mmap(&path, N as usize + 1, 8).is_err());
+                assert!(dmc.input_private_mmap(&path, N as usize, 7).is_err());
+                let wires = dmc.input_private_mmap(&path, N as usize, 8)?;
+                for (i, w) in wires.iter().enumerate() {
+                    assert_eq!(w.value(), f61p((i * i) as u64));
+                    let zero = dmc.addc(w, -f61p((i * i) as u64))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(())
+            },
+            |dmc| {
+                let wires = dmc.input_private_batch(N as usize)?;
+                for (i, w) in wires.iter().enumerate() {
+                    let zero = dmc.addc(w, -f61p((i * i) as u64))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    fn run_reveal_slice(tampered: Option<usize>) -> (Result<Vec<F61p>>, Result<Vec<F61p>>) {
+        const N: u64 = 100;
+        run::<F61p, _, _>(
+            move |dmc| {
+                let mut wires = Vec::new();
+                for i in 0..N {
+                    let x = dmc.input_private(f61p(i))?;
+                    wires.push(dmc.mul(&x, &x)?);
+                }
+                if let Some(i) = tampered {
+                    let (x, m) = wires[i].decompose();
+                    wires[i] = MacProver::new(x + f61p(1), m);
+                }
+                dmc.reveal_slice(&wires)
+            },
+            |dmc| {
+                let mut wires = Vec::new();
+                for _ in 0..N {
+                    let x = dmc.input_private()?;
+                    wires.push(dmc.mul(&x, &x)?);
+                }
+                dmc.reveal_slice(&wires)
+            },
+        )
+    }
+
+    #[test]
+    fn test_reveal_slice() {
+        let (prover, verifier) = run_reveal_slice(None);
+        let expected: Vec<_> = (0..100).map(|i| f61p(i * i)).collect();
+        assert_eq!(prover.unwrap(), expected);
+        assert_eq!(verifier.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_reveal_slice_tampered_value_fails() {
+        let (_, verifier) = run_reveal_slice(Some(42));
+        assert!(verifier.is_err());
+    }
+
+    // Open `x^2 + 1` for a private `x = 5`, with `forged` replacing its value.
+    fn run_reveal(forged: Option<u64>) -> (Result<F61p>, Result<F61p>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let x = dmc.input_private(f61p(5))?;
+                let xx = dmc.mul(&x, &x)?;
+                let mut y = dmc.addc(&xx, f61p(1))?;
+                if let Some(forged) = forged {
+                    y = MacProver::new(f61p(forged), y.mac());
+                }
+                dmc.reveal(&y)
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let xx = dmc.mul(&x, &x)?;
+                let y = dmc.addc(&xx, f61p(1))?;
+                let r = dmc.reveal(&y);
+                if r.is_err() {
+                    // The verifier cannot be used after a failed opening.
+                    assert!(dmc.input_private().is_err());
+                }
+                r
+            },
+        )
+    }
+
+    #[test]
+    fn test_reveal() {
+        let (prover, verifier) = run_reveal(None);
+        assert_eq!(prover.unwrap(), f61p(26));
+        assert_eq!(verifier.unwrap(), f61p(26));
+
+        let (_, verifier) = run_reveal(Some(27));
+        assert!(verifier.is_err());
+    }
+
+    // Run `config_circuit` with a prover built from the configuration of a
+    // prover set up with `setup`, against a verifier set up with
+    // `setup_verifier`. Check that both provers produce the same wires and that
+    // the built prover flushes as often as the verifier, and return its
+    // statistics and configuration.
+    fn run_prover_from_config(
+        setup: fn(&mut DietMacAndCheeseProver<F61p, TestChannel, AesRng>),
+        setup_verifier: fn(&mut DietMacAndCheeseVerifier<F61p, TestChannel, AesRng>),
+    ) -> (CircuitStats, DmcConfig<F61p>) {
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                setup(dmc);
+                Ok((dmc.config(), config_circuit(dmc)?))
+            },
+            move |dmc| {
+                setup_verifier(dmc);
+                config_circuit_verifier(dmc)
+            },
+        );
+        verifier.unwrap();
+        let (config, expected) = prover.unwrap();
+
+        let ((wires, stats, config), verifier) = run_channels(
+            move |channel, rng| {
+                let mut dmc = config.build(channel, rng).unwrap();
+                let wires = config_circuit(&mut dmc).unwrap();
+                dmc.finalize().unwrap();
+                (wires, dmc.stats(), dmc.config())
+            },
+            |channel, rng| {
+                let mut dmc = DietMacAndCheeseVerifier::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                setup_verifier(&mut dmc);
+                config_circuit_verifier(&mut dmc).unwrap();
+                dmc.finalize().unwrap();
+                dmc.stats()
+            },
+        );
+        assert_eq!(wires, expected);
+        assert_eq!(stats.flushes_check_zero, verifier.flushes_check_zero);
+        assert_eq!(stats.flushes_mult_check, verifier.flushes_mult_check);
+        (stats, config)
+    }
+
+    #[test]
+    fn test_prover_from_config() {
+        run_prover_from_config(
+            |dmc| dmc.set_mult_check_interval(Some(1)),
+            |dmc| dmc.set_mult_check_interval(Some(1)),
+        );
+    }
+
+    #[test]
+    fn test_prover_from_config_keeps_zero_check_schedule() {
+        let (stats, _) = run_prover_from_config(
+            |dmc| dmc.set_zero_check_schedule(Box::new(Batched { capacity: 1 })),
+            |dmc| dmc.set_zero_check_schedule(Box::new(Batched { capacity: 1 })),
+        );
+        // The assertion is checked as soon as it is queued, then the empty queue
+        // is checked by `finalize`.
+        assert_eq!(stats.flushes_check_zero, 2);
+    }
+
+    #[test]
+    fn test_prover_from_config_keeps_strict_mult_check() {
+        let (_, config) = run_prover_from_config(|dmc| dmc.set_strict_mult_check(true), |_| {});
+        assert!(config.strict_mult_check);
+    }
+
+    #[test]
+    fn test_prover_from_config_keeps_input_budget() {
+        let (_, config) = run_prover_from_config(
+            |dmc| dmc.set_input_budget(Some(1)),
+            |dmc| dmc.set_input_budget(Some(1)),
+        );
+        assert_eq!(config.input_budget, Some(1));
+    }
+
+    #[test]
+    fn test_parties_from_config_with_queue_capacity() {
+        let config = DmcConfig::<F61p>::new(LPN_SETUP_SMALL, LPN_EXTEND_SMALL, false)
+            .with_queue_capacity(NonZeroUsize::new(1).unwrap());
+        let prover_config = config.clone();
+        let (prover, verifier) = run_channels(
+            move |channel, rng| {
+                let mut dmc = prover_config.build(channel, rng).unwrap();
+                config_circuit(&mut dmc).unwrap();
+                dmc.finalize().unwrap();
+                dmc.stats()
+            },
+            |channel, rng| {
+                let mut dmc = config.build_verifier(channel, rng).unwrap();
+                config_circuit_verifier(&mut dmc).unwrap();
+                dmc.finalize().unwrap();
+                dmc.stats()
+            },
+        );
+        // The assertion is checked as soon as it is queued, then the empty queue
+        // is checked by `finalize`.
+        assert_eq!(prover.flushes_check_zero, 2);
+        assert_eq!(verifier.flushes_check_zero, 2);
+    }
+
+    #[test]
+    fn test_config_without_lpn_params_fails() {
+        let config = DmcConfig::<F61p> {
+            lpn: None,
+            no_batching: false,
+            zero_check_schedule: Arc::new(Batched { capacity: 1 }),
+            mult_check_interval: None,
+            strict_mult_check: false,
+            input_budget: None,
+            field: PhantomData,
+        };
+        let mut channel = Channel::new(std::io::empty(), std::io::sink());
+        assert!(config
+            .build(&mut channel, AesRng::from_seed(Default::default()))
+            .is_err());
+    }
+
+    #[cfg(feature = "insecure-bench")]
+    #[test]
+    fn test_accept_all_verifier() {
+        use crate::backend::AcceptAllVerifier;
+
+        let (sender, receiver) = UnixStream::pair().unwrap();
+        let handle = std::thread::spawn(move || {
+            let rng = AesRng::from_seed(Default::default());
+            let reader = BufReader::new(sender.try_clone().unwrap());
+            let writer = BufWriter::new(sender);
+            let mut channel = Channel::new(reader, writer);
+
+            let mut dmc: DietMacAndCheeseProver<F61p, _, _> = DietMacAndCheeseProver::init(
+                &mut channel,
+                rng,
+                LPN_SETUP_SMALL,
+                LPN_EXTEND_SMALL,
+                false,
+            )
+            .unwrap();
+            dmc.set_zero_check_schedule(Box::new(Batched { capacity: 2 }));
+
+            let two = F61p::ONE + F61p::ONE;
+            let x = dmc.input_private(two).unwrap();
+            let square = dmc.mul(&x, &x).unwrap();
+            let zero = dmc.addc(&square, -(two * two)).unwrap();
+            dmc.assert_zero(&zero).unwrap();
+            BackendT::assert_zero_many(&mut dmc, &[zero, zero]).unwrap();
+            dmc.finalize().unwrap();
+        });
+
+        let rng = AesRng::from_seed(Default::default());
+        let reader = BufReader::new(receiver.try_clone().unwrap());
+        let writer = BufWriter::new(receiver);
+        let mut channel = Channel::new(reader, writer);
+
+        let mut dmc: AcceptAllVerifier<F61p, _, _> =
+            AcceptAllVerifier::init(&mut channel, rng, LPN_SETUP_SMALL, LPN_EXTEND_SMALL, false)
+                .unwrap();
+        dmc.0
+            .set_zero_check_schedule(Box::new(Batched { capacity: 2 }));
+
+        // The verifier checks `x * x - 5 == 0`, which a regular verifier would
+        // reject, while the prover checks `x * x - 4 == 0`. The zero checks
+        // triggered by `assert_zero_many` are ignored as well.
+        let five = F61p::try_from(5u128).unwrap();
+        let x = dmc.input_private(None).unwrap();
+        let square = dmc.mul(&x, &x).unwrap();
+        let zero = dmc.add_constant(&square, -five).unwrap();
+        dmc.assert_zero(&zero).unwrap();
+        BackendT::assert_zero_many(&mut dmc, &[zero, zero]).unwrap();
+        dmc.finalize().unwrap();
+
+        handle.join().unwrap();
+    }
+
+    #[derive(Clone, Copy)]
+    enum FuzzGate {
+        Input(F61p),
+        Add(usize, usize),
+        Mul(usize, usize),
+        AddConstant(usize, F61p),
+        MulConstant(usize, F61p),
+    }
+
+    // Generate a random circuit with its assertions `wire == value`, which hold
+    // on the generated inputs.
+    fn random_circuit(seed: u128, nb_gates: usize) -> (Vec<FuzzGate>, Vec<(usize, F61p)>) {
+        use rand::Rng;
+
+        let mut rng = AesRng::from_seed(seed.into());
+        let mut gates = Vec::with_capacity(nb_gates);
+        let mut values: Vec<F61p> = Vec::with_capacity(nb_gates);
+        for i in 0..nb_gates {
+            let a = if i == 0 { 0 } else { rng.gen_range(0..i) };
+            let b = if i == 0 { 0 } else { rng.gen_range(0..i) };
+            let c = F61p::random(&mut rng);
+            let (gate, value) = match if i < 2 { 0 } else { rng.gen_range(0..5) } {
+                0 => (FuzzGate::Input(c), c),
+                1 => (FuzzGate::Add(a, b), values[a] + values[b]),
+                2 => (FuzzGate::Mul(a, b), values[a] * values[b]),
+                3 => (FuzzGate::AddConstant(a, c), values[a] + c),
+                _ => (FuzzGate::MulConstant(a, c), values[a] * c),
+            };
+            gates.push(gate);
+            values.push(value);
+        }
+        let asserts = (0..nb_gates / 10)
+            .map(|_| {
+                let w = rng.gen_range(0..nb_gates);
+                (w, values[w])
+            })
+            .collect();
+        (gates, asserts)
+    }
+
+    fn eval_random_circuit<B: BackendT<FieldElement = F61p>>(
+        backend: &mut B,
+        gates: &[FuzzGate],
+        asserts: &[(usize, F61p)],
+        is_prover: bool,
+    ) -> Result<()> {
+        let mut wires = Vec::with_capacity(gates.len());
+        for gate in gates {
+            let wire = match *gate {
+                FuzzGate::Input(v) => backend.input_private(is_prover.then_some(v))?,
+                FuzzGate::Add(a, b) => backend.add(&wires[a], &wires[b])?,
+                FuzzGate::Mul(a, b) => backend.mul(&wires[a], &wires[b])?,
+                FuzzGate::AddConstant(a, c) => backend.add_constant(&wires[a], c)?,
+                FuzzGate::MulConstant(a, c) => backend.mul_constant(&wires[a], c)?,
+            };
+            wires.push(wire);
+        }
+        for (w, value) in asserts {
+            let zero = backend.add_constant(&wires[*w], -*value)?;
+            backend.assert_zero(&zero)?;
+        }
+        Ok(())
+    }
+
+    fn fuzz_random_circuit(seed: u128, tampered: Option<usize>) -> (Result<()>, Result<()>) {
+        let (gates, mut asserts) = random_circuit(seed, 200);
+        if let Some(i) = tampered {
+            let i = i % asserts.len();
+            asserts[i].1 += F61p::ONE;
+        }
+        let (prover_gates, prover_asserts) = (gates.clone(), asserts.clone());
+        run_lockstep::<F61p, _, _>(
+            seed,
+            move |dmc| eval_random_circuit(dmc, &prover_gates, &prover_asserts, true),
+            move |dmc| eval_random_circuit(dmc, &gates, &asserts, false),
+        )
+    }
+
+    #[test]
+    fn test_fuzz_random_circuits() {
+        for seed in 0..8 {
+            let (prover, verifier) = fuzz_random_circuit(seed, None);
+            prover.unwrap();
+            verifier.unwrap();
+
+            let (prover, verifier) = fuzz_random_circuit(seed, Some(seed as usize));
+            assert!(prover.is_err());
+            assert!(verifier.is_err());
+        }
+    }
+
+    // Return the estimated round trips of the prover and the verifier for 20
+    // assertions and 5 multiplications.
+    fn run_round_trips(no_batching: bool) -> (usize, usize) {
+        run_channels(
+            move |channel, rng| {
+                let mut dmc = DietMacAndCheeseProver::<F61p, _, _>::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    no_batching,
+                )
+                .unwrap();
+                let x = dmc.input_private(F61p::ZERO).unwrap();
+                for _ in 0..5 {
+                    dmc.mul(&x, &x).unwrap();
+                }
+                for _ in 0..20 {
+                    dmc.assert_zero(&x).unwrap();
+                }
+                let estimate = dmc.estimated_round_trips();
+                dmc.finalize().unwrap();
+                estimate
+            },
+            |channel, rng| {
+                let mut dmc = DietMacAndCheeseVerifier::<F61p, _, _>::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    no_batching,
+                )
+                .unwrap();
+                let x = dmc.input_private().unwrap();
+                for _ in 0..5 {
+                    dmc.mul(&x, &x).unwrap();
+                }
+                for _ in 0..20 {
+                    dmc.assert_zero(&x).unwrap();
+                }
+                let estimate = dmc.estimated_round_trips();
+                dmc.finalize().unwrap();
+                estimate
+            },
+        )
+    }
+
+    #[test]
+    fn test_estimated_round_trips() {
+        // One zero check and one multiplication check on `finalize`.
+        assert_eq!(run_round_trips(false), (2, 2));
+        // One zero check per assertion, and the final checks.
+        assert_eq!(run_round_trips(true), (22, 22));
+
+        // Chunks of 4 values when 10 values are queued, and the remaining 5 values
+        // on `finalize`, along with the multiplication check.
+        let chunked = Chunked {
+            capacity: 10,
+            chunk: 4,
+        };
+        assert_eq!(
+            estimated_round_trips(25, 0, false, &chunked, None),
+            3 + 3 + 2 + 1
+        );
+        assert_eq!(
+            estimated_round_trips(0, 25, false, &chunked, Some(10)),
+            1 + 3
+        );
+    }
+
+    #[test]
+    fn test_fold_public_affine_chain() {
+        // `((3 + 4) * 5 + 1) * 2`, on a public and on a private value.
+        let chain = |x: F61p| ((x + f61p(4)) * f61p(5) + f61p(1)) * f61p(2);
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                let mut outs = Vec::new();
+                for x in [dmc.input_public(f61p(3)), dmc.input_private(f61p(3))?] {
+                    let y = dmc.addc(&x, f61p(4))?;
+                    let y = dmc.mulc(&y, f61p(5))?;
+                    let y = dmc.addc(&y, f61p(1))?;
+                    let y = dmc.mulc(&y, f61p(2))?;
+                    let zero = dmc.addc(&y, -chain(f61p(3)))?;
+                    dmc.assert_zero(&zero)?;
+                    outs.push(y);
+                }
+                assert_eq!(outs[0].value(), outs[1].value());
+                assert_eq!(outs[0].mac(), F61p::ZERO);
+                Ok(dmc.stats())
+            },
+            move |dmc| {
+                for x in [dmc.input_public(f61p(3)), dmc.input_private()?] {
+                    let y = dmc.addc(&x, f61p(4))?;
+                    let y = dmc.mulc(&y, f61p(5))?;
+                    let y = dmc.addc(&y, f61p(1))?;
+                    let y = dmc.mulc(&y, f61p(2))?;
+                    let zero = dmc.addc(&y, -chain(f61p(3)))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(dmc.stats())
+            },
+        );
+        let prover = prover.unwrap();
+        let verifier = verifier.unwrap();
+        // The operations on the public value are folded by the prover only, but
+        // still counted by both parties.
+        assert_eq!(prover.folded, 5);
+        assert_eq!(verifier.folded, 0);
+        assert_eq!(verifier.add_constant, 6);
+        assert_eq!(verifier.mul_constant, 4);
+        assert_eq!(
+            CircuitStats {
+                folded: 0,
+                flush_time_check_zero: verifier.flush_time_check_zero,
+                flush_time_mult_check: verifier.flush_time_mult_check,
+                ..prover
+            },
+            verifier
+        );
+    }
+
+    #[test]
+    fn test_mult_state_bytes_is_constant() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(2))?;
+                let bytes = dmc.mult_state_bytes();
+                assert!(bytes > 0);
+                for _ in 0..1000 {
+                    dmc.mul(&x, &x)?;
+                }
+                assert_eq!(dmc.mult_state_bytes(), bytes);
+                Ok(())
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let bytes = dmc.mult_state_bytes();
+                for _ in 0..1000 {
+                    dmc.mul(&x, &x)?;
+                }
+                assert_eq!(dmc.mult_state_bytes(), bytes);
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    // A flush hook recording the kind and length of each flush in `events`.
+    fn record_flushes(events: &Arc<Mutex<Vec<(FlushKind, usize)>>>) -> FlushHook {
+        let events = events.clone();
+        Box::new(move |event: FlushEvent| {
+            // The test channel does not count its bytes.
+            assert_eq!(event.bytes, None);
+            events.lock().unwrap().push((event.kind, event.batch_len));
+        })
+    }
+
+    fn record_progress(reports: &Arc<Mutex<Vec<CircuitStats>>>) -> ProgressCallback {
+        let reports = reports.clone();
+        Box::new(move |stats: &CircuitStats| reports.lock().unwrap().push(*stats))
+    }
+
+    #[test]
+    fn test_progress_callback() {
+        let prover_reports = Arc::new(Mutex::new(Vec::new()));
+        let verifier_reports = Arc::new(Mutex::new(Vec::new()));
+        let reports = prover_reports.clone();
+        // One private input, then enough additions to reach two ticks.
+        let interval = 5;
+        let adds = 2 * interval - 1;
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_progress_interval(interval);
+                dmc.set_progress_callback(record_progress(&reports));
+                let x = dmc.input_private(F61p::ZERO)?;
+                for _ in 0..adds {
+                    dmc.add(&x, &x)?;
+                }
+                Ok(())
+            },
+            |dmc| {
+                dmc.set_progress_interval(interval);
+                dmc.set_progress_callback(record_progress(&verifier_reports));
+                let x = dmc.input_private()?;
+                for _ in 0..adds {
+                    dmc.add(&x, &x)?;
+                }
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+
+        for reports in [prover_reports, verifier_reports] {
+            let reports = reports.lock().unwrap();
+            assert_eq!(reports.len(), 2);
+            for (i, stats) in reports.iter().enumerate() {
+                assert_eq!(stats.private_inputs, 1);
+                assert_eq!(stats.add, (i + 1) * interval - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_comm_stats() {
+        let (sender, receiver) = UnixStream::pair().unwrap();
+        let handle = std::thread::spawn(move || {
+            let rng = AesRng::from_seed(Default::default());
+            let reader = BufReader::new(sender.try_clone().unwrap());
+            let writer = BufWriter::new(sender);
+            let mut channel = TrackChannel::new(Channel::new(reader, writer));
+            let mut dmc = DietMacAndCheeseProver::<F61p, _, _>::init(
+                &mut channel,
+                rng,
+                LPN_SETUP_SMALL,
+                LPN_EXTEND_SMALL,
+                false,
+            )
+            .unwrap();
+            let x = dmc.input_private(f61p(3)).unwrap();
+            let xx = dmc.mul(&x, &x).unwrap();
+            let zero = dmc.addc(&xx, -f61p(9)).unwrap();
+            dmc.assert_zero(&zero).unwrap();
+            dmc.finalize().unwrap();
+            dmc.comm_stats().unwrap()
+        });
+        let rng = AesRng::from_seed(Default::default());
+        let reader = BufReader::new(receiver.try_clone().unwrap());
+        let writer = BufWriter::new(receiver);
+        let mut channel = TrackChannel::new(Channel::new(reader, writer));
+        let mut dmc = DietMacAndCheeseVerifier::<F61p, _, _>::init(
+            &mut channel,
+            rng,
+            LPN_SETUP_SMALL,
+            LPN_EXTEND_SMALL,
+            false,
+        )
+        .unwrap();
+        let x = dmc.input_private().unwrap();
+        let xx = dmc.mul(&x, &x).unwrap();
+        let zero = dmc.addc(&xx, -f61p(9)).unwrap();
+        dmc.assert_zero(&zero).unwrap();
+        dmc.finalize().unwrap();
+        let verifier = dmc.comm_stats().unwrap();
+        let prover = handle.join().unwrap();
+
+        // The counts include the sVOLE setup.
+        assert!(prover.bytes_sent > 0 && verifier.bytes_sent > 0);
+        assert_eq!(prover.bytes_sent, verifier.bytes_received);
+        assert_eq!(prover.bytes_received, verifier.bytes_sent);
+
+        // An untracked channel does not count its bytes.
+        let (prover, verifier) =
+            run::<F61p, _, _>(|dmc| Ok(dmc.comm_stats()), |dmc| Ok(dmc.comm_stats()));
+        assert_eq!(prover.unwrap(), None);
+        assert_eq!(verifier.unwrap(), None);
+    }
+
+    #[test]
+    fn test_progress_interval() {
+        let prover_reports = Arc::new(Mutex::new(Vec::new()));
+        let verifier_reports = Arc::new(Mutex::new(Vec::new()));
+        let reports = prover_reports.clone();
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_progress_interval(4);
+                dmc.set_progress_callback(record_progress(&reports));
+                let x = dmc.input_private(F61p::ZERO)?;
+                for _ in 0..9 {
+                    dmc.add(&x, &x)?;
+                }
+                Ok(())
+            },
+            |dmc| {
+                dmc.set_progress_interval(4);
+                dmc.set_progress_callback(record_progress(&verifier_reports));
+                let x = dmc.input_private()?;
+                for _ in 0..9 {
+                    dmc.add(&x, &x)?;
+                }
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+
+        for reports in [prover_reports, verifier_reports] {
+            let adds: Vec<_> = reports.lock().unwrap().iter().map(|s| s.add).collect();
+            assert_eq!(adds, vec![3, 7]);
+        }
+    }
+
+    #[test]
+    fn test_gate_rate() {
+        let mut monitor = Monitor::default();
+        assert_eq!(monitor.gate_rate(), 0.0);
+        monitor.incr_monitor_add();
+        let rate = monitor.gate_rate();
+        assert!(rate.is_finite() && rate >= 0.0);
+    }
+
+    #[test]
+    fn test_flush_hook_counts_bytes() {
+        let bytes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = bytes.clone();
+        run_channels(
+            move |channel, rng| {
+                let mut channel = TrackChannel::new(channel.clone());
+                let mut dmc = DietMacAndCheeseProver::<F61p, _, _>::init(
+                    &mut channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                dmc.set_flush_hook(Box::new(move |event: FlushEvent| {
+                    recorded.lock().unwrap().push(event.bytes.unwrap());
+                }));
+                let x = dmc.input_private(F61p::ZERO).unwrap();
+                dmc.mul(&x, &x).unwrap();
+                dmc.assert_zero(&x).unwrap();
+                dmc.finalize().unwrap();
+            },
+            |channel, rng| {
+                let mut dmc = DietMacAndCheeseVerifier::<F61p, _, _>::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                let x = dmc.input_private().unwrap();
+                dmc.mul(&x, &x).unwrap();
+                dmc.assert_zero(&x).unwrap();
+                dmc.finalize().unwrap();
+            },
+        );
+        // The zero check and the multiplication check both communicate.
+        let bytes = bytes.lock().unwrap();
+        assert_eq!(bytes.len(), 2);
+        assert!(bytes.iter().all(|b| *b > 0));
+    }
+
+    #[test]
+    fn test_flush_hook_counts_flushes() {
+        let prover_events = Arc::new(Mutex::new(Vec::new()));
+        let verifier_events = Arc::new(Mutex::new(Vec::new()));
+        let events = prover_events.clone();
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_zero_check_schedule(Box::new(Batched { capacity: 3 }));
+                dmc.set_flush_hook(record_flushes(&events));
+                let x = dmc.input_private(F61p::ZERO)?;
+                for _ in 0..2 {
+                    dmc.mul(&x, &x)?;
+                }
+                for _ in 0..7 {
+                    dmc.assert_zero(&x)?;
+                }
+                Ok(())
+            },
+            |dmc| {
+                dmc.set_zero_check_schedule(Box::new(Batched { capacity: 3 }));
+                dmc.set_flush_hook(record_flushes(&verifier_events));
+                let x = dmc.input_private()?;
+                for _ in 0..2 {
+                    dmc.mul(&x, &x)?;
+                }
+                for _ in 0..7 {
+                    dmc.assert_zero(&x)?;
+                }
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+
+        // Two full batches, then the remaining value and the multiplications on
+        // `finalize`.
+        let expected = vec![
+            (FlushKind::CheckZero, 3),
+            (FlushKind::CheckZero, 3),
+            (FlushKind::CheckZero, 1),
+            (FlushKind::MultCheck, 2),
+        ];
+        assert_eq!(*prover_events.lock().unwrap(), expected);
+        assert_eq!(*verifier_events.lock().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_reset_proof_state_keeps_monitor() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                for i in 1..=3 {
+                    let x = dmc.input_private(F61p::ZERO)?;
+                    let y = dmc.mul(&x, &x)?;
+                    dmc.assert_zero(&y)?;
+                    dmc.finalize()?;
+                    dmc.reset_proof_state();
+                    assert_eq!(dmc.monitor.monitor_mul, i);
+                    assert_eq!(dmc.monitor.monitor_zk_mult_check, i);
+                    assert_eq!(dmc.monitor.monitor_zk_check_zero, i);
+                }
+                Ok(())
+            },
+            |dmc| {
+                for i in 1..=3 {
+                    let x = dmc.input_private()?;
+                    let y = dmc.mul(&x, &x)?;
+                    dmc.assert_zero(&y)?;
+                    dmc.finalize()?;
+                    dmc.reset_proof_state();
+                    assert_eq!(dmc.monitor.monitor_mul, i);
+                    assert_eq!(dmc.monitor.monitor_zk_mult_check, i);
+                    assert_eq!(dmc.monitor.monitor_zk_check_zero, i);
+                }
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    // Prove `x^2 == y` for the private `x`, with the public `y`.
+    fn square_statement<B: BackendT<FieldElement = F61p>>(
+        dmc: &mut B,
+        x: Option<F61p>,
+        y: F61p,
+    ) -> Result<()> {
+        let x = dmc.input_private(x)?;
+        let xx = dmc.mul(&x, &x)?;
+        let zero = dmc.add_constant(&xx, -y)?;
+        dmc.assert_zero(&zero)
+    }
+
+    // Prove that `3^2 == y`, with both parties set to finalize on drop and
+    // dropped without calling `finalize`.
+    fn run_finalize_on_drop(y: u64) -> (DropError, DropError) {
+        run_channels(
+            move |channel, rng| {
+                let mut dmc = DietMacAndCheeseProver::<F61p, _, _>::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                let drop_error = dmc.finalize_on_drop();
+                let x = dmc.input_private(f61p(3)).unwrap();
+                let xx = dmc.mul(&x, &x).unwrap();
+                let zero = dmc.addc(&xx, -f61p(y)).unwrap();
+                dmc.assert_zero(&zero).unwrap();
+                drop_error
+            },
+            move |channel, rng| {
+                let mut dmc = DietMacAndCheeseVerifier::<F61p, _, _>::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                let drop_error = dmc.finalize_on_drop();
+                let x = dmc.input_private().unwrap();
+                let xx = dmc.mul(&x, &x).unwrap();
+                let zero = dmc.addc(&xx, -f61p(y)).unwrap();
+                dmc.assert_zero(&zero).unwrap();
+                drop_error
+            },
+        )
+    }
+
+    #[test]
+    fn test_finalize_on_drop() {
+        let (prover, verifier) = run_finalize_on_drop(9);
+        assert!(prover.take_drop_error().is_none());
+        assert!(verifier.take_drop_error().is_none());
+
+        let (_, verifier) = run_finalize_on_drop(10);
+        let e = verifier.take_drop_error().unwrap();
+        assert_eq!(
+            e.downcast_ref::<DmcError>(),
+            Some(&DmcError::CheckZeroFailed)
+        );
+        assert!(verifier.take_drop_error().is_none());
+    }
+
+    #[test]
+    fn test_reset_session() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                square_statement(dmc, Some(f61p(3)), f61p(9))?;
+                dmc.finalize()?;
+                dmc.reset_session();
+                assert_eq!(dmc.stats(), CircuitStats::default());
+                assert_eq!(dmc.max_witness_value(), F61p::ZERO);
+
+                square_statement(dmc, Some(f61p(4)), f61p(16))?;
+                let second = dmc.stats();
+                dmc.finalize()?;
+                dmc.reset_session();
+                // A third, false statement is rejected, and the session is reset.
+                let r = square_statement(dmc, Some(f61p(5)), f61p(24)).and_then(|_| dmc.finalize());
+                assert!(r.is_err());
+                dmc.reset_session();
+                Ok(second)
+            },
+            |dmc| {
+                square_statement(dmc, None, f61p(9))?;
+                dmc.finalize()?;
+                dmc.reset_session();
+                assert_eq!(dmc.stats(), CircuitStats::default());
+
+                square_statement(dmc, None, f61p(16))?;
+                let second = dmc.stats();
+                dmc.finalize()?;
+                dmc.reset_session();
+                let r = square_statement(dmc, None, f61p(24)).and_then(|_| dmc.finalize());
+                assert!(r.is_err());
+                dmc.reset_session();
+                Ok(second)
+            },
+        );
+        for stats in [prover.unwrap(), verifier.unwrap()] {
+            assert_eq!(stats.private_inputs, 1);
+            assert_eq!(stats.mul, 1);
+            assert_eq!(stats.assert_zero, 1);
+        }
+    }
+
+    fn dmc_error<T: std::fmt::Debug>(r: Result<T>) -> Option<DmcError> {
+        r.unwrap_err().downcast_ref::<DmcError>().copied()
+    }
+
+    #[test]
+    fn test_dmc_error_kinds() {
+        assert_eq!(
+            dmc_error(from_bytes_le::<F61p>(&[0xff; 9])),
+            Some(DmcError::InvalidFieldElement)
+        );
+        assert_eq!(
+            dmc_error(from_bytes_le::<F61p>(&[0xff; 8])),
+            Some(DmcError::InvalidFieldElement)
+        );
+
+        // A false statement fails the zero check, and the party may not be used
+        // until it is reset.
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let r = square_statement(dmc, Some(f61p(3)), f61p(10)).and_then(|_| dmc.finalize());
+                assert_eq!(dmc_error(r), Some(DmcError::CheckZeroFailed));
+                assert_eq!(
+                    dmc_error(dmc.input_private(f61p(1))),
+                    Some(DmcError::UsedAfterError)
+                );
+                dmc.reset_session();
+                Ok(())
+            },
+            |dmc| {
+                let r = square_statement(dmc, None, f61p(10)).and_then(|_| dmc.finalize());
+                assert_eq!(dmc_error(r), Some(DmcError::CheckZeroFailed));
+                assert_eq!(
+                    dmc_error(dmc.input_private()),
+                    Some(DmcError::UsedAfterError)
+                );
+                dmc.reset_session();
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+
+        // An invalid multiplication triple fails the multiplication check.
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                let z = dmc.input_private(f61p(10))?;
+                dmc.prover
+                    .get_refmut()
+                    .quicksilver_push(&mut dmc.state_mult_check, &(x, x, z))?;
+                Ok(())
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                dmc.mul(&x, &x)?;
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        assert_eq!(dmc_error(verifier), Some(DmcError::MultCheckFailed));
+
+        // The errors of the channel are I/O errors.
+        let (prover, verifier) = run::<F61p, _, _>(
+            |_| -> Result<()> { Err(eyre::eyre!("the prover gives up")) },
+            |dmc| dmc.input_private(),
+        );
+        assert!(prover.is_err());
+        let verifier = verifier.unwrap_err();
+        assert!(is_io_error(&verifier));
+        assert!(verifier.downcast_ref::<DmcError>().is_none());
+        assert!(!is_io_error(&eyre::Report::new(DmcError::CheckZeroFailed)));
+    }
+
+    // Emulate a multiplication gate which forgets to push its triple to the
+    // multiplication check.
+    #[test]
+    fn test_strict_mult_check_detects_unchecked_multiplication() {
+        fn is_unchecked(r: Result<()>) -> bool {
+            r.unwrap_err().downcast_ref::<DmcError>()
+                == Some(&DmcError::UncheckedMultiplications(1))
+        }
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                dmc.set_strict_mult_check(true);
+                let x = dmc.input_private(f61p(3))?;
+                dmc.mul(&x, &x)?;
+                dmc.input_private(f61p(9))?;
+                dmc.monitor.incr_monitor_mul();
+                Ok(())
+            },
+            |dmc| {
+                dmc.set_strict_mult_check(true);
+                let x = dmc.input_private()?;
+                dmc.mul(&x, &x)?;
+                dmc.input_private()?;
+                dmc.monitor.incr_monitor_mul();
+                Ok(())
+            },
+        );
+        assert!(is_unchecked(prover));
+        assert!(is_unchecked(verifier));
+    }
+
+    #[test]
+    fn test_mult_check_discrepancy_without_strict_mode() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                dmc.mul(&x, &x)?;
+                dmc.monitor.incr_monitor_mul();
+                dmc.finalize()?;
+                assert_eq!(dmc.mult_check_discrepancy(), 1);
+                Ok(())
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                dmc.mul(&x, &x)?;
+                dmc.monitor.incr_monitor_mul();
+                dmc.finalize()?;
+                assert_eq!(dmc.mult_check_discrepancy(), 1);
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    // Use the wire `x = 3` of a proof in a sub-proof, with the prover substituting
+    // its value with `forged` if set.
+    fn test_shared_wire(forged: Option<u64>) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)?;
+                let mut sub = dmc.sub_proof(AesRng::from_seed(Default::default()))?;
+                let x = match forged {
+                    Some(v) => MacProver::new(f61p(v), x.mac()),
+                    None => x,
+                };
+                let zero = sub.addc(&x, -x.value())?;
+                let r = sub.assert_zero(&zero).and_then(|_| sub.finalize());
+                if r.is_err() {
+                    sub.reset();
+                }
+                r
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)?;
+                let mut sub = dmc.sub_proof(AesRng::from_seed(Default::default()))?;
+                let zero = sub.addc(&x, -f61p(forged.unwrap_or(3)))?;
+                let r = sub.assert_zero(&zero).and_then(|_| sub.finalize());
+                if r.is_err() {
+                    sub.reset();
+                }
+                r
+            },
+        )
+    }
+
+    #[test]
+    fn test_sub_proof_shares_wire() {
+        let (prover, verifier) = test_shared_wire(None);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_sub_proof_substituted_wire_fails() {
+        let (prover, verifier) = test_shared_wire(Some(4));
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    // Check that `text` is in the Prometheus text format, and return its
+    // samples by metric name and labels.
+    fn parse_prometheus(text: &str) -> HashMap<String, f64> {
+        let is_name = |name: &str| {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        };
+        let mut samples = HashMap::new();
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                let mut words = comment.splitn(3, ' ');
+                let keyword = words.next().unwrap();
+                assert!(keyword == "HELP" || keyword == "TYPE", "{}", line);
+                assert!(is_name(words.next().unwrap()), "{}", line);
+                continue;
+            }
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            let value: f64 = value.parse().unwrap();
+            let (name, labels) = series.split_once('{').unwrap();
+            assert!(is_name(name), "{}", line);
+            let labels = labels.strip_suffix('}').unwrap();
+            for label in labels.split(',') {
+                let (key, value) = label.split_once('=').unwrap();
+                assert!(is_name(key), "{}", line);
+                assert!(value.len() >= 2 && value.starts_with('"') && value.ends_with('"'));
+            }
+            samples.insert(series.to_string(), value);
+        }
+        samples
+    }
+
+    #[test]
+    fn test_stats_prometheus() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)?;
+                dmc.finalize()?;
+                Ok(dmc.stats_prometheus())
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)?;
+                dmc.finalize()?;
+                Ok(dmc.stats_prometheus())
+            },
+        );
+        for (text, party) in [(prover.unwrap(), "prover"), (verifier.unwrap(), "verifier")] {
+            let samples = parse_prometheus(&text);
+            let sample = |name: &str, label: &str| {
+                samples[&format!("{}{{party=\"{}\",{}}}", name, party, label)]
+            };
+            assert_eq!(sample("dmc_gates_total", "gate=\"input_private\""), 1.0);
+            assert_eq!(sample("dmc_gates_total", "gate=\"mul\""), 1.0);
+            assert_eq!(sample("dmc_gates_total", "gate=\"assert_zero\""), 1.0);
+            assert_eq!(sample("dmc_checked_total", "kind=\"mult_check\""), 1.0);
+            assert_eq!(sample("dmc_flushes_total", "kind=\"check_zero\""), 1.0);
+            assert_eq!(sample("dmc_flushes_total", "kind=\"mult_check\""), 1.0);
+            assert!(sample("dmc_flush_seconds_total", "kind=\"check_zero\"") >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_queue_capacity() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                dmc.set_queue_capacity(NonZeroUsize::new(2).unwrap());
+                let x = dmc.input_private(f61p(3))?;
+                for _ in 0..5 {
+                    let zero = dmc.addc(&x, -f61p(3))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(dmc.stats().flushes_check_zero)
+            },
+            |dmc| {
+                dmc.set_queue_capacity(NonZeroUsize::new(2).unwrap());
+                let x = dmc.input_private()?;
+                for _ in 0..5 {
+                    let zero = dmc.addc(&x, -f61p(3))?;
+                    dmc.assert_zero(&zero)?;
+                }
+                Ok(dmc.stats().flushes_check_zero)
+            },
+        );
+        assert_eq!(prover.unwrap(), 2);
+        assert_eq!(verifier.unwrap(), 2);
+    }
+
+    #[test]
+    fn test_sub() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(7))?;
+                let y = dmc.input_private(f61p(3))?;
+                let diff = dmc.sub(&x, &y)?;
+                let zero = dmc.addc(&diff, -f61p(4))?;
+                dmc.assert_zero(&zero)?;
+                Ok(dmc.stats())
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let y = dmc.input_private()?;
+                let diff = dmc.sub(&x, &y)?;
+                let zero = dmc.addc(&diff, -f61p(4))?;
+                dmc.assert_zero(&zero)?;
+                Ok(dmc.stats())
+            },
+        );
+        for stats in [prover.unwrap(), verifier.unwrap()] {
+            assert_eq!(stats.sub, 1);
+            assert_eq!(stats.mul_constant, 0);
+        }
+
+        // In characteristic two, `y - x = x - y = x + y`.
+        let (prover, verifier) = run::<F40b, _, _>(
+            |dmc| {
+                let x = dmc.input_private(F2::ONE)?;
+                let y = dmc.input_private(F2::ZERO)?;
+                let diff = dmc.sub(&y, &x)?;
+                assert_eq!(diff.value(), F2::ONE);
+                dmc.assert_eq(&diff, &x)
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let y = dmc.input_private()?;
+                let diff = dmc.sub(&y, &x)?;
+                dmc.assert_eq(&diff, &x)
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    // Divide the private `a` by the private `b`, and check the quotient and
+    // its negation against their expected values.
+    fn run_div(a: F61p, b: F61p) -> (Result<CircuitStats>, Result<CircuitStats>) {
+        let q = if b == F61p::ZERO {
+            F61p::ZERO
+        } else {
+            a * b.inverse()
+        };
+        run::<F61p, _, _>(
+            move |dmc| {
+                let a = dmc.input_private(a)?;
+                let b = dmc.input_private(b)?;
+                let quotient = dmc.div(&a, &b)?;
+                assert_eq!(quotient.value(), q);
+                let neg = dmc.neg(&quotient)?;
+                let zero = dmc.addc(&neg, q)?;
+                dmc.assert_zero(&zero)?;
+                Ok(dmc.stats())
+            },
+            move |dmc| {
+                let a = dmc.input_private()?;
+                let b = dmc.input_private()?;
+                let quotient = dmc.div(&a, &b)?;
+                let neg = dmc.neg(&quotient)?;
+                let zero = dmc.addc(&neg, q)?;
+                dmc.assert_zero(&zero)?;
+                Ok(dmc.stats())
+            },
+        )
+    }
+
+    #[test]
+    fn test_div() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let mut pairs = vec![(f61p(12), F61p::ONE), (F61p::ZERO, f61p(5))];
+        pairs.extend((0..8).map(|_| (F61p::random(&mut rng), F61p::random_nonzero(&mut rng))));
+        for (a, b) in pairs {
+            let (prover, verifier) = run_div(a, b);
+            for stats in [prover.unwrap(), verifier.unwrap()] {
+                assert_eq!(stats.mul, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_by_zero_fails() {
+        let (prover, verifier) = run_div(f61p(3), F61p::ZERO);
+        assert_eq!(dmc_error(prover), Some(DmcError::DivisionByZero));
+        assert!(verifier.is_err());
+
+        // The prover may not be used until it is reset.
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let a = dmc.input_private(f61p(3))?;
+                let b = dmc.input_private(F61p::ZERO)?;
+                assert_eq!(dmc_error(dmc.div(&a, &b)), Some(DmcError::DivisionByZero));
+                assert_eq!(
+                    dmc_error(dmc.input_private(f61p(1))),
+                    Some(DmcError::UsedAfterError)
+                );
+                dmc.reset_session();
+                Ok(())
+            },
+            |dmc| {
+                dmc.input_private()?;
+                dmc.input_private()?;
+                dmc.reset_session();
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    // Return the sum of the products of `a` and `b`, with one multiplication each.
+    fn naive_dot_product<B: BackendT>(
+        backend: &mut B,
+        a: &[B::Wire],
+        b: &[B::Wire],
+    ) -> Result<B::Wire> {
+        let mut sum = backend.constant(B::FieldElement::ZERO)?;
+        for (a, b) in a.iter().zip(b) {
+            let product = backend.mul(a, b)?;
+            sum = backend.add(&sum, &product)?;
+        }
+        Ok(sum)
+    }
+
+    #[test]
+    fn test_dot_product() {
+        const N: usize = 10;
+        let mut rng = AesRng::from_seed(Default::default());
+        let values: Vec<F61p> = (0..2 * N).map(|_| F61p::random(&mut rng)).collect();
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                let wires = values
+                    .iter()
+                    .map(|v| dmc.input_private(*v))
+                    .collect::<Result<Vec<_>>>()?;
+                let (a, b) = wires.split_at(N);
+                let dot = dmc.dot_product(a, b)?;
+                let naive = naive_dot_product(dmc, a, b)?;
+                dmc.assert_eq(&dot, &naive)?;
+                Ok(dmc.stats())
+            },
+            |dmc| {
+                let wires = (0..2 * N)
+                    .map(|_| dmc.input_private())
+                    .collect::<Result<Vec<_>>>()?;
+                let (a, b) = wires.split_at(N);
+                let dot = dmc.dot_product(a, b)?;
+                let naive = naive_dot_product(dmc, a, b)?;
+                dmc.assert_eq(&dot, &naive)?;
+                Ok(dmc.stats())
+            },
+        );
+        // A single multiplication for the dot product, and `N` for the naive sum
+        // of products.
+        for stats in [prover.unwrap(), verifier.unwrap()] {
+            assert_eq!(stats.mul, N + 1);
+        }
+    }
+
+    // Assert that `x^2` equals the public `y` for a private `x = 3`.
+    fn run_assert_eq(y: u64) -> (Result<CircuitStats>, Result<CircuitStats>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                let xx = dmc.mul(&x, &x)?;
+                let y = dmc.input_public(f61p(y));
+                dmc.assert_eq(&xx, &y)?;
+                Ok(dmc.stats())
+            },
+            move |dmc| {
+                let x = dmc.input_private()?;
+                let xx = dmc.mul(&x, &x)?;
+                let y = dmc.input_public(f61p(y));
+                dmc.assert_eq(&xx, &y)?;
+                Ok(dmc.stats())
+            },
+        )
+    }
+
+    fn run_assert_zero_many(
+        bad: Option<usize>,
+        events: &Arc<Mutex<Vec<(FlushKind, usize)>>>,
+    ) -> (Result<CircuitStats>, Result<()>) {
+        let prover_events = events.clone();
+        run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_zero_check_schedule(Box::new(Batched { capacity: 3 }));
+                dmc.set_flush_hook(record_flushes(&prover_events));
+                let x = dmc.input_private(F61p::ZERO)?;
+                let mut values = vec![x; 7];
+                if let Some(bad) = bad {
+                    values[bad] = dmc.input_private(F61p::ONE)?;
+                }
+                dmc.assert_zero_many(&values)?;
+                Ok(dmc.stats())
+            },
+            |dmc| {
+                dmc.set_zero_check_schedule(Box::new(Batched { capacity: 3 }));
+                let x = dmc.input_private()?;
+                let mut values = vec![x; 7];
+                if let Some(bad) = bad {
+                    values[bad] = dmc.input_private()?;
+                }
+                dmc.assert_zero_many(&values)
+            },
+        )
+    }
+
+    #[test]
+    fn test_assert_zero_many() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let (prover, verifier) = run_assert_zero_many(None, &events);
+        verifier.unwrap();
+        assert_eq!(prover.unwrap().assert_zero, 7);
+        // The values are checked each time they fill the capacity of 3, as with
+        // `assert_zero`, leaving one value to check on `finalize`.
+        let expected = vec![
+            (FlushKind::CheckZero, 3),
+            (FlushKind::CheckZero, 3),
+            (FlushKind::CheckZero, 1),
+            (FlushKind::MultCheck, 0),
+        ];
+        assert_eq!(*events.lock().unwrap(), expected);
+
+        let (prover, verifier) = run_assert_zero_many(Some(5), &events);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    #[test]
+    fn test_assert_eq() {
+        let (prover, verifier) = run_assert_eq(9);
+        for stats in [prover.unwrap(), verifier.unwrap()] {
+            assert_eq!(stats.assert_zero, 1);
+            assert_eq!(stats.add, 0);
+            assert_eq!(stats.mul_constant, 0);
+        }
+
+        let (_, verifier) = run_assert_eq(10);
+        assert!(verifier.is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)?;
+                let before = dmc.stats();
+                dmc.finalize()?;
+                Ok((before, dmc.stats()))
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)?;
+                let before = dmc.stats();
+                dmc.finalize()?;
+                Ok((before, dmc.stats()))
+            },
+        );
+        for (before, after) in [prover.unwrap(), verifier.unwrap()] {
+            let gates = CircuitStats {
+                private_inputs: 1,
+                add_constant: 1,
+                mul: 1,
+                assert_zero: 1,
+                ..Default::default()
+            };
+            assert_eq!(before, gates);
+            assert_eq!(
+                after,
+                CircuitStats {
+                    checked_zero: 1,
+                    checked_mul: 1,
+                    flushes_check_zero: 1,
+                    flushes_mult_check: 1,
+                    flush_time_check_zero: after.flush_time_check_zero,
+                    flush_time_mult_check: after.flush_time_mult_check,
+                    ..gates
+                }
+            );
+        }
+    }
+
+    // Input 3 values one by one, then 2 values in a batch, under `budget`.
+    fn test_budget(budget: usize) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                dmc.set_input_budget(Some(budget));
+                for i in 0..3 {
+                    dmc.input_private(f61p(i))?;
+                }
+                dmc.input_private_batch(&[f61p(3), f61p(4)])?;
+                Ok(())
+            },
+            move |dmc| {
+                dmc.set_input_budget(Some(budget));
+                for _ in 0..3 {
+                    dmc.input_private()?;
+                }
+                dmc.input_private_batch(2)?;
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_input_budget() {
+        let (prover, verifier) = test_budget(5);
+        prover.unwrap();
+        verifier.unwrap();
+
+        for budget in [2, 4] {
+            let (prover, verifier) = test_budget(budget);
+            for r in [prover, verifier] {
+                assert_eq!(
+                    r.unwrap_err().downcast_ref::<DmcError>(),
+                    Some(&DmcError::BudgetExceeded)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_finalize_cancellable() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                let xx = dmc.mul(&x, &x)?;
+                dmc.assert_zero(&xx)?;
+                let r = dmc.finalize_cancellable(&AtomicBool::new(true));
+                assert_eq!(dmc.monitor.flushes_check_zero, 0);
+                assert_eq!(dmc.monitor.flushes_mult_check, 0);
+                r
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let xx = dmc.mul(&x, &x)?;
+                dmc.assert_zero(&xx)?;
+                let r = dmc.finalize_cancellable(&AtomicBool::new(true));
+                assert_eq!(dmc.monitor.flushes_check_zero, 0);
+                assert_eq!(dmc.monitor.flushes_mult_check, 0);
+                r
+            },
+        );
+        for r in [prover, verifier] {
+            assert_eq!(
+                r.unwrap_err().downcast_ref::<DmcError>(),
+                Some(&DmcError::Cancelled)
+            );
+        }
+
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                let zero = dmc.addc(&x, -f61p(3))?;
+                dmc.assert_zero(&zero)?;
+                dmc.finalize_cancellable(&AtomicBool::new(false))
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                let zero = dmc.addc(&x, -f61p(3))?;
+                dmc.assert_zero(&zero)?;
+                dmc.finalize_cancellable(&AtomicBool::new(false))
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    // A channel which sets `cancelled` on its first read once `armed` is set.
+    struct CancelOnRead<C> {
+        channel: C,
+        armed: Arc<AtomicBool>,
+        cancelled: Arc<AtomicBool>,
+    }
+
+    impl<C: AbstractChannel> AbstractChannel for CancelOnRead<C> {
+        fn read_bytes(&mut self, bytes: &mut [u8]) -> std::io::Result<()> {
+            if self.armed.load(Ordering::Relaxed) {
+                self.cancelled.store(true, Ordering::Relaxed);
+            }
+            self.channel.read_bytes(bytes)
+        }
+
+        fn write_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+            self.channel.write_bytes(bytes)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.channel.flush()
+        }
+
+        fn clone(&self) -> Self {
+            Self {
+                channel: self.channel.clone(),
+                armed: self.armed.clone(),
+                cancelled: self.cancelled.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_finalize_cancellable_between_chunks() {
+        let (prover, verifier) = run_channels(
+            |channel, rng| {
+                let armed = Arc::new(AtomicBool::new(false));
+                let cancelled = Arc::new(AtomicBool::new(false));
+                let mut channel = CancelOnRead {
+                    channel: channel.clone(),
+                    armed: armed.clone(),
+                    cancelled: cancelled.clone(),
+                };
+                let mut dmc = DietMacAndCheeseProver::<F61p, _, _>::init(
+                    &mut channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )?;
+                dmc.set_zero_check_schedule(Box::new(Chunked {
+                    capacity: 1000,
+                    chunk: 1,
+                }));
+                for _ in 0..3 {
+                    let zero = dmc.input_private(F61p::ZERO)?;
+                    dmc.assert_zero(&zero)?;
+                }
+                // The first chunk reads from the verifier, which cancels the
+                // remaining chunks.
+                armed.store(true, Ordering::Relaxed);
+                let r = dmc.finalize_cancellable(&cancelled);
+                assert_eq!(dmc.monitor.flushes_check_zero, 0);
+                r
+            },
+            |channel, rng| {
+                let mut dmc = DietMacAndCheeseVerifier::<F61p, _, _>::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )?;
+                dmc.set_zero_check_schedule(Box::new(Chunked {
+                    capacity: 1000,
+                    chunk: 1,
+                }));
+                for _ in 0..3 {
+                    let zero = dmc.input_private()?;
+                    dmc.assert_zero(&zero)?;
+                }
+                dmc.finalize()
+            },
+        );
+        assert_eq!(
+            prover.unwrap_err().downcast_ref::<DmcError>(),
+            Some(&DmcError::Cancelled)
+        );
+        assert!(verifier.is_err());
+    }
+
+    fn is_already_finalized(r: Result<()>) -> bool {
+        r.unwrap_err().downcast_ref::<DmcError>() == Some(&DmcError::AlreadyFinalized)
+    }
+
+    #[test]
+    fn test_gate_after_finalize_fails() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let x = dmc.input_private(f61p(3))?;
+                dmc.finalize()?;
+                dmc.finalize()?;
+                assert!(is_already_finalized(dmc.mul(&x, &x).map(|_| ())));
+                assert!(is_already_finalized(dmc.assert_zero(&x)));
+                dmc.reset();
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)
+            },
+            |dmc| {
+                let x = dmc.input_private()?;
+                dmc.finalize()?;
+                dmc.finalize()?;
+                assert!(is_already_finalized(dmc.mul(&x, &x).map(|_| ())));
+                assert!(is_already_finalized(dmc.assert_zero(&x)));
+                dmc.reset();
+                let xx = dmc.mul(&x, &x)?;
+                let zero = dmc.addc(&xx, -f61p(9))?;
+                dmc.assert_zero(&zero)
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_f61p() {
+        test::<F61p>();
+        test_challenge::<F61p>();
+    }
+
+    #[test]
+    fn test_f40b() {
+        test_challenge::<F40b>();
+    }
+
+    #[test]
+    fn test_challenges_f61p() {
+        let challenges = test_challenges::<F61p>();
+        for (i, c) in challenges.iter().enumerate() {
+            assert!(!challenges[i + 1..].contains(c));
+        }
+    }
+
+    #[test]
+    fn test_challenges_f40b() {
+        test_challenges::<F40b>();
     }
 }
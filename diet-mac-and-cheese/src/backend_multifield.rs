@@ -42,6 +42,7 @@ use std::path::PathBuf;
 // III) Memory. Structure for Wires and stack to support function calls
 // IV)  EvaluatorSingle. An evaluator for a single field
 // V)   EvaluatorMulti. An evaluator holding multiple single-field evaluators.
+// VI)  Mixed sessions. Arithmetic and boolean backends with conversions, finalized together.
 
 // NOTES: optimizations to consider
 //
@@ -215,6 +216,9 @@ impl<FE: FiniteField<PrimeField = FE>, C: AbstractChannel, RNG: CryptoRng + Rng>
     fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
         self.dmc.mul(a, b)
     }
+    fn div(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.dmc.div(a, b)
+    }
     fn add_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
         self.dmc.add_constant(a, b)
     }
@@ -398,13 +402,15 @@ impl<FE: FiniteField<PrimeField = FE>, C: AbstractChannel, RNG: CryptoRng + Rng>
     }
 
     fn finalize_conv(&mut self) -> Result<()> {
-        for (_key, edabits) in self.edabits_map.0.iter() {
+        // The edabits are taken out of the map, so that they are checked once
+        // even if the conversions are finalized again.
+        for (_key, edabits) in std::mem::take(&mut self.edabits_map.0) {
             self.conv.conv(
                 &mut self.dmc.channel,
                 &mut self.dmc.rng,
                 5,
                 5,
-                edabits,
+                &edabits,
                 None,
             )?;
         }
@@ -488,6 +494,9 @@ impl<FE: FiniteField<PrimeField = FE>, C: AbstractChannel, RNG: CryptoRng + Rng>
     fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
         self.dmc.mul(a, b)
     }
+    fn div(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.dmc.div(a, b)
+    }
     fn add_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
         self.dmc.add_constant(a, b)
     }
@@ -649,13 +658,15 @@ impl<FE: FiniteField<PrimeField = FE>, C: AbstractChannel, RNG: CryptoRng + Rng>
         Ok(mac)
     }
     fn finalize_conv(&mut self) -> Result<()> {
-        for (_key, edabits) in self.edabits_map.0.iter() {
+        // The edabits are taken out of the map, so that they are checked once
+        // even if the conversions are finalized again.
+        for (_key, edabits) in std::mem::take(&mut self.edabits_map.0) {
             self.conv.conv(
                 &mut self.dmc.channel,
                 &mut self.dmc.rng,
                 5,
                 5,
-                edabits,
+                &edabits,
                 None,
             )?;
         }
@@ -1007,7 +1018,7 @@ impl<C: AbstractChannel + 'static> EvaluatorCirc<C> {
                     self.load_backend(channel, rng, rng2, *field, *idx as usize, lpn_small)?;
                 }
                 _ => {
-                    todo!("Type not supported yet: {:?}", spec);
+                    return Err(eyre!("Type not supported yet: {:?}", spec));
                 }
             }
         }
@@ -1427,6 +1438,193 @@ impl<C: AbstractChannel + 'static> EvaluatorCirc<C> {
     }
 }
 
+// VI) Mixed sessions
+
+/// A builder of sessions proving circuits over an arithmetic field and over
+/// booleans, with conversions between them.
+///
+/// The two backends of a session share the channel and the sVOLE functionality
+/// over [`F40b`] used by the boolean wires and the conversions, and are finalized
+/// together with `finalize_all`. The prover and the verifier must build their
+/// sessions from builders with the same settings.
+///
+/// # Synchronization
+///
+/// The sub-protocols of a session are not independent, and the parties stay in
+/// sync as long as they follow this contract:
+///
+/// - Both backends write to the one channel, so the prover and the verifier
+///   must perform the same operations on `arith`, `binary` and the conversions
+///   in the same interleaving, not only the same operations on each backend.
+/// - Each backend checks its multiplications and zero assertions when its own
+///   queues fill up, and draws VOLEs from its own sVOLE (over `FE` for the
+///   arithmetic backend, over [`F40b`] for the boolean one), extending it when
+///   it runs out. With the same interleaving, both parties do so at the same
+///   points.
+/// - The random generator of a session is forked for each sub-protocol, so the
+///   coins of one do not depend on how much the other is used.
+/// - A conversion adds wires to both backends and its check involves both, so
+///   `finalize_all` runs the conversion checks before those of the backends.
+#[derive(Clone, Copy)]
+pub struct SessionBuilder {
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    no_batching: bool,
+}
+
+impl SessionBuilder {
+    /// Create a builder from a pair of LPN parameters as defined by svole.
+    pub fn new(lpn_setup: LpnParams, lpn_extend: LpnParams) -> Self {
+        Self {
+            lpn_setup,
+            lpn_extend,
+            no_batching: false,
+        }
+    }
+
+    /// Set whether the checks of the backends are performed eagerly.
+    pub fn no_batching(mut self, no_batching: bool) -> Self {
+        self.no_batching = no_batching;
+        self
+    }
+
+    /// Build a prover session over the arithmetic field `FE`.
+    pub fn build_prover<FE: FiniteField<PrimeField = FE>, C: AbstractChannel>(
+        &self,
+        channel: &mut C,
+        mut rng: AesRng,
+    ) -> Result<MixedSessionProver<FE, C>> {
+        let fcom_f2 = RcRefCell::new(FComProver::<F40b>::init(
+            channel,
+            &mut rng,
+            self.lpn_setup,
+            self.lpn_extend,
+        )?);
+        let conv = DietMacAndCheeseConvProver::init(
+            channel,
+            rng.fork(),
+            rng.fork(),
+            &fcom_f2,
+            self.lpn_setup,
+            self.lpn_extend,
+            self.no_batching,
+        )?;
+        Ok(MixedSessionProver { conv })
+    }
+
+    /// Build a verifier session over the arithmetic field `FE`.
+    pub fn build_verifier<FE: FiniteField<PrimeField = FE>, C: AbstractChannel>(
+        &self,
+        channel: &mut C,
+        mut rng: AesRng,
+    ) -> Result<MixedSessionVerifier<FE, C>> {
+        let fcom_f2 = RcRefCell::new(FComVerifier::<F40b>::init(
+            channel,
+            &mut rng,
+            self.lpn_setup,
+            self.lpn_extend,
+        )?);
+        let conv = DietMacAndCheeseConvVerifier::init(
+            channel,
+            rng.fork(),
+            rng.fork(),
+            &fcom_f2,
+            self.lpn_setup,
+            self.lpn_extend,
+            self.no_batching,
+        )?;
+        Ok(MixedSessionVerifier { conv })
+    }
+}
+
+/// The prover of a session built with [`SessionBuilder`].
+pub struct MixedSessionProver<FE: FiniteField, C: AbstractChannel> {
+    conv: DietMacAndCheeseConvProver<FE, C, AesRng>,
+}
+
+impl<FE: FiniteField<PrimeField = FE>, C: AbstractChannel> MixedSessionProver<FE, C> {
+    /// Return the backend over the arithmetic field.
+    pub fn arith(&mut self) -> &mut DietMacAndCheeseProver<FE, C, AesRng> {
+        &mut self.conv.dmc
+    }
+
+    /// Return the backend over booleans.
+    pub fn binary(&mut self) -> &mut DietMacAndCheeseProver<F40b, C, AesRng> {
+        &mut self.conv.dmc_f2
+    }
+
+    /// Convert an arithmetic wire to its boolean wires, least significant first.
+    pub fn to_bits(&mut self, wire: &MacProver<FE>) -> Result<Vec<MacProver<F40b>>> {
+        self.conv
+            .assert_conv_to_bits(wire)?
+            .into_iter()
+            .map(|bit| match bit {
+                MacBitGeneric::BitProver(m) => Ok(m),
+                _ => Err(eyre!("Expected a bit of the prover")),
+            })
+            .collect()
+    }
+
+    /// Convert boolean wires, least significant first, to an arithmetic wire.
+    pub fn from_bits(&mut self, bits: &[MacProver<F40b>]) -> Result<MacProver<FE>> {
+        let bits: Vec<_> = bits.iter().map(|m| MacBitGeneric::BitProver(*m)).collect();
+        self.conv.assert_conv_from_bits(&bits)
+    }
+
+    /// Finalize the session, running the conversion checks first, as they
+    /// involve values of both backends, followed by the checks of the arithmetic
+    /// and of the boolean backends.
+    pub fn finalize_all(&mut self) -> Result<()> {
+        self.conv.finalize_conv()?;
+        self.conv.finalize()
+    }
+}
+
+/// The verifier of a session built with [`SessionBuilder`].
+pub struct MixedSessionVerifier<FE: FiniteField, C: AbstractChannel> {
+    conv: DietMacAndCheeseConvVerifier<FE, C, AesRng>,
+}
+
+impl<FE: FiniteField<PrimeField = FE>, C: AbstractChannel> MixedSessionVerifier<FE, C> {
+    /// Return the backend over the arithmetic field.
+    pub fn arith(&mut self) -> &mut DietMacAndCheeseVerifier<FE, C, AesRng> {
+        &mut self.conv.dmc
+    }
+
+    /// Return the backend over booleans.
+    pub fn binary(&mut self) -> &mut DietMacAndCheeseVerifier<F40b, C, AesRng> {
+        &mut self.conv.dmc_f2
+    }
+
+    /// Convert an arithmetic wire to its boolean wires, least significant first.
+    pub fn to_bits(&mut self, wire: &MacVerifier<FE>) -> Result<Vec<MacVerifier<F40b>>> {
+        self.conv
+            .assert_conv_to_bits(wire)?
+            .into_iter()
+            .map(|bit| match bit {
+                MacBitGeneric::BitVerifier(m) => Ok(m),
+                _ => Err(eyre!("Expected a bit of the verifier")),
+            })
+            .collect()
+    }
+
+    /// Convert boolean wires, least significant first, to an arithmetic wire.
+    pub fn from_bits(&mut self, bits: &[MacVerifier<F40b>]) -> Result<MacVerifier<FE>> {
+        let bits: Vec<_> = bits
+            .iter()
+            .map(|m| MacBitGeneric::BitVerifier(*m))
+            .collect();
+        self.conv.assert_conv_from_bits(&bits)
+    }
+
+    /// Finalize the session, in the same order as
+    /// [`MixedSessionProver::finalize_all`].
+    pub fn finalize_all(&mut self) -> Result<()> {
+        self.conv.finalize_conv()?;
+        self.conv.finalize()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{RcRefCell, TypeStore};
@@ -1436,8 +1634,11 @@ mod tests {
             EvaluatorCirc,
             //F2_VEC, F384P_VEC, F384Q_VEC, F61P_VEC,
             Party,
+            SessionBuilder,
         },
+        backend_trait::BackendT,
         plugins::MuxV0,
+        test_utils::run_channels,
     };
     use crate::{
         circuit_ir::{CircInputs, FunStore, FuncDecl, GateM, WireId, WireRange},
@@ -2281,14 +2482,17 @@ mod tests {
                 .less_eq_than_with_public2(vec![zero].as_slice(), vec![F2::ZERO].as_slice())
                 .unwrap();
             party.dmc_f2.finalize().unwrap();
+            party.dmc_f2.reset();
             party
                 .less_eq_than_with_public2(vec![zero].as_slice(), vec![F2::ONE].as_slice())
                 .unwrap();
             party.dmc_f2.finalize().unwrap();
+            party.dmc_f2.reset();
             party
                 .less_eq_than_with_public2(vec![one].as_slice(), vec![F2::ONE].as_slice())
                 .unwrap();
             party.dmc_f2.finalize().unwrap();
+            party.dmc_f2.reset();
             party
                 .less_eq_than_with_public2(vec![one].as_slice(), vec![F2::ZERO].as_slice())
                 .unwrap();
@@ -2299,6 +2503,7 @@ mod tests {
                 .less_eq_than_with_public2(vec![zero].as_slice(), vec![F2::ZERO].as_slice())
                 .unwrap();
             party.dmc_f2.finalize().unwrap();
+            party.dmc_f2.reset();
 
             party
                 .less_eq_than_with_public2(
@@ -2307,6 +2512,7 @@ mod tests {
                 )
                 .unwrap();
             party.dmc_f2.finalize().unwrap();
+            party.dmc_f2.reset();
 
             party
                 .less_eq_than_with_public2(
@@ -2324,6 +2530,7 @@ mod tests {
                 )
                 .unwrap();
             party.dmc_f2.finalize().unwrap();
+            party.dmc_f2.reset();
 
             party
                 .less_eq_than_with_public2(
@@ -2332,6 +2539,7 @@ mod tests {
                 )
                 .unwrap();
             party.dmc_f2.finalize().unwrap();
+            party.dmc_f2.reset();
 
             party
                 .less_eq_than_with_public2(
@@ -2381,14 +2589,17 @@ mod tests {
             .less_eq_than_with_public2(vec![zero].as_slice(), vec![F2::ZERO].as_slice())
             .unwrap();
         party.dmc_f2.finalize().unwrap();
+        party.dmc_f2.reset();
         party
             .less_eq_than_with_public2(vec![zero].as_slice(), vec![F2::ONE].as_slice())
             .unwrap();
         party.dmc_f2.finalize().unwrap();
+        party.dmc_f2.reset();
         party
             .less_eq_than_with_public2(vec![one].as_slice(), vec![F2::ONE].as_slice())
             .unwrap();
         party.dmc_f2.finalize().unwrap();
+        party.dmc_f2.reset();
         party
             .less_eq_than_with_public2(vec![one].as_slice(), vec![F2::ZERO].as_slice())
             .unwrap();
@@ -2399,6 +2610,7 @@ mod tests {
             .less_eq_than_with_public2(vec![zero].as_slice(), vec![F2::ZERO].as_slice())
             .unwrap();
         party.dmc_f2.finalize().unwrap();
+        party.dmc_f2.reset();
 
         party
             .less_eq_than_with_public2(
@@ -2407,6 +2619,7 @@ mod tests {
             )
             .unwrap();
         party.dmc_f2.finalize().unwrap();
+        party.dmc_f2.reset();
 
         party
             .less_eq_than_with_public2(
@@ -2424,6 +2637,7 @@ mod tests {
             )
             .unwrap();
         party.dmc_f2.finalize().unwrap();
+        party.dmc_f2.reset();
 
         party
             .less_eq_than_with_public2(
@@ -2432,6 +2646,7 @@ mod tests {
             )
             .unwrap();
         party.dmc_f2.finalize().unwrap();
+        party.dmc_f2.reset();
 
         party
             .less_eq_than_with_public2(
@@ -2494,6 +2709,51 @@ mod tests {
         test_f2_mux_on_slices();
     }
 
+    // Square `x` in F61p, check in binary that the square is odd and that its
+    // bits 0 and 3 are set, and convert the bits back to F61p.
+    fn test_mixed_session(x: u64) -> (eyre::Result<()>, eyre::Result<()>) {
+        let builder = SessionBuilder::new(LPN_SETUP_SMALL, LPN_EXTEND_SMALL);
+        let square = F61p::try_from((x * x) as u128).unwrap();
+        run_channels(
+            move |channel, rng| {
+                let mut session = builder.build_prover::<F61p, _>(channel, rng)?;
+                let x = session
+                    .arith()
+                    .input_private(F61p::try_from(x as u128).unwrap())?;
+                let y = session.arith().mul(&x, &x)?;
+                let bits = session.to_bits(&y)?;
+                let and = session.binary().mul(&bits[0], &bits[3])?;
+                let not_and = session.binary().add_constant(&and, F2::ONE)?;
+                session.binary().assert_zero(&not_and)?;
+                let z = session.from_bits(&bits)?;
+                let diff = session.arith().add_constant(&z, -square)?;
+                session.arith().assert_zero(&diff)?;
+                session.finalize_all()
+            },
+            |channel, rng| {
+                let mut session = builder.build_verifier::<F61p, _>(channel, rng)?;
+                let x = session.arith().input_private()?;
+                let y = session.arith().mul(&x, &x)?;
+                let bits = session.to_bits(&y)?;
+                let and = session.binary().mul(&bits[0], &bits[3])?;
+                let not_and = session.binary().add_constant(&and, F2::ONE)?;
+                session.binary().assert_zero(&not_and)?;
+                let z = session.from_bits(&bits)?;
+                let diff = session.arith().add_constant(&z, -square)?;
+                session.arith().assert_zero(&diff)?;
+                session.finalize_all()
+            },
+        )
+    }
+
+    #[test]
+    fn test_mixed_session_finalizes_once() {
+        // 25 is 11001 in binary.
+        let (prover, verifier) = test_mixed_session(5);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
     #[test]
     fn test_less_eq_than_circuit() {
         test_less_eq_than_1();
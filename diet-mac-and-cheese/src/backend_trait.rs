@@ -2,6 +2,8 @@
 
 //! Core backend trait used for Diet Mac'n'Cheese.
 
+#[cfg(feature = "insecure-bench")]
+use crate::backend::AcceptAllVerifier;
 use crate::{
     backend::{from_bytes_le, DietMacAndCheeseProver, DietMacAndCheeseVerifier},
     homcom::{MacProver, MacVerifier},
@@ -22,13 +24,78 @@ pub trait BackendT {
     fn one(&self) -> Result<Self::FieldElement>;
     fn zero(&self) -> Result<Self::FieldElement>;
     fn copy(&mut self, wire: &Self::Wire) -> Result<Self::Wire>;
+    /// Return a public wire whose value is a random challenge.
+    ///
+    /// The challenge is sampled by the verifier after every value input so far
+    /// is committed, and the prover and the verifier agree on its value as long
+    /// as they request their challenges in the same order. This allows
+    /// combining many checks into one with a random linear combination, whose
+    /// soundness error is about the number of combined values divided by the
+    /// size of the field: challenges over a small field, e.g., `F2`, give no
+    /// meaningful soundness.
+    ///
+    /// ```
+    /// use diet_mac_and_cheese::backend_trait::BackendT;
+    /// use eyre::Result;
+    /// use scuttlebutt::ring::FiniteRing;
+    ///
+    /// /// Assert that `a` and `b` are equal with a single `assert_zero`, by
+    /// /// checking that `sum_i r^i (a_i - b_i)` is zero for a random `r`.
+    /// fn assert_all_eq<B: BackendT>(backend: &mut B, a: &[B::Wire], b: &[B::Wire]) -> Result<()> {
+    ///     // `a` and `b` are committed before the challenge is drawn.
+    ///     let r = backend.challenge()?;
+    ///     let mut acc = backend.constant(B::FieldElement::ZERO)?;
+    ///     for (a, b) in a.iter().zip(b) {
+    ///         let diff = backend.sub(a, b)?;
+    ///         let acc_r = backend.mul(&acc, &r)?;
+    ///         acc = backend.add(&acc_r, &diff)?;
+    ///     }
+    ///     backend.assert_zero(&acc)
+    /// }
+    /// ```
     fn challenge(&mut self) -> Result<Self::Wire>;
+    /// Return `n` independent challenges, obtained in a single interaction.
+    ///
+    /// Both parties must request the same number of challenges.
+    fn challenges(&mut self, n: usize) -> Result<Vec<Self::Wire>> {
+        (0..n).map(|_| self.challenge()).collect()
+    }
 
     fn constant(&mut self, val: Self::FieldElement) -> Result<Self::Wire>;
     fn assert_zero(&mut self, wire: &Self::Wire) -> Result<()>;
+    /// Assert that all of `wires` are zero.
+    ///
+    /// By default, this asserts that each wire is zero in turn.
+    fn assert_zero_many(&mut self, wires: &[Self::Wire]) -> Result<()> {
+        wires.iter().try_for_each(|wire| self.assert_zero(wire))
+    }
+    /// Assert that `a` and `b` are equal.
+    ///
+    /// By default, this asserts that `a - b` is zero.
+    fn assert_eq(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<()> {
+        let diff = self.sub(a, b)?;
+        self.assert_zero(&diff)
+    }
 
     fn add(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire>;
+    /// Subtract `b` from `a`.
+    ///
+    /// By default, `b` is negated by a multiplication by the constant `-1`.
+    fn sub(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        let minus_b = self.mul_constant(b, -Self::FieldElement::ONE)?;
+        self.add(a, &minus_b)
+    }
+    /// Negate `a`.
+    ///
+    /// By default, `a` is multiplied by the constant `-1`.
+    fn neg(&mut self, a: &Self::Wire) -> Result<Self::Wire> {
+        self.mul_constant(a, -Self::FieldElement::ONE)
+    }
     fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire>;
+    /// Divide `a` by `b`, which must be nonzero.
+    ///
+    /// This costs a single multiplication, checking that the quotient times `b` is `a`.
+    fn div(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire>;
     fn add_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire>;
     fn mul_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire>;
 
@@ -54,9 +121,11 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendT
     }
 
     fn challenge(&mut self) -> Result<Self::Wire> {
-        self.channel.flush()?;
-        let challenge = self.channel.read_serializable::<FE>()?;
-        Ok(MacProver::new(FE::PrimeField::ZERO, challenge))
+        self.challenge()
+    }
+
+    fn challenges(&mut self, n: usize) -> Result<Vec<Self::Wire>> {
+        self.challenges(n)
     }
 
     fn one(&self) -> Result<Self::FieldElement> {
@@ -75,14 +144,30 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendT
         self.assert_zero(wire)
     }
 
+    fn assert_zero_many(&mut self, wires: &[Self::Wire]) -> Result<()> {
+        self.assert_zero_many(wires)
+    }
+
+    fn assert_eq(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<()> {
+        self.assert_eq(a, b)
+    }
+
     fn add(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
         self.add(a, b)
     }
 
+    fn sub(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.sub(a, b)
+    }
+
     fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
         self.mul(a, b)
     }
 
+    fn div(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.div(a, b)
+    }
+
     fn add_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
         self.addc(a, b)
     }
@@ -126,9 +211,11 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendT
     }
 
     fn challenge(&mut self) -> Result<Self::Wire> {
-        let challenge = FE::random(&mut self.rng);
-        self.channel.write_serializable(&challenge)?;
-        Ok(MacVerifier::new(challenge))
+        self.challenge()
+    }
+
+    fn challenges(&mut self, n: usize) -> Result<Vec<Self::Wire>> {
+        self.challenges(n)
     }
 
     fn one(&self) -> Result<Self::FieldElement> {
@@ -147,14 +234,30 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendT
         self.assert_zero(wire)
     }
 
+    fn assert_zero_many(&mut self, wires: &[Self::Wire]) -> Result<()> {
+        self.assert_zero_many(wires)
+    }
+
+    fn assert_eq(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<()> {
+        self.assert_eq(a, b)
+    }
+
     fn add(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
         self.add(a, b)
     }
 
+    fn sub(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.sub(a, b)
+    }
+
     fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
         self.mul(a, b)
     }
 
+    fn div(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.div(a, b)
+    }
+
     fn add_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
         self.addc(a, b)
     }
@@ -181,3 +284,82 @@ impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendT
         self.reset();
     }
 }
+
+#[cfg(feature = "insecure-bench")]
+impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendT
+    for AcceptAllVerifier<FE, C, RNG>
+{
+    type Wire = MacVerifier<FE>;
+    type FieldElement = FE::PrimeField;
+
+    fn from_bytes_le(val: &[u8]) -> Result<Self::FieldElement> {
+        from_bytes_le(val)
+    }
+
+    fn copy(&mut self, wire: &Self::Wire) -> Result<Self::Wire> {
+        self.0.copy(wire)
+    }
+
+    fn challenge(&mut self) -> Result<Self::Wire> {
+        self.0.challenge()
+    }
+
+    fn challenges(&mut self, n: usize) -> Result<Vec<Self::Wire>> {
+        self.0.challenges(n)
+    }
+
+    fn one(&self) -> Result<Self::FieldElement> {
+        self.0.one()
+    }
+
+    fn zero(&self) -> Result<Self::FieldElement> {
+        self.0.zero()
+    }
+
+    fn constant(&mut self, val: Self::FieldElement) -> Result<Self::Wire> {
+        BackendT::constant(&mut self.0, val)
+    }
+
+    fn assert_zero(&mut self, wire: &Self::Wire) -> Result<()> {
+        self.assert_zero(wire)
+    }
+
+    fn assert_zero_many(&mut self, wires: &[Self::Wire]) -> Result<()> {
+        self.assert_zero_many(wires)
+    }
+
+    fn add(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.0.add(a, b)
+    }
+
+    fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.0.mul(a, b)
+    }
+
+    fn div(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.0.div(a, b)
+    }
+
+    fn add_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
+        self.0.addc(a, b)
+    }
+
+    fn mul_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
+        self.0.mulc(a, b)
+    }
+
+    fn input_public(&mut self, val: Self::FieldElement) -> Result<Self::Wire> {
+        Ok(self.0.input_public(val))
+    }
+
+    fn input_private(&mut self, val: Option<Self::FieldElement>) -> Result<Self::Wire> {
+        BackendT::input_private(&mut self.0, val)
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        self.finalize()
+    }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
@@ -0,0 +1,156 @@
+//! Boolean gates over wires of [`F2`].
+//!
+//! A [`BooleanBackend`] wraps a backend over [`F2`], e.g. a
+//! [`DietMacAndCheeseProver`] or [`DietMacAndCheeseVerifier`] whose MACs live
+//! in [`F40b`](scuttlebutt::field::F40b), and exposes the usual boolean gates
+//! in terms of the field operations: `and` is a multiplication, `xor` an
+//! addition and `not` the addition of the constant one. Only `and` and `or`
+//! use a multiplication, which is checked when the backend is finalized.
+//!
+//! [`DietMacAndCheeseProver`]: crate::DietMacAndCheeseProver
+//! [`DietMacAndCheeseVerifier`]: crate::DietMacAndCheeseVerifier
+
+use crate::backend_trait::BackendT;
+use eyre::Result;
+use scuttlebutt::{field::F2, ring::FiniteRing};
+
+/// A boolean circuit evaluated on a backend over [`F2`].
+pub struct BooleanBackend<'a, B: BackendT<FieldElement = F2>> {
+    backend: &'a mut B,
+}
+
+impl<'a, B: BackendT<FieldElement = F2>> BooleanBackend<'a, B> {
+    /// Create a boolean circuit evaluated on `backend`.
+    pub fn new(backend: &'a mut B) -> Self {
+        Self { backend }
+    }
+
+    /// Return the underlying backend, e.g., to call `finalize`.
+    pub fn backend(&mut self) -> &mut B {
+        self.backend
+    }
+
+    /// Input a public bit.
+    pub fn input_public(&mut self, val: bool) -> Result<B::Wire> {
+        self.backend.input_public(F2::from(val))
+    }
+
+    /// Input a private bit, which is `None` for the verifier.
+    pub fn input_private(&mut self, val: Option<bool>) -> Result<B::Wire> {
+        self.backend.input_private(val.map(F2::from))
+    }
+
+    /// Add a constant bit.
+    pub fn constant(&mut self, val: bool) -> Result<B::Wire> {
+        self.backend.constant(F2::from(val))
+    }
+
+    /// Return `a AND b`, using a single multiplication.
+    pub fn and(&mut self, a: &B::Wire, b: &B::Wire) -> Result<B::Wire> {
+        self.backend.mul(a, b)
+    }
+
+    /// Return `a XOR b`.
+    pub fn xor(&mut self, a: &B::Wire, b: &B::Wire) -> Result<B::Wire> {
+        self.backend.add(a, b)
+    }
+
+    /// Return `NOT a`.
+    pub fn not(&mut self, a: &B::Wire) -> Result<B::Wire> {
+        self.backend.add_constant(a, F2::ONE)
+    }
+
+    /// Return `a OR b`, computed as `(a XOR b) XOR (a AND b)`.
+    pub fn or(&mut self, a: &B::Wire, b: &B::Wire) -> Result<B::Wire> {
+        let xor = self.xor(a, b)?;
+        let and = self.and(a, b)?;
+        self.xor(&xor, &and)
+    }
+
+    /// Assert that `a` is false.
+    pub fn assert_false(&mut self, a: &B::Wire) -> Result<()> {
+        self.backend.assert_zero(a)
+    }
+
+    /// Assert that `a` is true.
+    pub fn assert_true(&mut self, a: &B::Wire) -> Result<()> {
+        let not_a = self.not(a)?;
+        self.assert_false(&not_a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BooleanBackend;
+    use crate::backend_trait::BackendT;
+    use crate::test_utils::run;
+    use eyre::Result;
+    use scuttlebutt::field::F2;
+
+    const TRUTH_TABLE: [(bool, bool); 4] =
+        [(false, false), (false, true), (true, false), (true, true)];
+
+    // Evaluate every gate on every pair of inputs and check the results
+    // against `expected`, indexed by gate then by pair.
+    fn truth_table<B: BackendT<FieldElement = F2>>(
+        backend: &mut B,
+        witness: bool,
+        expected: [[bool; 4]; 4],
+    ) -> Result<()> {
+        let mut circuit = BooleanBackend::new(backend);
+        for (i, (a, b)) in TRUTH_TABLE.into_iter().enumerate() {
+            let a = circuit.input_private(witness.then_some(a))?;
+            let b = circuit.input_private(witness.then_some(b))?;
+            let outputs = [
+                circuit.and(&a, &b)?,
+                circuit.xor(&a, &b)?,
+                circuit.or(&a, &b)?,
+                circuit.not(&a)?,
+            ];
+            for (out, expected) in outputs.iter().zip(expected) {
+                if expected[i] {
+                    circuit.assert_true(out)?;
+                } else {
+                    circuit.assert_false(out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn test_truth_table(expected: [[bool; 4]; 4]) -> (Result<()>, Result<()>) {
+        run::<F2, _, _>(
+            move |dmc| truth_table(dmc, true, expected),
+            move |dmc| truth_table(dmc, false, expected),
+        )
+    }
+
+    const EXPECTED: [[bool; 4]; 4] = [
+        // and
+        [false, false, false, true],
+        // xor
+        [false, true, true, false],
+        // or
+        [false, true, true, true],
+        // not of the first input
+        [true, true, false, false],
+    ];
+
+    #[test]
+    fn test_boolean_truth_table() {
+        let (prover, verifier) = test_truth_table(EXPECTED);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_boolean_wrong_truth_table_fails() {
+        for gate in 0..4 {
+            let mut expected = EXPECTED;
+            expected[gate][1] = !expected[gate][1];
+            let (prover, verifier) = test_truth_table(expected);
+            assert!(prover.is_err());
+            assert!(verifier.is_err());
+        }
+    }
+}
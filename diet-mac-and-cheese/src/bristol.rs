@@ -0,0 +1,472 @@
+//! Proofs of boolean circuits given in the Bristol Fashion format.
+//!
+//! The format is described at <https://homes.esat.kuleuven.be/~nsmart/MPC/>:
+//! a header gives the number of gates and wires, then the number of bits of
+//! each input and output, and each following line is a gate. The input bits
+//! are the first wires of the circuit and the output bits its last wires.
+//!
+//! The `AND`, `XOR`, `INV`, `EQ` and `EQW` gates are supported and evaluated
+//! with a [`BooleanBackend`]; only `AND` gates use a multiplication.
+
+use crate::{backend_trait::BackendT, boolean::BooleanBackend};
+use eyre::{eyre, Context, Result};
+use scuttlebutt::field::F2;
+use std::io::BufRead;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Gate {
+    And(usize, usize, usize),
+    Xor(usize, usize, usize),
+    Inv(usize, usize),
+    Eqw(usize, usize),
+    Eq(bool, usize),
+}
+
+/// What to do with the output bits of a [`BristolCircuit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Return the output wires, e.g., for the caller to open them with
+    /// `reveal_slice`.
+    Return,
+    /// Assert that every output bit is zero.
+    AssertZero,
+}
+
+/// A boolean circuit read from a Bristol Fashion file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BristolCircuit {
+    nwires: usize,
+    inputs: Vec<usize>,
+    outputs: Vec<usize>,
+    gates: Vec<Gate>,
+}
+
+// Parse the whitespace-separated integers of a header line.
+fn parse_header(line: &str, lineno: usize) -> Result<Vec<usize>> {
+    line.split_whitespace()
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| eyre!("line {}: invalid number `{}`", lineno, token))
+        })
+        .collect()
+}
+
+// Parse a header line made of a count followed by that many sizes.
+fn parse_sizes(line: &str, lineno: usize) -> Result<Vec<usize>> {
+    let nums = parse_header(line, lineno)?;
+    match nums.split_first() {
+        Some((&n, sizes)) if sizes.len() == n => Ok(sizes.to_vec()),
+        _ => Err(eyre!(
+            "line {}: expected a count followed by as many sizes",
+            lineno
+        )),
+    }
+}
+
+// Return the total number of bits of the given sizes.
+fn sum_sizes(sizes: &[usize], lineno: usize) -> Result<usize> {
+    sizes
+        .iter()
+        .try_fold(0usize, |sum, &size| sum.checked_add(size))
+        .ok_or_else(|| eyre!("line {}: the number of bits overflows", lineno))
+}
+
+fn parse_gate(line: &str, lineno: usize) -> Result<Gate> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let (op, args) = tokens
+        .split_last()
+        .ok_or_else(|| eyre!("line {}: missing gate", lineno))?;
+    let args = parse_header(&args.join(" "), lineno)?;
+    let arity = match *op {
+        "AND" | "XOR" => 2,
+        "INV" | "EQ" | "EQW" => 1,
+        "MAND" => return Err(eyre!("line {}: MAND gates are not supported", lineno)),
+        op => return Err(eyre!("line {}: unknown gate `{}`", lineno, op)),
+    };
+    if args.len() != arity + 3 || args[0] != arity || args[1] != 1 {
+        return Err(eyre!(
+            "line {}: {} expects {} input(s) and 1 output",
+            lineno,
+            op,
+            arity
+        ));
+    }
+    Ok(match *op {
+        "AND" => Gate::And(args[2], args[3], args[4]),
+        "XOR" => Gate::Xor(args[2], args[3], args[4]),
+        "INV" => Gate::Inv(args[2], args[3]),
+        "EQW" => Gate::Eqw(args[2], args[3]),
+        _ => match args[2] {
+            0 | 1 => Gate::Eq(args[2] == 1, args[3]),
+            _ => return Err(eyre!("line {}: EQ expects a constant 0 or 1", lineno)),
+        },
+    })
+}
+
+// Return the value of wire `i`, which `parse` checks is assigned before it is read.
+fn wire<W>(wires: &[Option<W>], i: usize) -> Result<&W> {
+    wires[i]
+        .as_ref()
+        .ok_or_else(|| eyre!("wire {} is not assigned", i))
+}
+
+impl BristolCircuit {
+    /// Parse a circuit in the Bristol Fashion format.
+    ///
+    /// This fails with the offending line number when the circuit is
+    /// malformed, e.g., when a gate reads a wire that is not yet assigned or
+    /// assigns a wire twice. As each gate assigns a single wire, the number of
+    /// wires must be the number of input bits plus the number of gates.
+    pub fn parse(reader: impl BufRead) -> Result<Self> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .map(|(i, line)| Ok((i + 1, line?)))
+            .filter(|line: &Result<(usize, String)>| {
+                line.as_ref().map_or(true, |(_, l)| !l.trim().is_empty())
+            });
+        let mut header = || -> Result<(usize, String)> {
+            lines
+                .next()
+                .unwrap_or_else(|| Err(eyre!("unexpected end of circuit")))
+        };
+
+        let (header_lineno, line) = header()?;
+        let (ngates, nwires) = match parse_header(&line, header_lineno)?[..] {
+            [ngates, nwires] => (ngates, nwires),
+            _ => {
+                return Err(eyre!(
+                    "line {}: expected the number of gates and wires",
+                    header_lineno
+                ))
+            }
+        };
+        let (lineno, line) = header()?;
+        let inputs = parse_sizes(&line, lineno)?;
+        let ninputs = sum_sizes(&inputs, lineno)?;
+        let (lineno, line) = header()?;
+        let outputs = parse_sizes(&line, lineno)?;
+        let noutputs = sum_sizes(&outputs, lineno)?;
+        if ninputs > nwires || noutputs > nwires {
+            return Err(eyre!(
+                "line {}: more input or output bits than the {} wires",
+                lineno,
+                nwires
+            ));
+        }
+
+        // The counts of the header are only trusted once the gates are read, so
+        // that a malformed header cannot make the parser allocate for them.
+        let mut gates = Vec::new();
+        for line in lines {
+            let (lineno, line) = line?;
+            gates.push((lineno, parse_gate(&line, lineno)?));
+        }
+        if gates.len() != ngates {
+            return Err(eyre!("expected {} gates but found {}", ngates, gates.len()));
+        }
+        // Each gate assigns a single wire, and the other wires are the inputs.
+        if nwires - ninputs != ngates {
+            return Err(eyre!(
+                "line {}: {} wires do not match the {} input bits and {} gates",
+                header_lineno,
+                nwires,
+                ninputs,
+                ngates
+            ));
+        }
+
+        let mut assigned = vec![false; nwires];
+        assigned[..ninputs].fill(true);
+        for &(lineno, gate) in gates.iter() {
+            let (ins, out) = match gate {
+                Gate::And(a, b, out) | Gate::Xor(a, b, out) => (vec![a, b], out),
+                Gate::Inv(a, out) | Gate::Eqw(a, out) => (vec![a], out),
+                Gate::Eq(_, out) => (vec![], out),
+            };
+            for wire in ins {
+                if !assigned.get(wire).copied().unwrap_or(false) {
+                    return Err(eyre!("line {}: wire {} is not assigned", lineno, wire));
+                }
+            }
+            match assigned.get_mut(out) {
+                Some(assigned) if !*assigned => *assigned = true,
+                Some(_) => return Err(eyre!("line {}: wire {} is assigned twice", lineno, out)),
+                None => {
+                    return Err(eyre!(
+                        "line {}: wire {} is out of the {} wires",
+                        lineno,
+                        out,
+                        nwires
+                    ))
+                }
+            }
+        }
+        // The gates assign distinct wires, as many as the wires which are not
+        // inputs, so that all the wires and in particular the outputs are assigned.
+
+        Ok(Self {
+            nwires,
+            inputs,
+            outputs,
+            gates: gates.into_iter().map(|(_, gate)| gate).collect(),
+        })
+    }
+
+    /// Read a circuit from the Bristol Fashion file at `path`.
+    pub fn read(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let file = std::fs::File::open(path.as_ref())
+            .with_context(|| format!("Cannot open {}", path.as_ref().display()))?;
+        Self::parse(std::io::BufReader::new(file))
+            .with_context(|| format!("Invalid circuit {}", path.as_ref().display()))
+    }
+
+    /// Return the number of bits of each input.
+    pub fn inputs(&self) -> &[usize] {
+        &self.inputs
+    }
+
+    /// Return the number of bits of each output.
+    pub fn outputs(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    /// Return the number of `AND` gates, i.e., of multiplications to check.
+    pub fn and_gates(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|g| matches!(g, Gate::And(..)))
+            .count()
+    }
+
+    /// Evaluate the circuit on the private input bits `witness`, given in
+    /// order for all the inputs, and which is `None` for the verifier.
+    ///
+    /// The output bits are handled according to `mode`, and returned in
+    /// order for all the outputs.
+    pub fn evaluate<B: BackendT<FieldElement = F2>>(
+        &self,
+        circuit: &mut BooleanBackend<B>,
+        witness: Option<&[bool]>,
+        mode: OutputMode,
+    ) -> Result<Vec<B::Wire>> {
+        let ninputs = self.inputs.iter().sum();
+        if let Some(witness) = witness {
+            if witness.len() != ninputs {
+                return Err(eyre!(
+                    "Expected {} input bits, but the witness has {}",
+                    ninputs,
+                    witness.len()
+                ));
+            }
+        }
+
+        let mut wires: Vec<Option<B::Wire>> = Vec::with_capacity(self.nwires);
+        for i in 0..ninputs {
+            wires.push(Some(circuit.input_private(witness.map(|w| w[i]))?));
+        }
+        wires.resize_with(self.nwires, || None);
+        for gate in self.gates.iter() {
+            let (out, value) = match *gate {
+                Gate::And(a, b, out) => (out, circuit.and(wire(&wires, a)?, wire(&wires, b)?)?),
+                Gate::Xor(a, b, out) => (out, circuit.xor(wire(&wires, a)?, wire(&wires, b)?)?),
+                Gate::Inv(a, out) => (out, circuit.not(wire(&wires, a)?)?),
+                Gate::Eqw(a, out) => (out, circuit.backend().copy(wire(&wires, a)?)?),
+                Gate::Eq(val, out) => (out, circuit.constant(val)?),
+            };
+            wires[out] = Some(value);
+        }
+
+        let noutputs: usize = self.outputs.iter().sum();
+        let outputs = wires
+            .drain(self.nwires - noutputs..)
+            .map(|w| w.ok_or_else(|| eyre!("output wire is not assigned")))
+            .collect::<Result<Vec<_>>>()?;
+        if mode == OutputMode::AssertZero {
+            for out in outputs.iter() {
+                circuit.assert_false(out)?;
+            }
+        }
+        Ok(outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BristolCircuit, OutputMode};
+    use crate::boolean::BooleanBackend;
+    use crate::test_utils::run;
+    use eyre::Result;
+    use scuttlebutt::{field::F2, ring::FiniteRing};
+
+    // A 2-bit adder, with the carry computed as an OR of `INV` and `AND` gates.
+    const ADDER: &str = "10 14
+2 2 2
+1 3
+
+2 1 0 2 4 AND
+2 1 1 3 5 XOR
+2 1 1 3 6 AND
+2 1 5 4 7 AND
+1 1 6 8 INV
+1 1 7 9 INV
+2 1 8 9 10 AND
+2 1 0 2 11 XOR
+2 1 5 4 12 XOR
+1 1 10 13 INV
+";
+
+    // Outputs `a XOR b` for 2-bit inputs, which is zero when they are equal.
+    const EQUAL: &str = "2 6
+2 2 2
+1 2
+
+2 1 0 2 4 XOR
+2 1 1 3 5 XOR
+";
+
+    fn bits(x: usize, n: usize) -> Vec<bool> {
+        (0..n).map(|i| (x >> i) & 1 == 1).collect()
+    }
+
+    fn from_bits(bits: &[F2]) -> usize {
+        bits.iter()
+            .enumerate()
+            .map(|(i, &b)| usize::from(b == F2::ONE) << i)
+            .sum()
+    }
+
+    #[test]
+    fn test_bristol_adder() {
+        let adder = BristolCircuit::parse(ADDER.as_bytes()).unwrap();
+        assert_eq!(adder.inputs(), &[2, 2]);
+        assert_eq!(adder.outputs(), &[3]);
+        assert_eq!(adder.and_gates(), 4);
+
+        let prover_adder = adder.clone();
+        let (prover, verifier) = run::<F2, _, _>(
+            move |dmc| {
+                for (a, b) in (0..4).flat_map(|a| (0..4).map(move |b| (a, b))) {
+                    let witness = [bits(a, 2), bits(b, 2)].concat();
+                    let mut circuit = BooleanBackend::new(&mut *dmc);
+                    let out =
+                        prover_adder.evaluate(&mut circuit, Some(&witness), OutputMode::Return)?;
+                    assert_eq!(from_bits(&dmc.reveal_slice(&out)?), a + b);
+                }
+                Ok(())
+            },
+            move |dmc| {
+                let mut sums = Vec::new();
+                for _ in 0..16 {
+                    let mut circuit = BooleanBackend::new(&mut *dmc);
+                    let out = adder.evaluate(&mut circuit, None, OutputMode::Return)?;
+                    sums.push(from_bits(&dmc.reveal_slice(&out)?));
+                }
+                Ok(sums)
+            },
+        );
+        prover.unwrap();
+        let expected: Vec<usize> = (0..4).flat_map(|a| (0..4).map(move |b| a + b)).collect();
+        assert_eq!(verifier.unwrap(), expected);
+    }
+
+    fn prove_equal(a: usize, b: usize) -> (Result<()>, Result<()>) {
+        let equal = BristolCircuit::parse(EQUAL.as_bytes()).unwrap();
+        let prover_equal = equal.clone();
+        let (prover, verifier) = run::<F2, _, _>(
+            move |dmc| {
+                let witness = [bits(a, 2), bits(b, 2)].concat();
+                let mut circuit = BooleanBackend::new(dmc);
+                prover_equal.evaluate(&mut circuit, Some(&witness), OutputMode::AssertZero)?;
+                Ok(())
+            },
+            move |dmc| {
+                let mut circuit = BooleanBackend::new(dmc);
+                equal.evaluate(&mut circuit, None, OutputMode::AssertZero)?;
+                Ok(())
+            },
+        );
+        (prover, verifier)
+    }
+
+    #[test]
+    fn test_bristol_assert_zero() {
+        let (prover, verifier) = prove_equal(2, 2);
+        prover.unwrap();
+        verifier.unwrap();
+
+        let (prover, verifier) = prove_equal(2, 3);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    #[test]
+    fn test_bristol_wrong_witness_length_fails() {
+        let adder = BristolCircuit::parse(ADDER.as_bytes()).unwrap();
+        let (prover, _) = run::<F2, _, _>(
+            move |dmc| {
+                let mut circuit = BooleanBackend::new(dmc);
+                adder.evaluate(&mut circuit, Some(&[true; 3]), OutputMode::Return)?;
+                Ok(())
+            },
+            |_| Ok(()),
+        );
+        assert!(prover
+            .unwrap_err()
+            .to_string()
+            .contains("Expected 4 input bits"));
+    }
+
+    #[test]
+    fn test_bristol_malformed() {
+        let header = "1 3\n2 1 1\n1 1\n\n";
+        for (gate, error) in [
+            ("2 1 0 1 3 NAND", "line 5: unknown gate `NAND`"),
+            ("2 1 0 1 3 MAND", "line 5: MAND gates are not supported"),
+            ("2 1 0 3 AND", "line 5: AND expects 2 input(s) and 1 output"),
+            ("2 1 0 x 3 AND", "line 5: invalid number `x`"),
+            ("2 1 0 2 3 AND", "line 5: wire 2 is not assigned"),
+            ("2 1 0 1 1 AND", "line 5: wire 1 is assigned twice"),
+            ("2 1 0 1 3 AND", "line 5: wire 3 is out of the 3 wires"),
+            ("1 1 2 3 EQ", "line 5: EQ expects a constant 0 or 1"),
+            ("", "expected 1 gates but found 0"),
+        ] {
+            let circuit = format!("{}{}\n", header, gate);
+            let err = BristolCircuit::parse(circuit.as_bytes()).unwrap_err();
+            assert_eq!(err.to_string(), error);
+        }
+
+        for (circuit, error) in [
+            ("1\n", "line 1: expected the number of gates and wires"),
+            (
+                "1 4\n2 1\n",
+                "line 2: expected a count followed by as many sizes",
+            ),
+            ("1 3\n2 1 1\n", "unexpected end of circuit"),
+            (
+                "1 2\n2 1 2\n1 1\n",
+                "line 3: more input or output bits than the 2 wires",
+            ),
+            (
+                "18446744073709551615 5\n2 1 1\n1 1\n",
+                "expected 18446744073709551615 gates but found 0",
+            ),
+            (
+                "1 18446744073709551615\n2 1 1\n1 1\n2 1 0 1 2 AND\n",
+                "line 1: 18446744073709551615 wires do not match the 2 input bits and 1 gates",
+            ),
+            (
+                "1 3\n2 1 1\n1 1\n2 1 0 1 2 AND\n2 1 0 1 2 AND\n",
+                "expected 1 gates but found 2",
+            ),
+            (
+                "1 3\n2 1 18446744073709551615\n1 1\n",
+                "line 2: the number of bits overflows",
+            ),
+        ] {
+            let err = BristolCircuit::parse(circuit.as_bytes()).unwrap_err();
+            assert_eq!(err.to_string(), error);
+        }
+    }
+}
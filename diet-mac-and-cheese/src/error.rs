@@ -0,0 +1,73 @@
+//! The errors of the parties of Diet Mac'n'Cheese, shared by the backends and
+//! the homomorphic commitments.
+
+/// Errors of the parties of Diet Mac'n'Cheese, which can be recovered from the
+/// [`eyre::Report`] of a failing call with `downcast_ref`.
+///
+/// The errors of the channel are not `DmcError`s, and are detected with
+/// [`is_io_error`]. Unlike a failed check, which means that the proof is
+/// rejected, they may be worth retrying on a new connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmcError {
+    /// A zero check failed: a value asserted to be zero is not.
+    CheckZeroFailed,
+    /// A multiplication check failed: a multiplication triple is not valid.
+    MultCheckFailed,
+    /// The check of revealed values failed.
+    OpenFailed,
+    /// The bytes of a field element do not fit in the field.
+    InvalidFieldElement,
+    /// A gate was called after an earlier error. Calling `reset` allows using
+    /// the party again.
+    UsedAfterError,
+    /// A gate was called after `finalize`, and would not be covered by its
+    /// checks. Calling `reset` allows using the party again.
+    AlreadyFinalized,
+    /// In strict mode, `finalize` found that the number of multiplications
+    /// differs by the given amount from the number of multiplications covered
+    /// by the multiplication checks.
+    UncheckedMultiplications(isize),
+    /// A private input would exceed the budget set with `set_input_budget`.
+    BudgetExceeded,
+    /// `finalize_cancellable` was cancelled before completing its checks.
+    Cancelled,
+    /// The prover divided by a wire whose value is zero.
+    DivisionByZero,
+}
+
+impl std::fmt::Display for DmcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DmcError::CheckZeroFailed => write!(f, "check_zero failed"),
+            DmcError::MultCheckFailed => write!(f, "checkMultiply fails"),
+            DmcError::OpenFailed => write!(f, "open fails"),
+            DmcError::InvalidFieldElement => write!(f, "Invalid field element"),
+            DmcError::UsedAfterError => write!(
+                f,
+                "An error occurred earlier. This functionality should not be used further"
+            ),
+            DmcError::AlreadyFinalized => {
+                write!(f, "The party was used after `finalize` without a `reset`")
+            }
+            DmcError::UncheckedMultiplications(n) => {
+                write!(
+                    f,
+                    "The number of multiplications differs by {} from the number of checked multiplications",
+                    n
+                )
+            }
+            DmcError::BudgetExceeded => {
+                write!(f, "The budget of private inputs is exceeded")
+            }
+            DmcError::Cancelled => write!(f, "`finalize` was cancelled"),
+            DmcError::DivisionByZero => write!(f, "Division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for DmcError {}
+
+/// Return whether `report` was caused by an I/O error, e.g., of the channel.
+pub fn is_io_error(report: &eyre::Report) -> bool {
+    report.chain().any(|e| e.is::<std::io::Error>())
+}
@@ -0,0 +1,1258 @@
+//! Gadgets built on top of the basic gates of a [`BackendT`].
+//!
+//! The gadgets are written once against the [`BackendT`] interface and are
+//! shared by the prover and the verifier. Whenever a gadget needs a private hint
+//! from the prover (e.g., the bit decomposition of a wire), the hint is derived
+//! from [`BackendGadgetsT::wire_value`], which is only available to the prover.
+
+use crate::{
+    backend::{DietMacAndCheeseProver, DietMacAndCheeseVerifier},
+    backend_trait::BackendT,
+    homcom::{MacProver, MacVerifier},
+};
+use eyre::{eyre, Result};
+use generic_array::typenum::Unsigned;
+use rand::{CryptoRng, Rng};
+use scuttlebutt::{
+    field::{FiniteField, F2},
+    ring::FiniteRing,
+    AbstractChannel,
+};
+
+/// This trait extends the [`BackendT`] trait with gadgets composed of its gates.
+pub trait BackendGadgetsT: BackendT {
+    /// Return the clear value of `wire`, if known.
+    ///
+    /// This is `Some` for the prover and `None` for the verifier.
+    fn wire_value(&self, wire: &Self::Wire) -> Option<Self::FieldElement>;
+
+    /// Return `a` if `cond` is one, and `b` if `cond` is zero.
+    ///
+    /// This computes `cond * a + (1 - cond) * b` with a single multiplication.
+    /// The gadget does not check that `cond` is a boolean wire, this is the
+    /// responsibility of the caller: a private `cond` outside of `{0, 1}` lets a
+    /// malicious prover output an arbitrary combination of `a` and `b`. Use
+    /// [`Self::assert_bool`] when `cond` is not already known to be boolean.
+    fn select(&mut self, cond: &Self::Wire, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        // cond * (a - b) + b
+        let diff = self.sub(a, b)?;
+        let cond_diff = self.mul(cond, &diff)?;
+        self.add(&cond_diff, b)
+    }
+
+    /// Assert that `wire` is either zero or one.
+    ///
+    /// This checks `wire * (wire - 1) == 0` with a single multiplication.
+    fn assert_bool(&mut self, wire: &Self::Wire) -> Result<()> {
+        // wire * wire - wire == 0
+        let square = self.mul(wire, wire)?;
+        let not_bool = self.sub(&square, wire)?;
+        self.assert_zero(&not_bool)
+    }
+
+    /// Assert that `sum w_i x_i == target` for the public weights `w_i` of `terms`.
+    ///
+    /// As the weights are public, this only uses linear gates and a single
+    /// `assert_zero`, and adds no multiplication to check.
+    fn assert_linear(
+        &mut self,
+        terms: &[(Self::FieldElement, Self::Wire)],
+        target: Self::FieldElement,
+    ) -> Result<()> {
+        let mut acc = self.constant(-target)?;
+        for (w, x) in terms {
+            let wx = self.mul_constant(x, *w)?;
+            acc = self.add(&acc, &wx)?;
+        }
+        self.assert_zero(&acc)
+    }
+
+    /// Return the inner product of `a` and `b`, which must have the same length.
+    ///
+    /// By default, this uses one multiplication per pair of wires. Diet
+    /// Mac'n'Cheese checks the whole inner product as a single multiplication.
+    fn dot_product(&mut self, a: &[Self::Wire], b: &[Self::Wire]) -> Result<Self::Wire> {
+        if a.len() != b.len() {
+            return Err(eyre!(
+                "dot_product of vectors of lengths {} and {}",
+                a.len(),
+                b.len()
+            ));
+        }
+        let mut acc = self.constant(Self::FieldElement::ZERO)?;
+        for (x, y) in a.iter().zip(b) {
+            let xy = self.mul(x, y)?;
+            acc = self.add(&acc, &xy)?;
+        }
+        Ok(acc)
+    }
+
+    /// Assert that the inner product of `a` and `b` is the public `expected`.
+    ///
+    /// This is [`Self::dot_product`] followed by a single `assert_zero`.
+    fn assert_dot_product(
+        &mut self,
+        a: &[Self::Wire],
+        b: &[Self::Wire],
+        expected: Self::FieldElement,
+    ) -> Result<()> {
+        let dot = self.dot_product(a, b)?;
+        let diff = self.add_constant(&dot, -expected)?;
+        self.assert_zero(&diff)
+    }
+
+    /// Return the evaluation at `x` of the public polynomial with coefficients
+    /// `coeffs`, from the constant term to the leading term.
+    ///
+    /// This uses Horner's rule, with one multiplication by `x` per degree of the
+    /// polynomial beyond the first.
+    fn eval_poly(&mut self, x: &Self::Wire, coeffs: &[Self::FieldElement]) -> Result<Self::Wire> {
+        let Some((leading, rest)) = coeffs.split_last() else {
+            return self.constant(Self::FieldElement::ZERO);
+        };
+        let Some((next, rest)) = rest.split_last() else {
+            return self.constant(*leading);
+        };
+        let acc = self.mul_constant(x, *leading)?;
+        let mut acc = self.add_constant(&acc, *next)?;
+        for c in rest.iter().rev() {
+            acc = self.mul(&acc, x)?;
+            acc = self.add_constant(&acc, *c)?;
+        }
+        Ok(acc)
+    }
+
+    /// Assert that `x` is a root of the public polynomial with coefficients
+    /// `coeffs`, from the constant term to the leading term.
+    ///
+    /// See [`Self::eval_poly`] for the cost of the evaluation.
+    fn assert_root(&mut self, x: &Self::Wire, coeffs: &[Self::FieldElement]) -> Result<()> {
+        let value = self.eval_poly(x, coeffs)?;
+        self.assert_zero(&value)
+    }
+
+    /// Return the parity (XOR) of `bits`, as their sum in [`F2`].
+    ///
+    /// This only uses additions, and adds no multiplication to check. It is only
+    /// available over `F2`, as the sum of the bits is not their parity in odd
+    /// characteristic.
+    fn parity(&mut self, bits: &[Self::Wire]) -> Result<Self::Wire>
+    where
+        Self: BackendT<FieldElement = F2>,
+    {
+        let mut acc = self.constant(Self::FieldElement::ZERO)?;
+        for bit in bits {
+            acc = self.add(&acc, bit)?;
+        }
+        Ok(acc)
+    }
+
+    /// Return `bits` in reverse order, e.g., to switch between the bit-reflected
+    /// convention of GCM and the least significant first convention.
+    ///
+    /// This only rewires the bits, and uses no gate.
+    fn bit_reverse(&mut self, bits: &[Self::Wire]) -> Result<Vec<Self::Wire>> {
+        bits.iter().rev().map(|bit| self.copy(bit)).collect()
+    }
+
+    /// Return `base^e`, where `e` is given by its boolean wires `exp_bits`, least
+    /// significant first.
+    ///
+    /// This uses square-and-multiply, with 3 multiplications per bit, including
+    /// the one of [`Self::select`]. As with `select`, the gadget does not check
+    /// that `exp_bits` are boolean wires.
+    fn pow_by_bits(&mut self, base: &Self::Wire, exp_bits: &[Self::Wire]) -> Result<Self::Wire> {
+        let mut acc = self.constant(Self::FieldElement::ONE)?;
+        let mut power = self.copy(base)?;
+        for (i, bit) in exp_bits.iter().enumerate() {
+            let product = self.mul(&acc, &power)?;
+            acc = self.select(bit, &product, &acc)?;
+            if i + 1 < exp_bits.len() {
+                power = self.mul(&power, &power)?;
+            }
+        }
+        Ok(acc)
+    }
+
+    /// Assert that `g^x == h`, for public `g` and `h` and a private exponent `x`.
+    ///
+    /// `x` is decomposed into `bits` boolean wires, which are then used by
+    /// [`Self::pow_by_bits`]. Since the exponent only matters modulo the order of
+    /// `g`, `bits` should be the bit length of this order, which the caller must
+    /// know, and must be less than the number of bits of the field.
+    fn assert_discrete_log(
+        &mut self,
+        x: &Self::Wire,
+        g: Self::FieldElement,
+        h: Self::FieldElement,
+        bits: usize,
+    ) -> Result<()> {
+        let x_bits = decompose_bits(self, x, bits)?;
+        let base = self.constant(g)?;
+        let pow = self.pow_by_bits(&base, &x_bits)?;
+        let diff = self.add_constant(&pow, -h)?;
+        self.assert_zero(&diff)
+    }
+
+    /// Return a boolean wire which is one iff `a == b`.
+    ///
+    /// The prover inputs the inverse of `d = a - b`, or zero when `d` is zero,
+    /// and the bit is `1 - d * d^-1`. Checking that `d * bit` is zero ensures that
+    /// the bit is one only when `a == b`.
+    fn is_equal(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        let diff = self.sub(a, b)?;
+        let hint = self.wire_value(&diff).map(|d| {
+            if d == Self::FieldElement::ZERO {
+                Self::FieldElement::ZERO
+            } else {
+                d.inverse()
+            }
+        });
+        let inv = self.input_private(hint)?;
+        let prod = self.mul(&diff, &inv)?;
+        let minus_prod = self.mul_constant(&prod, -Self::FieldElement::ONE)?;
+        let bit = self.add_constant(&minus_prod, Self::FieldElement::ONE)?;
+        let check = self.mul(&diff, &bit)?;
+        self.assert_zero(&check)?;
+        Ok(bit)
+    }
+
+    /// Assert that `a != b`.
+    ///
+    /// The prover inputs the inverse of `a - b`, and the product of the
+    /// difference by its inverse is asserted to be one. The prover fails when
+    /// providing the inverse if its values are equal.
+    fn assert_ne(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<()> {
+        let diff = self.sub(a, b)?;
+        let hint = match self.wire_value(&diff) {
+            Some(d) if d == Self::FieldElement::ZERO => {
+                return Err(eyre!("Cannot assert that equal values differ"));
+            }
+            hint => hint.map(|d| d.inverse()),
+        };
+        let inv = self.input_private(hint)?;
+        let prod = self.mul(&diff, &inv)?;
+        let prod_minus_one = self.add_constant(&prod, -Self::FieldElement::ONE)?;
+        self.assert_zero(&prod_minus_one)
+    }
+
+    /// Return a boolean wire which is one iff `a < b`.
+    ///
+    /// Both `a` and `b` must be less than `2^bits`, which the gadget does not check,
+    /// and `bits + 1` must be less than the number of bits of the field.
+    fn less_than(&mut self, a: &Self::Wire, b: &Self::Wire, bits: usize) -> Result<Self::Wire> {
+        // `a - b + 2^bits` lies in `[1, 2^(bits+1))` and its top bit is set iff `a >= b`.
+        let diff = self.sub(a, b)?;
+        let shifted = self.add_constant(&diff, power_of_two(bits))?;
+        let shifted_bits = decompose_bits(self, &shifted, bits + 1)?;
+        let minus_top_bit = self.mul_constant(&shifted_bits[bits], -Self::FieldElement::ONE)?;
+        self.add_constant(&minus_top_bit, Self::FieldElement::ONE)
+    }
+
+    /// Return `(min(a, b), max(a, b))`.
+    ///
+    /// When `a` and `b` are equal they are returned in their original order.
+    /// This is the building block of sorting networks, and has the same
+    /// requirements on `bits` as [`Self::less_than`].
+    fn compare_and_swap(
+        &mut self,
+        a: &Self::Wire,
+        b: &Self::Wire,
+        bits: usize,
+    ) -> Result<(Self::Wire, Self::Wire)> {
+        let swap = self.less_than(b, a, bits)?;
+        let min = self.select(&swap, b, a)?;
+        let max = self.select(&swap, a, b)?;
+        Ok((min, max))
+    }
+
+    /// Assert that `xs` is sorted in nondecreasing order.
+    ///
+    /// Each adjacent pair is compared with [`Self::less_than`], which sets the
+    /// requirements on `bits`. Empty and single-element slices are sorted.
+    fn assert_sorted(&mut self, xs: &[Self::Wire], bits: usize) -> Result<()> {
+        for pair in xs.windows(2) {
+            let out_of_order = self.less_than(&pair[1], &pair[0], bits)?;
+            self.assert_zero(&out_of_order)?;
+        }
+        Ok(())
+    }
+
+    /// Assert that the elements of `xs` are pairwise distinct.
+    ///
+    /// This uses [`Self::assert_ne`] on every pair, i.e., a quadratic number of
+    /// multiplications, but puts no bound on the values. For larger slices of
+    /// values less than `2^bits`, see [`Self::assert_all_distinct_bounded`].
+    /// Empty and single-element slices are distinct.
+    fn assert_all_distinct(&mut self, xs: &[Self::Wire]) -> Result<()> {
+        for (i, x) in xs.iter().enumerate() {
+            for y in &xs[i + 1..] {
+                self.assert_ne(x, y)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assert that the elements of `xs` are pairwise distinct, where all the
+    /// values are less than `2^bits`.
+    ///
+    /// The values are sorted with Batcher's odd-even merge sort, whose
+    /// `O(n log^2 n)` comparators use [`Self::compare_and_swap`], and the
+    /// sorted values are asserted to be strictly increasing. This sets the same
+    /// requirements on `bits` as [`Self::less_than`].
+    fn assert_all_distinct_bounded(&mut self, xs: &[Self::Wire], bits: usize) -> Result<()> {
+        let n = xs.len();
+        let mut sorted = xs
+            .iter()
+            .map(|x| self.copy(x))
+            .collect::<Result<Vec<_>>>()?;
+        // The network for the next power of two, without the comparators
+        // involving the missing elements, which would not swap them.
+        let mut p = 1;
+        while p < n {
+            let mut k = p;
+            while k >= 1 {
+                let mut j = k % p;
+                while j + k < n {
+                    for i in 0..k.min(n - j - k) {
+                        if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                            let (min, max) =
+                                self.compare_and_swap(&sorted[i + j], &sorted[i + j + k], bits)?;
+                            sorted[i + j] = min;
+                            sorted[i + j + k] = max;
+                        }
+                    }
+                    j += 2 * k;
+                }
+                k /= 2;
+            }
+            p *= 2;
+        }
+        for pair in sorted.windows(2) {
+            let increasing = self.less_than(&pair[0], &pair[1], bits)?;
+            let not_increasing = self.add_constant(&increasing, -Self::FieldElement::ONE)?;
+            self.assert_zero(&not_increasing)?;
+        }
+        Ok(())
+    }
+
+    /// Return `(q, r)` such that `a = q * modulus + r` with `0 <= r < modulus`.
+    ///
+    /// The prover computes `q` and `r` from its clear value of `a`, which must be
+    /// less than `2^bits`. The quotient is range checked against `2^bits` and the
+    /// remainder against `modulus`, and `bits` plus the bit length of `modulus`
+    /// must be less than the number of bits of the field.
+    fn mod_reduce(
+        &mut self,
+        a: &Self::Wire,
+        modulus: u64,
+        bits: usize,
+    ) -> Result<(Self::Wire, Self::Wire)> {
+        if modulus == 0 {
+            return Err(eyre!("Cannot reduce modulo zero"));
+        }
+        let modulus_bits = (u64::BITS - modulus.leading_zeros()) as usize;
+        let field_bits = <Self::FieldElement as FiniteField>::NumberOfBitsInBitDecomposition::USIZE;
+        if bits + modulus_bits >= field_bits {
+            return Err(eyre!(
+                "Cannot reduce a {} bits value modulo a {} bits modulus in a field of {} bits",
+                bits,
+                modulus_bits,
+                field_bits
+            ));
+        }
+
+        let hint = self.wire_value(a).map(|v| {
+            let v = field_to_u128(&v);
+            let modulus = u128::from(modulus);
+            (u128_to_field(v / modulus), u128_to_field(v % modulus))
+        });
+        let q = self.input_private(hint.map(|(q, _)| q))?;
+        let r = self.input_private(hint.map(|(_, r)| r))?;
+
+        decompose_bits(self, &q, bits)?;
+        decompose_bits(self, &r, modulus_bits)?;
+        // r <= modulus - 1
+        let minus_r = self.mul_constant(&r, -Self::FieldElement::ONE)?;
+        let slack = self.add_constant(&minus_r, u128_to_field(u128::from(modulus - 1)))?;
+        decompose_bits(self, &slack, modulus_bits)?;
+
+        let q_modulus = self.mul_constant(&q, u128_to_field(u128::from(modulus)))?;
+        let recomposed = self.add(&q_modulus, &r)?;
+        let diff = self.sub(&recomposed, a)?;
+        self.assert_zero(&diff)?;
+        Ok((q, r))
+    }
+
+    /// Assert that `0 <= value < 2^bits` and return the bits of `value`,
+    /// least significant bit first.
+    ///
+    /// The prover inputs the bits as private hints, which are checked to be
+    /// boolean and to reconstruct `value`, so the verifier does not learn
+    /// them. `bits` must be less than the number of bits of the field, so that
+    /// the weighted sum of the bits cannot wrap around the modulus.
+    fn assert_range(&mut self, value: &Self::Wire, bits: usize) -> Result<Vec<Self::Wire>> {
+        decompose_bits(self, value, bits)
+    }
+
+    /// Assert that `value` has exactly `bits` bits, i.e., `2^(bits-1) <= value < 2^bits`.
+    ///
+    /// This is useful for normalized fixed-point representations, whose top bit
+    /// is set. `bits` must be nonzero and less than the number of bits of the field,
+    /// and zero never satisfies the assertion.
+    fn assert_bit_length(&mut self, value: &Self::Wire, bits: usize) -> Result<()> {
+        if bits == 0 {
+            return Err(eyre!("Cannot assert a bit length of zero"));
+        }
+        let value_bits = decompose_bits(self, value, bits)?;
+        let top_bit_minus_one =
+            self.add_constant(&value_bits[bits - 1], -Self::FieldElement::ONE)?;
+        self.assert_zero(&top_bit_minus_one)
+    }
+
+    /// Assert that `lo <= v <= hi`, where `lo` and `hi` are public.
+    ///
+    /// Both `v - lo` and `hi - v` are range checked against `2^bits`, so `hi - lo`
+    /// must be less than `2^bits` for an in-interval value to pass, and `bits + 1`
+    /// must be less than the number of bits of the field.
+    fn assert_in_interval(
+        &mut self,
+        v: &Self::Wire,
+        lo: Self::FieldElement,
+        hi: Self::FieldElement,
+        bits: usize,
+    ) -> Result<()> {
+        if field_to_u128(&lo) > field_to_u128(&hi) {
+            return Err(eyre!(
+                "Empty interval: the lower bound exceeds the upper bound"
+            ));
+        }
+        let above_lo = self.add_constant(v, -lo)?;
+        decompose_bits(self, &above_lo, bits)?;
+        let minus_v = self.mul_constant(v, -Self::FieldElement::ONE)?;
+        let below_hi = self.add_constant(&minus_v, hi)?;
+        decompose_bits(self, &below_hi, bits)?;
+        Ok(())
+    }
+}
+
+fn power_of_two<F: FiniteField>(exponent: usize) -> F {
+    let two = F::ONE + F::ONE;
+    (0..exponent).fold(F::ONE, |acc, _| acc * two)
+}
+
+// Return the integer whose binary representation is given by the low 128 bits
+// of the bit decomposition of `x`.
+fn field_to_u128<F: FiniteField>(x: &F) -> u128 {
+    x.bit_decomposition()
+        .iter()
+        .take(128)
+        .rev()
+        .fold(0, |acc, bit| (acc << 1) | u128::from(*bit))
+}
+
+fn u128_to_field<F: FiniteField>(x: u128) -> F {
+    let two = F::ONE + F::ONE;
+    (0..u128::BITS - x.leading_zeros())
+        .rev()
+        .fold(F::ZERO, |acc, i| {
+            let acc = acc * two;
+            if (x >> i) & 1 == 1 {
+                acc + F::ONE
+            } else {
+                acc
+            }
+        })
+}
+
+// Decompose `wire` into `nbits` boolean wires, least significant bit first,
+// and check that their weighted sum reconstructs `wire`.
+fn decompose_bits<B: BackendGadgetsT + ?Sized>(
+    backend: &mut B,
+    wire: &B::Wire,
+    nbits: usize,
+) -> Result<Vec<B::Wire>> {
+    let field_bits = <B::FieldElement as FiniteField>::NumberOfBitsInBitDecomposition::USIZE;
+    if nbits >= field_bits {
+        return Err(eyre!(
+            "Cannot decompose into {} bits a field element of {} bits",
+            nbits,
+            field_bits
+        ));
+    }
+
+    let hint = backend.wire_value(wire).map(|v| v.bit_decomposition());
+    let mut bits = Vec::with_capacity(nbits);
+    let mut recomposed = backend.constant(B::FieldElement::ZERO)?;
+    let mut power = B::FieldElement::ONE;
+    for i in 0..nbits {
+        let bit = backend.input_private(hint.as_ref().map(|h| {
+            if h[i] {
+                B::FieldElement::ONE
+            } else {
+                B::FieldElement::ZERO
+            }
+        }))?;
+        backend.assert_bool(&bit)?;
+
+        let weighted = backend.mul_constant(&bit, power)?;
+        recomposed = backend.add(&recomposed, &weighted)?;
+        power += power;
+        bits.push(bit);
+    }
+    let diff = backend.sub(&recomposed, wire)?;
+    backend.assert_zero(&diff)?;
+    Ok(bits)
+}
+
+impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendGadgetsT
+    for DietMacAndCheeseProver<FE, C, RNG>
+{
+    fn wire_value(&self, wire: &MacProver<FE>) -> Option<FE::PrimeField> {
+        Some(wire.value())
+    }
+
+    fn dot_product(&mut self, a: &[MacProver<FE>], b: &[MacProver<FE>]) -> Result<MacProver<FE>> {
+        self.dot_product(a, b)
+    }
+}
+
+impl<FE: FiniteField, C: AbstractChannel, RNG: CryptoRng + Rng> BackendGadgetsT
+    for DietMacAndCheeseVerifier<FE, C, RNG>
+{
+    fn wire_value(&self, _wire: &MacVerifier<FE>) -> Option<FE::PrimeField> {
+        None
+    }
+
+    fn dot_product(
+        &mut self,
+        a: &[MacVerifier<FE>],
+        b: &[MacVerifier<FE>],
+    ) -> Result<MacVerifier<FE>> {
+        self.dot_product(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{u128_to_field, BackendGadgetsT};
+    use crate::test_utils::{f61p, run};
+    use crate::{backend_trait::BackendT, plaintext::PlaintextBackend};
+    use eyre::Result;
+    use scuttlebutt::{
+        field::{F40b, F61p, F2},
+        ring::FiniteRing,
+    };
+
+    // Sort the inputs with an odd-even transposition sorting network and check
+    // the result against `expected`.
+    fn sorting_network<B: BackendGadgetsT>(
+        backend: &mut B,
+        witness: &[Option<B::FieldElement>],
+        expected: &[B::FieldElement],
+    ) -> Result<()> {
+        let n = witness.len();
+        let network: Vec<(usize, usize)> = (0..n)
+            .flat_map(|round| (round % 2..n.saturating_sub(1)).step_by(2))
+            .map(|i| (i, i + 1))
+            .collect();
+        let mut wires = witness
+            .iter()
+            .map(|w| backend.input_private(*w))
+            .collect::<Result<Vec<_>>>()?;
+        for (i, j) in network {
+            let (min, max) = backend.compare_and_swap(&wires[i], &wires[j], 8)?;
+            wires[i] = min;
+            wires[j] = max;
+        }
+        for (wire, e) in wires.iter().zip(expected) {
+            let diff = backend.add_constant(wire, -*e)?;
+            backend.assert_zero(&diff)?;
+        }
+        Ok(())
+    }
+
+    fn test_sort(values: &[u64], expected: &[u64]) -> (Result<()>, Result<()>) {
+        let witness: Vec<_> = values.iter().map(|v| Some(f61p(*v))).collect();
+        let expected: Vec<_> = expected.iter().map(|v| f61p(*v)).collect();
+        let expected_verifier = expected.clone();
+        let n = values.len();
+        run::<F61p, _, _>(
+            move |dmc| sorting_network(dmc, &witness, &expected),
+            move |dmc| sorting_network(dmc, &vec![None; n], &expected_verifier),
+        )
+    }
+
+    #[test]
+    fn test_compare_and_swap() {
+        for (values, expected) in [
+            (vec![3, 5], vec![3, 5]),
+            (vec![5, 3], vec![3, 5]),
+            (vec![7, 7], vec![7, 7]),
+            (vec![255, 0], vec![0, 255]),
+            (vec![4, 3, 2, 1], vec![1, 2, 3, 4]),
+            (vec![10, 200, 10, 0], vec![0, 10, 10, 200]),
+            (vec![1, 2, 3, 4], vec![1, 2, 3, 4]),
+            (vec![9, 1, 5], vec![1, 5, 9]),
+        ] {
+            let (prover, verifier) = test_sort(&values, &expected);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_compare_and_swap_wrong_order_fails() {
+        let (prover, verifier) = test_sort(&[2, 1], &[2, 1]);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_sorted(values: &[u64]) -> (Result<()>, Result<()>) {
+        let witness: Vec<_> = values.iter().map(|v| f61p(*v)).collect();
+        let n = values.len();
+        run::<F61p, _, _>(
+            move |dmc| {
+                let xs = witness
+                    .iter()
+                    .map(|w| dmc.input_private(*w))
+                    .collect::<Result<Vec<_>>>()?;
+                dmc.assert_sorted(&xs, 8)
+            },
+            move |dmc| {
+                let xs = (0..n)
+                    .map(|_| dmc.input_private())
+                    .collect::<Result<Vec<_>>>()?;
+                dmc.assert_sorted(&xs, 8)
+            },
+        )
+    }
+
+    #[test]
+    fn test_assert_sorted() {
+        for values in [vec![], vec![42], vec![1, 2, 3], vec![0, 7, 7, 255]] {
+            let (prover, verifier) = test_sorted(&values);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_assert_sorted_out_of_order_fails() {
+        let (prover, verifier) = test_sorted(&[1, 5, 4, 9]);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_distinct(values: &[u64], bounded: bool) -> (Result<()>, Result<()>) {
+        fn circuit<B: BackendGadgetsT>(
+            backend: &mut B,
+            witness: &[Option<B::FieldElement>],
+            bounded: bool,
+        ) -> Result<()> {
+            let xs = witness
+                .iter()
+                .map(|w| backend.input_private(*w))
+                .collect::<Result<Vec<_>>>()?;
+            if bounded {
+                backend.assert_all_distinct_bounded(&xs, 8)
+            } else {
+                backend.assert_all_distinct(&xs)
+            }
+        }
+        let witness: Vec<_> = values.iter().map(|v| Some(f61p(*v))).collect();
+        let n = values.len();
+        run::<F61p, _, _>(
+            move |dmc| circuit(dmc, &witness, bounded),
+            move |dmc| circuit(dmc, &vec![None; n], bounded),
+        )
+    }
+
+    #[test]
+    fn test_assert_all_distinct() {
+        for bounded in [false, true] {
+            for values in [
+                vec![],
+                vec![42],
+                vec![3, 1],
+                vec![3, 1, 4, 5, 9, 2, 6],
+                vec![255, 0, 17, 16, 200, 1, 2, 3, 128, 64, 32],
+            ] {
+                let (prover, verifier) = test_distinct(&values, bounded);
+                prover.unwrap();
+                verifier.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_assert_all_distinct_duplicate_fails() {
+        for bounded in [false, true] {
+            for values in [vec![7, 7], vec![3, 1, 4, 1, 5], vec![9, 2, 6, 5, 3, 5]] {
+                let (prover, verifier) = test_distinct(&values, bounded);
+                assert!(prover.is_err());
+                assert!(verifier.is_err());
+            }
+        }
+    }
+
+    fn test_mod_reduce(a: u64, modulus: u64, bits: usize) -> (Result<()>, Result<()>) {
+        fn circuit<B: BackendGadgetsT>(
+            backend: &mut B,
+            witness: Option<B::FieldElement>,
+            a: u64,
+            modulus: u64,
+            bits: usize,
+        ) -> Result<()> {
+            let a_wire = backend.input_private(witness)?;
+            let (q, r) = backend.mod_reduce(&a_wire, modulus, bits)?;
+            let q_diff = backend.add_constant(
+                &q,
+                -u128_to_field::<B::FieldElement>(u128::from(a / modulus)),
+            )?;
+            backend.assert_zero(&q_diff)?;
+            let r_diff = backend.add_constant(
+                &r,
+                -u128_to_field::<B::FieldElement>(u128::from(a % modulus)),
+            )?;
+            backend.assert_zero(&r_diff)
+        }
+        run::<F61p, _, _>(
+            move |dmc| circuit(dmc, Some(f61p(a)), a, modulus, bits),
+            move |dmc| circuit(dmc, None, a, modulus, bits),
+        )
+    }
+
+    fn test_interval(v: u64) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let v = dmc.input_private(f61p(v))?;
+                dmc.assert_in_interval(&v, f61p(10), f61p(20), 8)
+            },
+            |dmc| {
+                let v = dmc.input_private()?;
+                dmc.assert_in_interval(&v, f61p(10), f61p(20), 8)
+            },
+        )
+    }
+
+    #[test]
+    fn test_assert_in_interval() {
+        for v in [10, 15, 20] {
+            let (prover, verifier) = test_interval(v);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_assert_in_interval_outside_fails() {
+        for v in [9, 21] {
+            let (prover, verifier) = test_interval(v);
+            assert!(prover.is_err());
+            assert!(verifier.is_err());
+        }
+    }
+
+    #[test]
+    fn test_assert_in_interval_empty_interval_fails() {
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        let v = plaintext.input_private(Some(f61p(5))).unwrap();
+        assert!(plaintext
+            .assert_in_interval(&v, f61p(20), f61p(10), 8)
+            .is_err());
+    }
+
+    // Prove that `x` is a root of `(x - 2) * (x - 5) * (x - 9)`.
+    fn test_root(x: u64) -> (Result<()>, Result<()>) {
+        // x^3 - 16 x^2 + 73 x - 90
+        let coeffs = [-f61p(90), f61p(73), -f61p(16), f61p(1)];
+        run::<F61p, _, _>(
+            move |dmc| {
+                let v = f61p(x);
+                let x = dmc.input_private(v)?;
+                let value = dmc.eval_poly(&x, &coeffs)?;
+                assert_eq!(
+                    value.value(),
+                    ((v - f61p(16)) * v + f61p(73)) * v - f61p(90)
+                );
+                dmc.assert_root(&x, &coeffs)
+            },
+            move |dmc| {
+                let x = dmc.input_private()?;
+                dmc.eval_poly(&x, &coeffs)?;
+                dmc.assert_root(&x, &coeffs)
+            },
+        )
+    }
+
+    #[test]
+    fn test_assert_root() {
+        for x in [2, 5, 9] {
+            let (prover, verifier) = test_root(x);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_assert_root_non_root_fails() {
+        let (prover, verifier) = test_root(3);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn parity_circuit<B: BackendGadgetsT<FieldElement = F2>>(
+        backend: &mut B,
+        bits: &[Option<F2>],
+        expected: F2,
+    ) -> Result<()> {
+        let wires = bits
+            .iter()
+            .map(|b| backend.input_private(*b))
+            .collect::<Result<Vec<_>>>()?;
+        let parity = backend.parity(&wires)?;
+        let diff = backend.add_constant(&parity, expected)?;
+        backend.assert_zero(&diff)
+    }
+
+    #[test]
+    fn test_parity() {
+        for (bits, expected) in [
+            (vec![1, 1, 0, 1, 1], F2::ZERO),
+            (vec![1, 0, 1, 1], F2::ONE),
+            (vec![], F2::ZERO),
+        ] {
+            let bits: Vec<_> = bits.into_iter().map(|b| Some(F2::from(b == 1))).collect();
+            let mut plaintext = PlaintextBackend::<F2>::new();
+            parity_circuit(&mut plaintext, &bits, expected).unwrap();
+            assert_eq!(plaintext.stats().mul, 0);
+            assert!(parity_circuit(&mut plaintext, &bits, expected + F2::ONE).is_err());
+
+            let n = bits.len();
+            let (prover, verifier) = run::<F40b, _, _>(
+                move |dmc| parity_circuit(dmc, &bits, expected),
+                move |dmc| parity_circuit(dmc, &vec![None; n], expected),
+            );
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    fn test_dot_product(expected: u64) -> (Result<()>, Result<()>) {
+        fn circuit<B: BackendGadgetsT<FieldElement = F61p>>(
+            backend: &mut B,
+            witness: [Option<F61p>; 6],
+            expected: F61p,
+        ) -> Result<()> {
+            let wires = witness
+                .iter()
+                .map(|w| backend.input_private(*w))
+                .collect::<Result<Vec<_>>>()?;
+            backend.assert_dot_product(&wires[..3], &wires[3..], expected)
+        }
+        let witness = [1, 2, 3, 4, 5, 6].map(|x| Some(f61p(x)));
+        run::<F61p, _, _>(
+            move |dmc| circuit(dmc, witness, f61p(expected)),
+            move |dmc| circuit(dmc, [None; 6], f61p(expected)),
+        )
+    }
+
+    #[test]
+    fn test_assert_dot_product() {
+        let (prover, verifier) = test_dot_product(32);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_assert_dot_product_wrong_value_fails() {
+        let (prover, verifier) = test_dot_product(31);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    #[test]
+    fn test_bit_reverse() {
+        let bits = [1, 1, 0, 1, 0].map(|b| F2::from(b == 1));
+        let (prover, verifier) = run::<F40b, _, _>(
+            move |dmc| {
+                let wires = bits
+                    .iter()
+                    .map(|b| dmc.input_private(*b))
+                    .collect::<Result<Vec<_>>>()?;
+                let reversed = dmc.bit_reverse(&wires)?;
+                for (wire, bit) in reversed.iter().zip(bits.iter().rev()) {
+                    assert_eq!(wire.value(), *bit);
+                }
+                assert_eq!(dmc.bit_reverse(&reversed)?, wires);
+                Ok(())
+            },
+            |dmc| {
+                let wires = (0..5)
+                    .map(|_| dmc.input_private())
+                    .collect::<Result<Vec<_>>>()?;
+                let reversed = dmc.bit_reverse(&wires)?;
+                assert_eq!(dmc.bit_reverse(&reversed)?, wires);
+                Ok(())
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_pow_by_bits() {
+        // 5 is `101` in binary, least significant bit first.
+        let bits = [1, 0, 1].map(f61p);
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                let base = dmc.input_private(f61p(3))?;
+                let exp_bits = bits
+                    .iter()
+                    .map(|b| dmc.input_private(*b))
+                    .collect::<Result<Vec<_>>>()?;
+                let pow = dmc.pow_by_bits(&base, &exp_bits)?;
+                assert_eq!(pow.value(), f61p(3).pow(5));
+                let diff = dmc.addc(&pow, -f61p(243))?;
+                dmc.assert_zero(&diff)
+            },
+            |dmc| {
+                let base = dmc.input_private()?;
+                let exp_bits = (0..3)
+                    .map(|_| dmc.input_private())
+                    .collect::<Result<Vec<_>>>()?;
+                let pow = dmc.pow_by_bits(&base, &exp_bits)?;
+                let diff = dmc.addc(&pow, -f61p(243))?;
+                dmc.assert_zero(&diff)
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    // Prove knowledge of `x` such that `7^x == h`.
+    fn test_discrete_log(h: F61p) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let x = dmc.input_private(f61p(1000))?;
+                dmc.assert_discrete_log(&x, f61p(7), h, 10)
+            },
+            move |dmc| {
+                let x = dmc.input_private()?;
+                dmc.assert_discrete_log(&x, f61p(7), h, 10)
+            },
+        )
+    }
+
+    #[test]
+    fn test_assert_discrete_log() {
+        let (prover, verifier) = test_discrete_log(f61p(7).pow(1000));
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_assert_discrete_log_wrong_value_fails() {
+        let (prover, verifier) = test_discrete_log(f61p(7).pow(1001));
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_assert_linear(target: u64) -> (Result<()>, Result<()>) {
+        // 3 * 5 + 7 * 2 - 1 * 4 = 25
+        fn circuit<B: BackendGadgetsT>(
+            backend: &mut B,
+            witness: Option<[B::FieldElement; 3]>,
+            weights: [B::FieldElement; 3],
+            target: B::FieldElement,
+        ) -> Result<()> {
+            let mut terms = Vec::new();
+            for (i, w) in weights.iter().enumerate() {
+                terms.push((*w, backend.input_private(witness.map(|x| x[i]))?));
+            }
+            backend.assert_linear(&terms, target)
+        }
+        let weights = [f61p(3), f61p(7), -f61p(1)];
+        run::<F61p, _, _>(
+            move |dmc| {
+                circuit(
+                    dmc,
+                    Some([f61p(5), f61p(2), f61p(4)]),
+                    weights,
+                    f61p(target),
+                )
+            },
+            move |dmc| circuit(dmc, None, weights, f61p(target)),
+        )
+    }
+
+    #[test]
+    fn test_assert_linear_satisfied() {
+        let (prover, verifier) = test_assert_linear(25);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_assert_linear_unsatisfied_fails() {
+        let (prover, verifier) = test_assert_linear(26);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_is_equal(a: u64, b: u64, expected: u64) -> (Result<()>, Result<()>) {
+        fn circuit<B: BackendGadgetsT>(
+            backend: &mut B,
+            witness: Option<(B::FieldElement, B::FieldElement)>,
+            expected: u64,
+        ) -> Result<()> {
+            let a = backend.input_private(witness.map(|(a, _)| a))?;
+            let b = backend.input_private(witness.map(|(_, b)| b))?;
+            let bit = backend.is_equal(&a, &b)?;
+            let diff = backend.add_constant(
+                &bit,
+                -u128_to_field::<B::FieldElement>(u128::from(expected)),
+            )?;
+            backend.assert_zero(&diff)
+        }
+        run::<F61p, _, _>(
+            move |dmc| circuit(dmc, Some((f61p(a), f61p(b))), expected),
+            move |dmc| circuit(dmc, None, expected),
+        )
+    }
+
+    #[test]
+    fn test_is_equal_equal_values() {
+        for a in [0, 1, 12345] {
+            let (prover, verifier) = test_is_equal(a, a, 1);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_is_equal_unequal_values() {
+        for (a, b) in [(0, 1), (1, 0), (12345, 54321)] {
+            let (prover, verifier) = test_is_equal(a, b, 0);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+        let (prover, verifier) = test_is_equal(3, 4, 1);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_select(cond: u64, expected: u64) -> (Result<()>, Result<()>) {
+        fn circuit<B: BackendGadgetsT>(
+            backend: &mut B,
+            cond: Option<B::FieldElement>,
+            expected: u64,
+        ) -> Result<()> {
+            let cond = backend.input_private(cond)?;
+            backend.assert_bool(&cond)?;
+            let a = backend.input_public(u128_to_field(17))?;
+            let b = backend.input_public(u128_to_field(42))?;
+            let out = backend.select(&cond, &a, &b)?;
+            let diff = backend.add_constant(
+                &out,
+                -u128_to_field::<B::FieldElement>(u128::from(expected)),
+            )?;
+            backend.assert_zero(&diff)
+        }
+        run::<F61p, _, _>(
+            move |dmc| circuit(dmc, Some(f61p(cond)), expected),
+            move |dmc| circuit(dmc, None, expected),
+        )
+    }
+
+    #[test]
+    fn test_select_both_branches() {
+        for (cond, expected) in [(1, 17), (0, 42)] {
+            let (prover, verifier) = test_select(cond, expected);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+        let (prover, verifier) = test_select(1, 42);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    #[test]
+    fn test_select_non_boolean_condition_fails() {
+        // The output 2 * (17 - 42) + 42 == -8 matches, only `assert_bool` fails.
+        let (prover, verifier) = test_select(2, (1 << 61) - 1 - 8);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_assert_ne(a: u64, b: u64) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let a = dmc.input_private(f61p(a))?;
+                let b = dmc.input_private(f61p(b))?;
+                dmc.assert_ne(&a, &b)
+            },
+            |dmc| {
+                let a = dmc.input_private()?;
+                let b = dmc.input_private()?;
+                dmc.assert_ne(&a, &b)
+            },
+        )
+    }
+
+    #[test]
+    fn test_assert_ne_unequal_values() {
+        for (a, b) in [(0, 1), (1, 0), (12345, 54321)] {
+            let (prover, verifier) = test_assert_ne(a, b);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_assert_ne_equal_values_fails() {
+        for a in [0, 7] {
+            let (prover, verifier) = test_assert_ne(a, a);
+            assert!(prover.is_err());
+            assert!(verifier.is_err());
+        }
+    }
+
+    #[test]
+    fn test_mod_reduce_pairs() {
+        for (a, modulus, bits) in [
+            (100, 7, 8),
+            (5, 7, 8),
+            (0, 3, 8),
+            (255, 16, 8),
+            (1000, 1000, 10),
+            (12345, 1, 16),
+            ((1 << 40) + 17, 1 << 12, 41),
+        ] {
+            let (prover, verifier) = test_mod_reduce(a, modulus, bits);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_mod_reduce_out_of_range_fails() {
+        // The quotient 500 does not fit in 8 bits.
+        let (prover, verifier) = test_mod_reduce(1000, 2, 8);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_range(value: u64, bits: usize) -> (Result<()>, Result<()>) {
+        fn circuit<B: BackendGadgetsT>(
+            backend: &mut B,
+            witness: Option<B::FieldElement>,
+            value: u64,
+            bits: usize,
+        ) -> Result<()> {
+            let v = backend.input_private(witness)?;
+            let value_bits = backend.assert_range(&v, bits)?;
+            assert_eq!(value_bits.len(), bits);
+            // The returned bits are the bits of `value`.
+            for (i, bit) in value_bits.iter().enumerate() {
+                let expected = u128_to_field::<B::FieldElement>(u128::from((value >> i) & 1));
+                let diff = backend.add_constant(bit, -expected)?;
+                backend.assert_zero(&diff)?;
+            }
+            Ok(())
+        }
+        run::<F61p, _, _>(
+            move |dmc| circuit(dmc, Some(f61p(value)), value, bits),
+            move |dmc| circuit(dmc, None, value, bits),
+        )
+    }
+
+    #[test]
+    fn test_assert_range() {
+        for (value, bits) in [
+            (0, 0),
+            (0, 8),
+            (1, 1),
+            (200, 8),
+            (255, 8),
+            ((1 << 59) + 3, 60),
+        ] {
+            let (prover, verifier) = test_range(value, bits);
+            prover.unwrap();
+            verifier.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_assert_range_out_of_range_fails() {
+        for (value, bits) in [(1, 0), (256, 8), (2, 1)] {
+            let (prover, verifier) = test_range(value, bits);
+            assert!(prover.is_err());
+            assert!(verifier.is_err());
+        }
+    }
+
+    #[test]
+    fn test_assert_range_too_many_bits_fails() {
+        let (prover, verifier) = test_range(1, 61);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    fn test_bit_length(value: u64, bits: usize) -> (Result<()>, Result<()>) {
+        run::<F61p, _, _>(
+            move |dmc| {
+                let v = dmc.input_private(f61p(value))?;
+                dmc.assert_bit_length(&v, bits)
+            },
+            move |dmc| {
+                let v = dmc.input_private()?;
+                dmc.assert_bit_length(&v, bits)
+            },
+        )
+    }
+
+    #[test]
+    fn test_assert_bit_length() {
+        for bits in [1, 8, 32] {
+            for value in [1 << (bits - 1), (1 << bits) - 1] {
+                let (prover, verifier) = test_bit_length(value, bits);
+                prover.unwrap();
+                verifier.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_assert_bit_length_out_of_range_fails() {
+        for (value, bits) in [(0, 1), (0, 8), (127, 8), (256, 8), (1 << 31, 31)] {
+            let (prover, verifier) = test_bit_length(value, bits);
+            assert!(prover.is_err());
+            assert!(verifier.is_err());
+        }
+    }
+
+    #[test]
+    fn test_assert_bit_length_zero_bits_fails() {
+        let (prover, verifier) = test_bit_length(0, 0);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    #[test]
+    fn test_less_than_too_many_bits_fails() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                let a = dmc.input_private(f61p(1))?;
+                dmc.less_than(&a, &a, 60).map(|_| ())
+            },
+            |dmc| {
+                let a = dmc.input_private()?;
+                dmc.less_than(&a, &a, 60).map(|_| ())
+            },
+        );
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Recording of the gates evaluated by the prover as a DAG, for debugging and
+//! teaching with small circuits.
+//!
+//! Each gate is a node of the graph, with an edge from the gates computing its
+//! inputs. Every MAC of the prover carries a [`WireTag`] assigned when it is
+//! created, so a wire refers to the gate which produced it, even when several
+//! wires have the same value.
+
+use scuttlebutt::field::FiniteField;
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::homcom::MacProver;
+
+/// The tag identifying a wire in the gate graph.
+///
+/// Tags are taken from a global counter, so they are unique across provers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct WireTag(u64);
+
+impl WireTag {
+    /// Return a tag which was not returned before.
+    pub(crate) fn fresh() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A DAG of the gates evaluated by a prover.
+#[derive(Default)]
+pub(crate) struct GateGraph {
+    nodes: Vec<String>,
+    edges: Vec<(usize, usize)>,
+    wires: HashMap<WireTag, usize>,
+}
+
+impl GateGraph {
+    /// Record a gate named `label` with `inputs`, and producing `output` if any.
+    pub(crate) fn gate<FE: FiniteField>(
+        &mut self,
+        label: &str,
+        inputs: &[&MacProver<FE>],
+        output: Option<&MacProver<FE>>,
+    ) {
+        let id = self.nodes.len();
+        self.nodes.push(label.to_string());
+        for input in inputs {
+            // Wires which were not produced by a recorded gate have no node.
+            if let Some(from) = self.wires.get(&input.tag()) {
+                self.edges.push((*from, id));
+            }
+        }
+        if let Some(output) = output {
+            self.wires.insert(output.tag(), id);
+        }
+    }
+
+    /// Return the graph in the Graphviz `dot` format.
+    pub(crate) fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph gates {\n");
+        for (id, label) in self.nodes.iter().enumerate() {
+            writeln!(dot, "    n{} [label=\"{}\"];", id, label).unwrap();
+        }
+        for (from, to) in self.edges.iter() {
+            writeln!(dot, "    n{} -> n{};", from, to).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,228 @@
+//! Proofs of knowledge of a GHASH preimage.
+//!
+//! GHASH is the polynomial hash used by AES-GCM: given a key `H` and blocks
+//! `x_1, ..., x_n` of [`F128b`], the digest is `y_n` where `y_0 = 0` and
+//! `y_i = (y_{i-1} + x_i) * H`. Note that the blocks are elements of [`F128b`]
+//! in the representation of `scuttlebutt`, not in the bit-reflected
+//! representation of the GCM specification.
+//!
+//! The circuit works over boolean wires, with each block represented by its
+//! 128 bits (least significant first). As the key is public, multiplying by
+//! `H` is linear over `F2` and only requires additions.
+
+use crate::backend_trait::BackendT;
+use eyre::Result;
+use scuttlebutt::{
+    field::{F128b, FiniteField, F2},
+    ring::FiniteRing,
+    serialization::CanonicalSerialize,
+};
+
+/// Compute the GHASH of `blocks` with `key`.
+pub fn ghash(key: F128b, blocks: &[F128b]) -> F128b {
+    blocks
+        .iter()
+        .fold(F128b::ZERO, |acc, block| (acc + *block) * key)
+}
+
+pub(crate) fn f128b_from_u128(x: u128) -> F128b {
+    F128b::from_bytes(&x.to_le_bytes().into()).unwrap()
+}
+
+pub(crate) fn bits(x: F128b) -> Vec<F2> {
+    x.bit_decomposition()
+        .iter()
+        .map(|b| if *b { F2::ONE } else { F2::ZERO })
+        .collect()
+}
+
+/// Compute the GHASH with `key` of the blocks given as 128 bit wires each, and
+/// return the 128 bit wires of the digest.
+pub fn ghash_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    key: F128b,
+    blocks: &[Vec<B::Wire>],
+) -> Result<Vec<B::Wire>> {
+    let mut acc = Vec::with_capacity(128);
+    for _ in 0..128 {
+        acc.push(backend.constant(F2::ZERO)?);
+    }
+    for block in blocks {
+        let mut sum = Vec::with_capacity(128);
+        for (a, b) in acc.iter().zip(block.iter()) {
+            sum.push(backend.add(a, b)?);
+        }
+        acc = mul_constant_circuit(backend, key, &sum)?;
+    }
+    Ok(acc)
+}
+
+/// Multiply the element given by its 128 bit wires `x` by the public `key`.
+pub(crate) fn mul_constant_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    key: F128b,
+    x: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    // `columns[j]` holds the bits of `x^j * key`: output bit `i` of the product
+    // of `x` by `key` is the sum of the bits `j` of `x` such that `columns[j][i]` is set.
+    let columns: Vec<Vec<F2>> = (0..128)
+        .map(|j| bits(f128b_from_u128(1 << j) * key))
+        .collect();
+    linear_circuit(backend, &columns, x)
+}
+
+// Return the wires `out[i] = sum of x[j] such that columns[j][i] is set`.
+pub(crate) fn linear_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    columns: &[Vec<F2>],
+    x: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    let mut out = Vec::with_capacity(128);
+    for i in 0..128 {
+        let mut acc = backend.constant(F2::ZERO)?;
+        for (j, column) in columns.iter().enumerate() {
+            if column[i] == F2::ONE {
+                acc = backend.add(&acc, &x[j])?;
+            }
+        }
+        out.push(acc);
+    }
+    Ok(out)
+}
+
+/// Assert that the GHASH with `key` of the blocks given as 128 bit wires each is
+/// the public `digest`.
+///
+/// Unlike [`assert_ghash_preimage`], the blocks are existing wires, e.g., the
+/// output of another circuit, which makes this suitable to open a commitment.
+pub fn assert_ghash_digest<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    key: F128b,
+    blocks: &[Vec<B::Wire>],
+    digest: F128b,
+) -> Result<()> {
+    let out = ghash_circuit(backend, key, blocks)?;
+    for (wire, bit) in out.iter().zip(bits(digest)) {
+        let diff = backend.add_constant(wire, bit)?;
+        backend.assert_zero(&diff)?;
+    }
+    Ok(())
+}
+
+/// Prove knowledge of `nb_blocks` blocks whose GHASH with `key` is `digest`.
+///
+/// The prover provides the blocks in `preimage`, and the verifier provides `None`.
+pub fn assert_ghash_preimage<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    key: F128b,
+    preimage: Option<&[F128b]>,
+    nb_blocks: usize,
+    digest: F128b,
+) -> Result<()> {
+    let mut blocks = Vec::with_capacity(nb_blocks);
+    for i in 0..nb_blocks {
+        let block_bits = preimage.map(|p| bits(p[i]));
+        let mut block = Vec::with_capacity(128);
+        for j in 0..128 {
+            block.push(backend.input_private(block_bits.as_ref().map(|b| b[j]))?);
+        }
+        blocks.push(block);
+    }
+    assert_ghash_digest(backend, key, &blocks, digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_ghash_digest, assert_ghash_preimage, bits, ghash};
+    use crate::backend_trait::BackendT;
+    use crate::test_utils::run;
+    use eyre::Result;
+    use rand::SeedableRng;
+    use scuttlebutt::{
+        field::{F128b, F40b, F2},
+        ring::FiniteRing,
+        AesRng,
+    };
+
+    fn test_preimage(claimed: Vec<F128b>, preimage: Vec<F128b>) -> (Result<()>, Result<()>) {
+        let mut rng = AesRng::from_seed(Default::default());
+        let key = F128b::random(&mut rng);
+        let digest = ghash(key, &preimage);
+        let nb_blocks = claimed.len();
+        run::<F40b, _, _>(
+            move |dmc| assert_ghash_preimage(dmc, key, Some(&claimed), nb_blocks, digest),
+            move |dmc| assert_ghash_preimage(dmc, key, None, nb_blocks, digest),
+        )
+    }
+
+    // Prove that the GHASH of the sum of two private blocks `x` and `y` is the
+    // GHASH of `x + y` for the expected `y`.
+    fn test_digest(y: F128b, expected_y: F128b) -> (Result<()>, Result<()>) {
+        fn circuit<B: BackendT<FieldElement = F2>>(
+            backend: &mut B,
+            key: F128b,
+            x_y: Option<(F128b, F128b)>,
+            digest: F128b,
+        ) -> Result<()> {
+            let x_bits = x_y.map(|(x, _)| bits(x));
+            let y_bits = x_y.map(|(_, y)| bits(y));
+            let mut block = Vec::with_capacity(128);
+            for i in 0..128 {
+                let x = backend.input_private(x_bits.as_ref().map(|b| b[i]))?;
+                let y = backend.input_private(y_bits.as_ref().map(|b| b[i]))?;
+                block.push(backend.add(&x, &y)?);
+            }
+            assert_ghash_digest(backend, key, &[block], digest)
+        }
+        let mut rng = AesRng::from_seed(Default::default());
+        let key = F128b::random(&mut rng);
+        let x = F128b::random(&mut rng);
+        let digest = ghash(key, &[x + expected_y]);
+        run::<F40b, _, _>(
+            move |dmc| circuit(dmc, key, Some((x, y)), digest),
+            move |dmc| circuit(dmc, key, None, digest),
+        )
+    }
+
+    #[test]
+    fn test_ghash_digest() {
+        let y = F128b::random(&mut AesRng::from_seed(Default::default()));
+        let (prover, verifier) = test_digest(y, y);
+        prover.unwrap();
+        verifier.unwrap();
+
+        let (prover, verifier) = test_digest(y + F128b::ONE, y);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+
+    #[test]
+    fn test_ghash_matches_definition() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let key = F128b::random(&mut rng);
+        let x1 = F128b::random(&mut rng);
+        let x2 = F128b::random(&mut rng);
+        assert_eq!(ghash(key, &[]), F128b::ZERO);
+        assert_eq!(ghash(key, &[x1, x2]), x1 * key * key + x2 * key);
+    }
+
+    #[test]
+    fn test_ghash_preimage() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let preimage: Vec<_> = (0..3).map(|_| F128b::random(&mut rng)).collect();
+        let (prover, verifier) = test_preimage(preimage.clone(), preimage);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_ghash_wrong_preimage_fails() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let preimage: Vec<_> = (0..3).map(|_| F128b::random(&mut rng)).collect();
+        let mut claimed = preimage.clone();
+        claimed[1] += F128b::ONE;
+        let (prover, verifier) = test_preimage(claimed, preimage);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+}
@@ -4,6 +4,9 @@
 //! `check_zero`, `open` and `check_multiply`.
 //! These functionalities are used for diet Mac'n'Cheese and in the edabits
 //! conversion protocol for field-switching.
+use crate::error::DmcError;
+#[cfg(feature = "debug-graph")]
+use crate::gate_graph::WireTag;
 use eyre::{eyre, Result};
 use generic_array::{typenum::Unsigned, GenericArray};
 use log::{debug, info, warn};
@@ -14,7 +17,7 @@ use scuttlebutt::field::Degree;
 use scuttlebutt::ring::FiniteRing;
 use scuttlebutt::serialization::CanonicalSerialize;
 use scuttlebutt::{field::FiniteField, AbstractChannel, AesRng, Block};
-use std::time::Instant;
+use std::{num::NonZeroUsize, time::Instant};
 use subtle::{Choice, ConditionallySelectable};
 
 #[allow(unused)]
@@ -173,17 +176,38 @@ pub(crate) trait HomComT: Sized {
 /// `MacProver(x, m)` and its corresponding `MacVerifier(k)`, the following
 /// equation holds for a global key `Δ` known only to the verifier: `m = k + Δ
 /// x`.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug)]
 pub struct MacProver<F: FiniteField>(
     /// The prover's value `x`.
     F::PrimeField,
     /// The prover's MAC `m`.
     F,
+    /// The tag of the wire in the gate graph.
+    #[cfg(feature = "debug-graph")]
+    WireTag,
 );
 
+// Equality ignores the tag: two wires with the same value and MAC are equal.
+impl<F: FiniteField> PartialEq for MacProver<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1 == other.1
+    }
+}
+
 impl<F: FiniteField> MacProver<F> {
+    /// Build a MAC from a value `x` and a MAC `m`.
+    ///
+    /// The MAC is only sound if `m = k + Δ x` for the key `k` held by the
+    /// verifier. This holds for MACs computed from MACs of the protocol by
+    /// linear operations mirrored by the verifier, but not for arbitrary parts:
+    /// a MAC which does not match the verifier's key makes the proof fail.
     pub fn new(x: F::PrimeField, m: F) -> Self {
-        Self(x, m)
+        Self(
+            x,
+            m,
+            #[cfg(feature = "debug-graph")]
+            WireTag::fresh(),
+        )
     }
 
     pub fn value(&self) -> F::PrimeField {
@@ -197,6 +221,12 @@ impl<F: FiniteField> MacProver<F> {
     pub fn decompose(&self) -> (F::PrimeField, F) {
         (self.0, self.1)
     }
+
+    /// Return the tag identifying the wire in the gate graph.
+    #[cfg(feature = "debug-graph")]
+    pub(crate) fn tag(&self) -> WireTag {
+        self.2
+    }
 }
 
 impl<F: FiniteField> Default for MacProver<F> {
@@ -219,6 +249,11 @@ pub struct MacVerifier<F: FiniteField>(
 );
 
 impl<F: FiniteField> MacVerifier<F> {
+    /// Build a MAC from a key `k`.
+    ///
+    /// The same soundness caveat as for [`MacProver::new`] applies: the key
+    /// must be obtained from keys of the protocol by linear operations mirrored
+    /// by the prover.
     pub fn new(k: F) -> Self {
         Self(k)
     }
@@ -234,9 +269,25 @@ impl<F: FiniteField> Default for MacVerifier<F> {
     }
 }
 
+/// Check that a prover's MAC and a verifier's key authenticate `value` under
+/// the global key `delta`.
+///
+/// This is the relationship between [`MacProver`] and [`MacVerifier`]: given a
+/// `MacProver(x, m)` and its corresponding `MacVerifier(k)`, it returns whether
+/// `m = k + Δ x`. Note that the check is not constant-time, and is meant for
+/// tests and debugging rather than for use within the protocol.
+pub fn verify_mac<FE: FiniteField>(
+    value: FE::PrimeField,
+    prover_mac: FE,
+    verifier_mac: FE,
+    delta: FE,
+) -> bool {
+    prover_mac == verifier_mac + value * delta
+}
+
 impl<FE: FiniteField> ConditionallySelectable for MacProver<FE> {
     fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
-        MacProver(
+        MacProver::new(
             FE::PrimeField::conditional_select(&a.0, &b.0, choice),
             FE::conditional_select(&a.1, &b.1, choice),
         )
@@ -253,6 +304,7 @@ impl<FE: FiniteField> ConditionallySelectable for MacVerifier<FE> {
 pub struct FComProver<FE: FiniteField> {
     svole_sender: Sender<FE>,
     voles: Vec<(FE::PrimeField, FE)>,
+    vole_chunk: usize,
 }
 
 fn make_x_i<FE: FiniteField>(i: usize) -> FE {
@@ -299,6 +351,20 @@ impl<FE: FiniteField> StateMultCheckProver<FE> {
         self.chi_power = self.chi;
         self.cnt = 0;
     }
+
+    /// Return the number of multiplication triples accumulated since the last check.
+    pub fn count(&self) -> usize {
+        self.cnt
+    }
+
+    /// Read a fresh challenge from the verifier, used for the triples pushed
+    /// after the last check.
+    pub fn refresh_challenge<C: AbstractChannel>(&mut self, channel: &mut C) -> Result<()> {
+        debug_assert_eq!(self.cnt, 0);
+        self.chi = channel.read_serializable()?;
+        self.chi_power = self.chi;
+        Ok(())
+    }
 }
 
 impl<FE: FiniteField> FComProver<FE> {
@@ -312,6 +378,7 @@ impl<FE: FiniteField> FComProver<FE> {
         Ok(Self {
             svole_sender: Sender::init(channel, rng, lpn_setup, lpn_extend)?,
             voles: Vec::new(),
+            vole_chunk: 1,
         })
     }
 
@@ -324,9 +391,32 @@ impl<FE: FiniteField> FComProver<FE> {
         Ok(Self {
             svole_sender: self.svole_sender.duplicate(channel, rng)?,
             voles: Vec::new(),
+            vole_chunk: self.vole_chunk,
         })
     }
 
+    /// Set the number of sVOLE extensions performed each time the voles run out.
+    ///
+    /// Each extension produces a number of voles fixed by the LPN parameters,
+    /// so the voles are produced in chunks of `n` extensions. The verifier must
+    /// use the same value.
+    pub fn set_vole_chunk(&mut self, n: NonZeroUsize) {
+        self.vole_chunk = n.get();
+    }
+
+    fn extend<C: AbstractChannel, RNG: CryptoRng + Rng>(
+        &mut self,
+        channel: &mut C,
+        rng: &mut RNG,
+    ) -> Result<()> {
+        let mut extension = Vec::new();
+        for _ in 0..self.vole_chunk {
+            self.svole_sender.send(channel, rng, &mut extension)?;
+            self.voles.append(&mut extension);
+        }
+        Ok(())
+    }
+
     /// Returns a random mac.
     pub fn random<C: AbstractChannel, RNG: CryptoRng + Rng>(
         &mut self,
@@ -334,11 +424,11 @@ impl<FE: FiniteField> FComProver<FE> {
         rng: &mut RNG,
     ) -> Result<MacProver<FE>> {
         match self.voles.pop() {
-            Some(e) => Ok(MacProver(e.0, e.1)),
+            Some(e) => Ok(MacProver::new(e.0, e.1)),
             None => {
-                self.svole_sender.send(channel, rng, &mut self.voles)?;
+                self.extend(channel, rng)?;
                 match self.voles.pop() {
-                    Some(e) => Ok(MacProver(e.0, e.1)),
+                    Some(e) => Ok(MacProver::new(e.0, e.1)),
                     None => Err(eyre!("svole failed for random")),
                 }
             }
@@ -391,36 +481,36 @@ impl<FE: FiniteField> FComProver<FE> {
     /// Add a constant to a Mac.
     #[inline]
     pub fn affine_add_cst(&self, cst: FE::PrimeField, x: MacProver<FE>) -> MacProver<FE> {
-        MacProver(cst + x.0, x.1)
+        MacProver::new(cst + x.0, x.1)
     }
 
     /// Multiply by a constant a Mac.
     #[inline]
     pub fn affine_mult_cst(&self, cst: FE::PrimeField, x: MacProver<FE>) -> MacProver<FE> {
-        MacProver(cst * x.0, cst * (x.1))
+        MacProver::new(cst * x.0, cst * (x.1))
     }
 
     /// Add two Macs.
     #[inline]
     pub fn add(&self, a: MacProver<FE>, b: MacProver<FE>) -> MacProver<FE> {
-        let MacProver(a, a_mac) = a;
-        let MacProver(b, b_mac) = b;
-        MacProver(a + b, a_mac + b_mac)
+        let MacProver(a, a_mac, ..) = a;
+        let MacProver(b, b_mac, ..) = b;
+        MacProver::new(a + b, a_mac + b_mac)
     }
 
     /// Negative Mac.
     #[inline]
     pub fn neg(&self, a: MacProver<FE>) -> MacProver<FE> {
-        let MacProver(a, a_mac) = a;
-        MacProver(-a, -a_mac)
+        let MacProver(a, a_mac, ..) = a;
+        MacProver::new(-a, -a_mac)
     }
 
     /// Subtraction of two Macs.
     #[inline]
     pub fn sub(&self, a: MacProver<FE>, b: MacProver<FE>) -> MacProver<FE> {
-        let MacProver(a, a_mac) = a;
-        let MacProver(b, b_mac) = b;
-        MacProver(a - b, a_mac - b_mac)
+        let MacProver(a, a_mac, ..) = a;
+        let MacProver(b, b_mac, ..) = b;
+        MacProver::new(a - b, a_mac - b_mac)
     }
 
     /// Check that a batch of Macs are zero.
@@ -434,7 +524,7 @@ impl<FE: FiniteField> FComProver<FE> {
 
         let mut m = FE::ZERO;
         let mut b = true;
-        for MacProver(x, x_mac) in x_mac_batch.iter() {
+        for MacProver(x, x_mac, ..) in x_mac_batch.iter() {
             b = b && *x == FE::PrimeField::ZERO;
             let chi = FE::random(&mut rng);
             m += chi * *x_mac;
@@ -446,7 +536,7 @@ impl<FE: FiniteField> FComProver<FE> {
             Ok(())
         } else {
             warn!("check_zero fails");
-            Err(eyre!("check_zero failed"))
+            Err(DmcError::CheckZeroFailed.into())
         }
     }
 
@@ -457,7 +547,7 @@ impl<FE: FiniteField> FComProver<FE> {
         batch: &[MacProver<FE>],
     ) -> Result<()> {
         let mut hasher = blake3::Hasher::new();
-        for MacProver(x, _) in batch.iter() {
+        for MacProver(x, ..) in batch.iter() {
             channel.write_serializable::<FE::PrimeField>(x)?;
             hasher.update(&x.to_bytes());
         }
@@ -466,7 +556,7 @@ impl<FE: FiniteField> FComProver<FE> {
         let mut rng = AesRng::from_seed(seed);
 
         let mut m = FE::ZERO;
-        for MacProver(_, x_mac) in batch.iter() {
+        for MacProver(_, x_mac, ..) in batch.iter() {
             let chi = FE::random(&mut rng);
             m += chi * *x_mac;
         }
@@ -489,7 +579,9 @@ impl<FE: FiniteField> FComProver<FE> {
         let chi = channel.read_serializable()?;
         let mut chi_power = chi;
 
-        for (MacProver(x, x_mac), MacProver(y, y_mac), MacProver(_z, z_mac)) in triples.iter() {
+        for (MacProver(x, x_mac, ..), MacProver(y, y_mac, ..), MacProver(_z, z_mac, ..)) in
+            triples.iter()
+        {
             let a0 = *x_mac * *y_mac;
             let a1 = *y * *x_mac + *x * *y_mac - *z_mac;
 
@@ -504,7 +596,7 @@ impl<FE: FiniteField> FComProver<FE> {
         let mut mask_mac = FE::ZERO;
 
         for i in 0..Degree::<FE>::USIZE {
-            let MacProver(u, u_mac) = self.random(channel, rng)?;
+            let MacProver(u, u_mac, ..) = self.random(channel, rng)?;
             let x_i: FE = make_x_i(i);
             mask += u * x_i;
             mask_mac += u_mac * x_i;
@@ -526,7 +618,7 @@ impl<FE: FiniteField> FComProver<FE> {
         state: &mut StateMultCheckProver<FE>,
         triple: &(MacProver<FE>, MacProver<FE>, MacProver<FE>),
     ) -> Result<()> {
-        let (MacProver(x, x_mac), MacProver(y, y_mac), MacProver(_z, z_mac)) = triple;
+        let (MacProver(x, x_mac, ..), MacProver(y, y_mac, ..), MacProver(_z, z_mac, ..)) = triple;
         let a0 = *x_mac * *y_mac;
         let a1 = *y * *x_mac + *x * *y_mac - *z_mac;
 
@@ -538,6 +630,35 @@ impl<FE: FiniteField> FComProver<FE> {
         Ok(())
     }
 
+    /// Push the relation `z = sum_i x_i * y_i` for later checking.
+    ///
+    /// The relation is checked as a single multiplication triple, whatever the
+    /// length of `x` and `y`.
+    pub fn quicksilver_push_dot_product(
+        &mut self,
+        state: &mut StateMultCheckProver<FE>,
+        x: &[MacProver<FE>],
+        y: &[MacProver<FE>],
+        z: &MacProver<FE>,
+    ) -> Result<()> {
+        if x.len() != y.len() {
+            return Err(eyre!("Vectors of a dot product differ in length"));
+        }
+        let mut a0 = FE::ZERO;
+        let mut a1 = -z.mac();
+        for (MacProver(x, x_mac, ..), MacProver(y, y_mac, ..)) in x.iter().zip(y) {
+            a0 += *x_mac * *y_mac;
+            a1 += *y * *x_mac + *x * *y_mac;
+        }
+
+        state.sum_a0 += a0 * state.chi_power;
+        state.sum_a1 += a1 * state.chi_power;
+        state.chi_power *= state.chi;
+        state.cnt += 1;
+
+        Ok(())
+    }
+
     /// Finalize the check for the list of pushed multiplication triples.
     pub fn quicksilver_finalize<C: AbstractChannel, RNG: CryptoRng + Rng>(
         &mut self,
@@ -550,7 +671,7 @@ impl<FE: FiniteField> FComProver<FE> {
         let mut mask_mac = FE::ZERO;
 
         for i in 0..Degree::<FE>::USIZE {
-            let MacProver(u, u_mac) = self.random(channel, rng)?;
+            let MacProver(u, u_mac, ..) = self.random(channel, rng)?;
             let x_i: FE = make_x_i(i);
             mask += u * x_i;
             mask_mac += u_mac * x_i;
@@ -579,6 +700,7 @@ pub struct FComVerifier<FE: FiniteField> {
     delta: FE,
     svole_receiver: Receiver<FE>,
     voles: Vec<FE>,
+    vole_chunk: usize,
 }
 
 pub struct StateMultCheckVerifier<FE> {
@@ -621,6 +743,26 @@ impl<FE: FiniteField> StateMultCheckVerifier<FE> {
         self.power_chi = self.chi;
         self.cnt = 0;
     }
+
+    /// Return the number of multiplication triples accumulated since the last check.
+    pub fn count(&self) -> usize {
+        self.cnt
+    }
+
+    /// Send a fresh challenge to the prover, used for the triples pushed after
+    /// the last check.
+    pub fn refresh_challenge<C: AbstractChannel, RNG: CryptoRng + Rng>(
+        &mut self,
+        channel: &mut C,
+        rng: &mut RNG,
+    ) -> Result<()> {
+        debug_assert_eq!(self.cnt, 0);
+        self.chi = FE::random(rng);
+        channel.write_serializable::<FE>(&self.chi)?;
+        channel.flush()?;
+        self.power_chi = self.chi;
+        Ok(())
+    }
 }
 
 impl<FE: FiniteField> FComVerifier<FE> {
@@ -636,6 +778,7 @@ impl<FE: FiniteField> FComVerifier<FE> {
             delta: recv.delta(),
             svole_receiver: recv,
             voles: Vec::new(),
+            vole_chunk: 1,
         })
     }
 
@@ -649,6 +792,7 @@ impl<FE: FiniteField> FComVerifier<FE> {
             delta: self.get_delta(),
             svole_receiver: self.svole_receiver.duplicate(channel, rng)?,
             voles: Vec::new(),
+            vole_chunk: self.vole_chunk,
         })
     }
 
@@ -658,6 +802,26 @@ impl<FE: FiniteField> FComVerifier<FE> {
         self.delta
     }
 
+    /// Set the number of sVOLE extensions performed each time the voles run out.
+    ///
+    /// The prover must use the same value, see [`FComProver::set_vole_chunk`].
+    pub fn set_vole_chunk(&mut self, n: NonZeroUsize) {
+        self.vole_chunk = n.get();
+    }
+
+    fn extend<C: AbstractChannel, RNG: CryptoRng + Rng>(
+        &mut self,
+        channel: &mut C,
+        rng: &mut RNG,
+    ) -> Result<()> {
+        let mut extension = Vec::new();
+        for _ in 0..self.vole_chunk {
+            self.svole_receiver.receive(channel, rng, &mut extension)?;
+            self.voles.append(&mut extension);
+        }
+        Ok(())
+    }
+
     /// Returns a random mac.
     pub fn random<C: AbstractChannel, RNG: CryptoRng + Rng>(
         &mut self,
@@ -668,7 +832,7 @@ impl<FE: FiniteField> FComVerifier<FE> {
             Some(e) => Ok(MacVerifier(e)),
             None => {
                 let _start = Instant::now();
-                self.svole_receiver.receive(channel, rng, &mut self.voles)?;
+                self.extend(channel, rng)?;
                 info!(
                     "SVOLE<time:{:?} field:{:?}>",
                     _start.elapsed(),
@@ -702,10 +866,25 @@ impl<FE: FiniteField> FComVerifier<FE> {
         num: usize,
         out: &mut Vec<MacVerifier<FE>>,
     ) -> Result<()> {
-        for _i in 0..num {
-            let r = self.random(channel, rng)?;
-            let y = channel.read_serializable::<FE::PrimeField>()?;
-            out.push(MacVerifier(r.0 - y * self.delta));
+        let size = <FE::PrimeField as CanonicalSerialize>::ByteReprLen::USIZE;
+        let mut remaining = num;
+        while remaining > 0 {
+            // The masked values are read in one go for all the inputs using the
+            // voles available without extension, as the prover sends them
+            // without interleaving extension messages.
+            let first = self.random(channel, rng)?;
+            let n = remaining.min(self.voles.len() + 1);
+            let bytes = channel.read_vec(n * size)?;
+            for (i, y) in bytes.chunks(size).enumerate() {
+                let r = if i == 0 {
+                    first
+                } else {
+                    self.random(channel, rng)?
+                };
+                let y = FE::PrimeField::from_bytes(GenericArray::from_slice(y))?;
+                out.push(MacVerifier(r.0 - y * self.delta));
+            }
+            remaining -= n;
         }
         Ok(())
     }
@@ -781,7 +960,7 @@ impl<FE: FiniteField> FComVerifier<FE> {
         if b {
             Ok(())
         } else {
-            Err(eyre!("check_zero failed"))
+            Err(DmcError::CheckZeroFailed.into())
         }
     }
 
@@ -819,7 +998,7 @@ impl<FE: FiniteField> FComVerifier<FE> {
             Ok(())
         } else {
             warn!("check_zero fails");
-            Err(eyre!("open fails"))
+            Err(DmcError::OpenFailed.into())
         }
     }
 
@@ -862,7 +1041,7 @@ impl<FE: FiniteField> FComVerifier<FE> {
             // - because of delta
             Ok(())
         } else {
-            Err(eyre!("checkMultiply fails"))
+            Err(DmcError::MultCheckFailed.into())
         }
     }
 
@@ -883,6 +1062,30 @@ impl<FE: FiniteField> FComVerifier<FE> {
         Ok(())
     }
 
+    /// Push the relation `z = sum_i x_i * y_i` for later check.
+    ///
+    /// See [`FComProver::quicksilver_push_dot_product`].
+    pub fn quicksilver_push_dot_product(
+        &mut self,
+        state: &mut StateMultCheckVerifier<FE>,
+        x: &[MacVerifier<FE>],
+        y: &[MacVerifier<FE>],
+        z: &MacVerifier<FE>,
+    ) -> Result<()> {
+        if x.len() != y.len() {
+            return Err(eyre!("Vectors of a dot product differ in length"));
+        }
+        let mut b = self.delta * z.mac();
+        for (MacVerifier(x_mac), MacVerifier(y_mac)) in x.iter().zip(y) {
+            b += *x_mac * *y_mac;
+        }
+
+        state.sum_b += b * state.power_chi;
+        state.power_chi *= state.chi;
+        state.cnt += 1;
+        Ok(())
+    }
+
     /// Finalize the check for the list of pushed multiplication triples.
     pub fn quicksilver_finalize<C: AbstractChannel, RNG: CryptoRng + Rng>(
         &mut self,
@@ -910,7 +1113,7 @@ impl<FE: FiniteField> FComVerifier<FE> {
             Ok(c)
         } else {
             state.reset();
-            Err(eyre!("checkMultiply fails"))
+            Err(DmcError::MultCheckFailed.into())
         }
     }
 
@@ -922,16 +1125,21 @@ impl<FE: FiniteField> FComVerifier<FE> {
 
 #[cfg(test)]
 mod tests {
-    use super::{FComProver, FComVerifier, MacProver};
+    use super::{
+        verify_mac, FComProver, FComVerifier, MacProver, MacVerifier, StateMultCheckProver,
+        StateMultCheckVerifier,
+    };
+    use crate::test_utils::run_channels;
     use ocelot::svole::wykw::{LPN_EXTEND_SMALL, LPN_SETUP_SMALL};
     use rand::SeedableRng;
     use scuttlebutt::{
-        field::{F40b, F61p, FiniteField},
+        field::{F40b, F61p, FiniteField, F2},
         ring::FiniteRing,
-        AbstractChannel, AesRng, Channel,
+        AbstractChannel, AesRng, Channel, TrackChannel,
     };
     use std::{
         io::{BufReader, BufWriter},
+        num::NonZeroUsize,
         os::unix::net::UnixStream,
     };
 
@@ -990,13 +1198,13 @@ mod tests {
 
             let mut v = Vec::new();
             for _ in 0..count {
-                let MacProver(x, x_mac) = fcom.random(&mut channel, &mut rng).unwrap();
+                let MacProver(x, x_mac, ..) = fcom.random(&mut channel, &mut rng).unwrap();
                 let cst = F61p::random(&mut rng);
                 channel.write_serializable::<F61p>(&cst).unwrap();
                 channel.flush().unwrap();
-                let m = fcom.affine_mult_cst(cst, MacProver(x, x_mac));
+                let m = fcom.affine_mult_cst(cst, MacProver::new(x, x_mac));
                 v.push(m);
-                let a = fcom.affine_add_cst(cst, MacProver(x, x_mac));
+                let a = fcom.affine_add_cst(cst, MacProver::new(x, x_mac));
                 v.push(a);
             }
             fcom.open(&mut channel, &v).unwrap();
@@ -1044,14 +1252,14 @@ mod tests {
 
             let mut v = Vec::new();
             for _ in 0..count {
-                let MacProver(x, x_mac) = fcom.random(&mut channel, &mut rng).unwrap();
-                let MacProver(y, y_mac) = fcom.random(&mut channel, &mut rng).unwrap();
+                let MacProver(x, x_mac, ..) = fcom.random(&mut channel, &mut rng).unwrap();
+                let MacProver(y, y_mac, ..) = fcom.random(&mut channel, &mut rng).unwrap();
                 let z = x * y;
                 let z_mac = fcom.input(&mut channel, &mut rng, &[z]).unwrap()[0];
                 v.push((
-                    MacProver(x, x_mac),
-                    MacProver(y, y_mac),
-                    MacProver(z, z_mac),
+                    MacProver::new(x, x_mac),
+                    MacProver::new(y, y_mac),
+                    MacProver::new(z, z_mac),
                 ));
             }
             channel.flush().unwrap();
@@ -1098,7 +1306,7 @@ mod tests {
                 for _ in 0..n {
                     let x = FE::PrimeField::ZERO;
                     let xmac = fcom.input1(&mut channel, &mut rng, x).unwrap();
-                    v.push(MacProver(x, xmac));
+                    v.push(MacProver::new(x, xmac));
                 }
                 channel.flush().unwrap();
                 let r = fcom.check_zero(&mut channel, v.as_slice());
@@ -1111,7 +1319,7 @@ mod tests {
                 for _ in 0..n {
                     let x = FE::PrimeField::random_nonzero(&mut rng);
                     let xmac = fcom.input1(&mut channel, &mut rng, x).unwrap();
-                    v.push(MacProver(x, xmac));
+                    v.push(MacProver::new(x, xmac));
                 }
                 channel.flush().unwrap();
                 let r = fcom.check_zero(&mut channel, v.as_slice());
@@ -1151,11 +1359,140 @@ mod tests {
         handle.join().unwrap();
     }
 
+    fn test_verify_mac<FE: FiniteField>() {
+        let mut rng = AesRng::from_seed(Default::default());
+        for _ in 0..100 {
+            let delta = FE::random(&mut rng);
+            let key = FE::random(&mut rng);
+            let x = FE::PrimeField::random(&mut rng);
+            let mac = key + x * delta;
+            assert!(verify_mac(x, mac, key, delta));
+            assert!(!verify_mac(x, mac + FE::ONE, key, delta));
+            assert!(!verify_mac(x + FE::PrimeField::ONE, mac, key, delta));
+        }
+    }
+
+    fn test_mac_parts<FE: FiniteField>() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let delta = FE::random(&mut rng);
+        let mut prover = Vec::new();
+        let mut verifier = Vec::new();
+        for _ in 0..10 {
+            let key = FE::random(&mut rng);
+            let x = FE::PrimeField::random(&mut rng);
+            let m = MacProver::<FE>::new(x, key + x * delta);
+            let k = MacVerifier::new(key);
+            assert_eq!(MacProver::new(m.value(), m.mac()), m);
+            assert_eq!(m.decompose(), (x, key + x * delta));
+            assert_eq!(MacVerifier::new(k.mac()), k);
+            prover.push(m);
+            verifier.push(k);
+        }
+
+        // Fold the MACs with a public random combination, outside of `FCom`.
+        let coeffs: Vec<_> = (0..10).map(|_| FE::PrimeField::random(&mut rng)).collect();
+        let (mut x, mut m) = (FE::PrimeField::ZERO, FE::ZERO);
+        let mut k = FE::ZERO;
+        for ((p, v), c) in prover.iter().zip(verifier.iter()).zip(coeffs.iter()) {
+            let (xi, mi) = p.decompose();
+            x += *c * xi;
+            m += *c * mi;
+            k += *c * v.mac();
+        }
+        let (x, m) = MacProver::<FE>::new(x, m).decompose();
+        assert!(verify_mac(x, m, MacVerifier::new(k).mac(), delta));
+    }
+
+    #[test]
+    fn test_mac_parts_f61p() {
+        test_mac_parts::<F61p>();
+    }
+
+    #[test]
+    fn test_mac_parts_gf40() {
+        test_mac_parts::<F40b>();
+    }
+
+    #[test]
+    fn test_verify_mac_f61p() {
+        test_verify_mac::<F61p>();
+    }
+
+    #[test]
+    fn test_verify_mac_gf40() {
+        test_verify_mac::<F40b>();
+    }
+
     #[test]
     fn test_fcom_random_f61p() {
         test_fcom_random::<F61p>();
     }
 
+    // Check a dot product of random vectors, whose claimed result is off by
+    // `offset`.
+    fn test_fcom_dot_product<FE: FiniteField>(offset: FE::PrimeField) -> bool {
+        let count = 50;
+        let (sender, receiver) = UnixStream::pair().unwrap();
+        let handle = std::thread::spawn(move || {
+            let mut rng = AesRng::from_seed(Default::default());
+            let reader = BufReader::new(sender.try_clone().unwrap());
+            let writer = BufWriter::new(sender);
+            let mut channel = Channel::new(reader, writer);
+            let mut fcom =
+                FComProver::<FE>::init(&mut channel, &mut rng, LPN_SETUP_SMALL, LPN_EXTEND_SMALL)
+                    .unwrap();
+
+            let mut x = Vec::new();
+            let mut y = Vec::new();
+            let mut z = offset;
+            for _ in 0..count {
+                x.push(fcom.random(&mut channel, &mut rng).unwrap());
+                y.push(fcom.random(&mut channel, &mut rng).unwrap());
+                z += x[x.len() - 1].value() * y[y.len() - 1].value();
+            }
+            let z_mac = fcom.input(&mut channel, &mut rng, &[z]).unwrap()[0];
+            let mut state = StateMultCheckProver::init(&mut channel).unwrap();
+            fcom.quicksilver_push_dot_product(&mut state, &x, &y, &MacProver::new(z, z_mac))
+                .unwrap();
+            fcom.quicksilver_finalize(&mut channel, &mut rng, &mut state)
+                .unwrap();
+        });
+        let mut rng = AesRng::from_seed(Default::default());
+        let reader = BufReader::new(receiver.try_clone().unwrap());
+        let writer = BufWriter::new(receiver);
+        let mut channel = Channel::new(reader, writer);
+        let mut fcom =
+            FComVerifier::<FE>::init(&mut channel, &mut rng, LPN_SETUP_SMALL, LPN_EXTEND_SMALL)
+                .unwrap();
+
+        let mut x = Vec::new();
+        let mut y = Vec::new();
+        for _ in 0..count {
+            x.push(fcom.random(&mut channel, &mut rng).unwrap());
+            y.push(fcom.random(&mut channel, &mut rng).unwrap());
+        }
+        let z = fcom.input(&mut channel, &mut rng, 1).unwrap()[0];
+        let mut state = StateMultCheckVerifier::init(&mut channel, &mut rng).unwrap();
+        fcom.quicksilver_push_dot_product(&mut state, &x, &y, &z)
+            .unwrap();
+        let r = fcom.quicksilver_finalize(&mut channel, &mut rng, &mut state);
+
+        handle.join().unwrap();
+        r.is_ok()
+    }
+
+    #[test]
+    fn test_fcom_dot_product_f61p() {
+        assert!(test_fcom_dot_product::<F61p>(F61p::ZERO));
+        assert!(!test_fcom_dot_product::<F61p>(F61p::ONE));
+    }
+
+    #[test]
+    fn test_fcom_dot_product_gf40() {
+        assert!(test_fcom_dot_product::<F40b>(F2::ZERO));
+        assert!(!test_fcom_dot_product::<F40b>(F2::ONE));
+    }
+
     #[test]
     fn test_fcom_affine_f61p() {
         test_fcom_affine();
@@ -1175,4 +1512,61 @@ mod tests {
     fn test_fcom_check_zero_f61p() {
         test_fcom_check_zero::<F61p>();
     }
+
+    // Draw `draws` random macs with the given vole chunk, and return the bits
+    // communicated by the prover after the initialization and the number of
+    // voles left in its buffer.
+    fn extension_rounds(chunk: usize, draws: usize) -> (usize, usize) {
+        let chunk = NonZeroUsize::new(chunk).unwrap();
+        let (prover, ()) = run_channels(
+            move |channel, mut rng| {
+                let mut channel = TrackChannel::new(channel.clone());
+                let mut fcom = FComProver::<F61p>::init(
+                    &mut channel,
+                    &mut rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                )
+                .unwrap();
+                fcom.set_vole_chunk(chunk);
+                channel.clear();
+                for _ in 0..draws {
+                    fcom.random(&mut channel, &mut rng).unwrap();
+                }
+                channel.flush().unwrap();
+                let bits = (channel.total_kilobits() * 1000.0).round() as usize;
+                (bits, fcom.voles.len())
+            },
+            |channel, mut rng| {
+                let mut fcom = FComVerifier::<F61p>::init(
+                    channel,
+                    &mut rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                )
+                .unwrap();
+                fcom.set_vole_chunk(chunk);
+                for _ in 0..draws {
+                    fcom.random(channel, &mut rng).unwrap();
+                }
+                channel.flush().unwrap();
+            },
+        );
+        prover
+    }
+
+    #[test]
+    fn test_vole_chunk_fixes_extension_rounds() {
+        let (one_round_bits, left) = extension_rounds(1, 1);
+        let per_round = left + 1;
+
+        let (bits, left) = extension_rounds(3, 1);
+        assert_eq!(bits, 3 * one_round_bits);
+        assert_eq!(left, 3 * per_round - 1);
+
+        // Exhausting the first chunk triggers exactly one more chunk.
+        let (bits, left) = extension_rounds(3, 3 * per_round + 1);
+        assert_eq!(bits, 6 * one_round_bits);
+        assert_eq!(left, 3 * per_round - 1);
+    }
 }
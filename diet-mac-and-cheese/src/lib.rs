@@ -15,18 +15,43 @@ solely on the `input_private()` function. Also the API satisfies the following i
 if any function call returns an error then any subsequent gate function call
 will directly return an error.
 */
+pub mod aes;
+pub mod auto_field;
 mod backend;
 pub mod backend_multifield;
 pub mod backend_trait;
+pub mod boolean;
+pub mod bristol;
 pub mod circuit_ir;
 pub mod edabits;
+mod error;
 mod fields;
+pub mod gadgets;
+#[cfg(feature = "debug-graph")]
+pub(crate) mod gate_graph;
+pub mod ghash;
 pub mod homcom;
 pub(crate) mod memory;
+pub mod merkle;
+pub mod outputs;
+pub mod plaintext;
 #[allow(clippy::all)]
 pub mod read_sieveir_phase2;
+pub mod replay;
+pub mod session;
 mod sieveir_phase2;
 pub mod text_reader;
-pub use backend::{from_bytes_le, DietMacAndCheeseProver, DietMacAndCheeseVerifier};
+pub mod windowed;
+#[cfg(feature = "insecure-bench")]
+pub use backend::AcceptAllVerifier;
+pub use backend::{
+    from_bytes_le, prove, verify, CircuitStats, CommStats, DietMacAndCheeseProver,
+    DietMacAndCheeseVerifier, DmcConfig, DropError, FlushEvent, FlushHook, FlushKind,
+    ProgressCallback, WitnessSource,
+};
 pub mod backend_zki;
+pub use error::{is_io_error, DmcError};
 pub(crate) mod plugins;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod zero_check;
@@ -0,0 +1,230 @@
+//! Proofs of membership in a Merkle tree over [`F128b`].
+//!
+//! The tree uses the compression function `h(l, r) = (l * K_L + r * K_R)^3`
+//! over [`F128b`], for two fixed public constants `K_L` and `K_R`. It is meant
+//! to exercise binary field arithmetic with backend gates and is not a vetted
+//! cryptographic hash function.
+//!
+//! As in [`crate::ghash`], the circuit works over boolean wires, with each
+//! element of [`F128b`] represented by its 128 bits (least significant first).
+//! Multiplying by a public constant and squaring are linear over `F2`, so each
+//! compression costs a single product of two private elements.
+
+use crate::{
+    backend_trait::BackendT,
+    ghash::{bits, f128b_from_u128, linear_circuit, mul_constant_circuit},
+};
+use eyre::{eyre, Result};
+use scuttlebutt::{
+    field::{F128b, F2},
+    ring::FiniteRing,
+};
+
+const K_L: u128 = 0x243f6a8885a308d313198a2e03707344;
+const K_R: u128 = 0xa4093822299f31d0082efa98ec4e6c89;
+
+/// Compress two children of the tree into their parent.
+pub fn compress(left: F128b, right: F128b) -> F128b {
+    let t = left * f128b_from_u128(K_L) + right * f128b_from_u128(K_R);
+    t * t * t
+}
+
+/// Compute the root of the tree from a `leaf` and its authentication `path`.
+///
+/// Each element of `path` is the sibling of the current node, starting from the
+/// leaf, along with whether the current node is the right child.
+pub fn merkle_root(leaf: F128b, path: &[(F128b, bool)]) -> F128b {
+    path.iter().fold(leaf, |node, (sibling, is_right)| {
+        if *is_right {
+            compress(*sibling, node)
+        } else {
+            compress(node, *sibling)
+        }
+    })
+}
+
+// Return the bits of `x^k` for `k` in `0..n`, with `x` the generator of the
+// polynomial basis of `F128b`.
+fn powers(n: usize) -> Vec<Vec<F2>> {
+    let x = f128b_from_u128(2);
+    let mut power = F128b::ONE;
+    let mut out = Vec::with_capacity(n);
+    for _ in 0..n {
+        out.push(bits(power));
+        power *= x;
+    }
+    out
+}
+
+fn add_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+    b: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    a.iter().zip(b).map(|(a, b)| backend.add(a, b)).collect()
+}
+
+fn square_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    // In characteristic two, the square of `sum a_i x^i` is `sum a_i x^(2i)`.
+    let columns: Vec<_> = powers(255).into_iter().step_by(2).collect();
+    linear_circuit(backend, &columns, a)
+}
+
+fn mul_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    a: &[B::Wire],
+    b: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    // Schoolbook product of the polynomials, followed by the reduction.
+    let mut coeffs = Vec::with_capacity(255);
+    for _ in 0..255 {
+        coeffs.push(backend.constant(F2::ZERO)?);
+    }
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            let ab = backend.mul(a, b)?;
+            coeffs[i + j] = backend.add(&coeffs[i + j], &ab)?;
+        }
+    }
+    linear_circuit(backend, &powers(255), &coeffs)
+}
+
+/// Compress the two children given by their 128 bit wires into their parent.
+pub fn compress_circuit<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    left: &[B::Wire],
+    right: &[B::Wire],
+) -> Result<Vec<B::Wire>> {
+    let l = mul_constant_circuit(backend, f128b_from_u128(K_L), left)?;
+    let r = mul_constant_circuit(backend, f128b_from_u128(K_R), right)?;
+    let t = add_circuit(backend, &l, &r)?;
+    let t2 = square_circuit(backend, &t)?;
+    mul_circuit(backend, &t2, &t)
+}
+
+/// Prove knowledge of a leaf and an authentication path of length `depth` for
+/// the public `root`.
+///
+/// The prover provides the leaf and the path, as in [`merkle_root`], and the
+/// verifier provides `None`. The leaf, the siblings and the directions remain
+/// private.
+pub fn assert_merkle_path<B: BackendT<FieldElement = F2>>(
+    backend: &mut B,
+    leaf: Option<F128b>,
+    path: Option<&[(F128b, bool)]>,
+    depth: usize,
+    root: F128b,
+) -> Result<()> {
+    if let Some(path) = path {
+        if path.len() != depth {
+            return Err(eyre!(
+                "The path has length {} instead of {}",
+                path.len(),
+                depth
+            ));
+        }
+    }
+    let leaf_bits = leaf.map(bits);
+    let mut node = Vec::with_capacity(128);
+    for i in 0..128 {
+        node.push(backend.input_private(leaf_bits.as_ref().map(|b| b[i]))?);
+    }
+    for level in 0..depth {
+        let step = path.map(|p| p[level]);
+        let sibling_bits = step.map(|(sibling, _)| bits(sibling));
+        let mut sibling = Vec::with_capacity(128);
+        for i in 0..128 {
+            sibling.push(backend.input_private(sibling_bits.as_ref().map(|b| b[i]))?);
+        }
+        let is_right = backend.input_private(step.map(|(_, r)| F2::from(r)))?;
+        // `is_right` is a bit as the wires are over `F2`.
+        // left = node + is_right * (sibling + node), right = node + sibling + left
+        let sum = add_circuit(backend, &node, &sibling)?;
+        let mut left = Vec::with_capacity(128);
+        for (n, s) in node.iter().zip(sum.iter()) {
+            let swap = backend.mul(&is_right, s)?;
+            left.push(backend.add(n, &swap)?);
+        }
+        let right = add_circuit(backend, &sum, &left)?;
+        node = compress_circuit(backend, &left, &right)?;
+    }
+    for (wire, bit) in node.iter().zip(bits(root)) {
+        let diff = backend.add_constant(wire, bit)?;
+        backend.assert_zero(&diff)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_merkle_path, compress, merkle_root};
+    use crate::test_utils::run;
+    use eyre::Result;
+    use rand::{Rng, SeedableRng};
+    use scuttlebutt::{
+        field::{F128b, F40b},
+        ring::FiniteRing,
+        AesRng,
+    };
+
+    const DEPTH: usize = 3;
+
+    fn random_path(rng: &mut AesRng) -> (F128b, Vec<(F128b, bool)>) {
+        let leaf = F128b::random(rng);
+        let path = (0..DEPTH)
+            .map(|_| (F128b::random(rng), rng.gen()))
+            .collect();
+        (leaf, path)
+    }
+
+    fn test_path(leaf: F128b, path: Vec<(F128b, bool)>, root: F128b) -> (Result<()>, Result<()>) {
+        run::<F40b, _, _>(
+            move |dmc| assert_merkle_path(dmc, Some(leaf), Some(&path), DEPTH, root),
+            move |dmc| assert_merkle_path(dmc, None, None, DEPTH, root),
+        )
+    }
+
+    #[test]
+    fn test_merkle_root_matches_definition() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let (leaf, s0, s1) = (
+            F128b::random(&mut rng),
+            F128b::random(&mut rng),
+            F128b::random(&mut rng),
+        );
+        assert_eq!(merkle_root(leaf, &[]), leaf);
+        assert_eq!(
+            merkle_root(leaf, &[(s0, false), (s1, true)]),
+            compress(s1, compress(leaf, s0))
+        );
+    }
+
+    #[test]
+    fn test_merkle_path() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let (leaf, path) = random_path(&mut rng);
+        let root = merkle_root(leaf, &path);
+        let (prover, verifier) = test_path(leaf, path, root);
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_merkle_tampered_path_fails() {
+        let mut rng = AesRng::from_seed(Default::default());
+        let (leaf, path) = random_path(&mut rng);
+        let root = merkle_root(leaf, &path);
+        let mut tampered = path.clone();
+        tampered[1].1 = !tampered[1].1;
+        let (prover, verifier) = test_path(leaf, tampered, root);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+
+        let (prover, verifier) = test_path(leaf + F128b::ONE, path, root);
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+}
@@ -0,0 +1,135 @@
+//! Tracking of the outputs of a proof.
+//!
+//! This is a testing aid: recording the outputs of two implementations of the
+//! same circuit allows checking that they agree for a given witness.
+
+use crate::gadgets::BackendGadgetsT;
+use eyre::{eyre, Result};
+use scuttlebutt::field::FiniteField;
+
+/// The outputs of a proof, in the order in which they were recorded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProofOutputs<F: FiniteField> {
+    values: Vec<F>,
+}
+
+impl<F: FiniteField> ProofOutputs<F> {
+    /// Create an empty set of outputs.
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    /// Record the value of `wire` as the next output.
+    ///
+    /// This fails when the value of `wire` is not known to `backend`, that is
+    /// for the verifier.
+    pub fn record<B: BackendGadgetsT<FieldElement = F>>(
+        &mut self,
+        backend: &B,
+        wire: &B::Wire,
+    ) -> Result<()> {
+        let value = backend
+            .wire_value(wire)
+            .ok_or_else(|| eyre!("Cannot record an output whose value is unknown"))?;
+        self.values.push(value);
+        Ok(())
+    }
+
+    /// Return the recorded outputs.
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+}
+
+/// Assert that two proofs produced the same outputs.
+///
+/// # Panics
+/// Panics if the number of outputs differ, or on the first output which differs.
+pub fn assert_outputs_match<F: FiniteField>(a: &ProofOutputs<F>, b: &ProofOutputs<F>) {
+    assert_eq!(
+        a.values.len(),
+        b.values.len(),
+        "different number of outputs"
+    );
+    for (i, (x, y)) in a.values.iter().zip(b.values.iter()).enumerate() {
+        assert_eq!(x, y, "outputs differ at index {}", i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_outputs_match, ProofOutputs};
+    use crate::{
+        backend::{DietMacAndCheeseProver, DietMacAndCheeseVerifier},
+        gadgets::BackendGadgetsT,
+        homcom::{MacProver, MacVerifier},
+        test_utils::{f61p, run, TestChannel},
+    };
+    use eyre::Result;
+    use scuttlebutt::{field::F61p, ring::FiniteRing, AesRng};
+
+    type Prover = DietMacAndCheeseProver<F61p, TestChannel, AesRng>;
+    type Verifier = DietMacAndCheeseVerifier<F61p, TestChannel, AesRng>;
+
+    // Compute `x * x + x`.
+    fn square_plus<B: BackendGadgetsT>(backend: &mut B, x: &B::Wire) -> Result<B::Wire> {
+        let square = backend.mul(x, x)?;
+        backend.add(&square, x)
+    }
+
+    // Compute `x * (x + 1)`, which equals `x * x + x`.
+    fn times_successor<B: BackendGadgetsT>(backend: &mut B, x: &B::Wire) -> Result<B::Wire> {
+        let successor = backend.add_constant(x, B::FieldElement::ONE)?;
+        backend.mul(x, &successor)
+    }
+
+    // Compute `x * x + 1`, a buggy version of `x * x + x`.
+    fn buggy<B: BackendGadgetsT>(backend: &mut B, x: &B::Wire) -> Result<B::Wire> {
+        let square = backend.mul(x, x)?;
+        backend.add_constant(&square, B::FieldElement::ONE)
+    }
+
+    // Run `circuit` on several inputs and return the outputs of the prover. The
+    // same circuit is passed twice, instantiated for each party.
+    fn outputs(
+        circuit: fn(&mut Prover, &MacProver<F61p>) -> Result<MacProver<F61p>>,
+        verifier_circuit: fn(&mut Verifier, &MacVerifier<F61p>) -> Result<MacVerifier<F61p>>,
+    ) -> ProofOutputs<F61p> {
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| {
+                let mut outputs = ProofOutputs::new();
+                for x in [0, 1, 5, 1 << 20] {
+                    let x = dmc.input_private(f61p(x))?;
+                    let y = circuit(dmc, &x)?;
+                    outputs.record(dmc, &y)?;
+                }
+                Ok(outputs)
+            },
+            move |dmc| {
+                for _ in 0..4 {
+                    let x = dmc.input_private()?;
+                    verifier_circuit(dmc, &x)?;
+                }
+                Ok(())
+            },
+        );
+        verifier.unwrap();
+        prover.unwrap()
+    }
+
+    #[test]
+    fn test_equivalent_circuits_match() {
+        let a = outputs(square_plus, square_plus);
+        let b = outputs(times_successor, times_successor);
+        assert_eq!(a.values().len(), 4);
+        assert_outputs_match(&a, &b);
+    }
+
+    #[test]
+    #[should_panic(expected = "outputs differ at index 0")]
+    fn test_buggy_circuit_differs() {
+        let a = outputs(square_plus, square_plus);
+        let b = outputs(buggy, buggy);
+        assert_outputs_match(&a, &b);
+    }
+}
@@ -0,0 +1,255 @@
+//! A backend evaluating circuits in the clear, without authentication.
+//!
+//! This is meant for developing circuits: the [`PlaintextBackend`] runs the
+//! same circuit code as the prover of Diet Mac'n'Cheese, checking assertions
+//! as they are made, without the cost of sVOLE and MACs. It provides no
+//! zero-knowledge nor soundness, and is no substitute for the real backends
+//! once the circuit is written.
+
+use crate::{
+    backend::from_bytes_le, backend_trait::BackendT, error::DmcError, gadgets::BackendGadgetsT,
+};
+use eyre::{eyre, Result};
+use scuttlebutt::{field::FiniteField, AesRng};
+
+/// The number of gates of each kind evaluated by a [`PlaintextBackend`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlaintextStats {
+    /// The number of public inputs.
+    pub public_inputs: usize,
+    /// The number of private inputs.
+    pub private_inputs: usize,
+    /// The number of additions.
+    pub add: usize,
+    /// The number of multiplications.
+    pub mul: usize,
+    /// The number of additions by a constant.
+    pub add_constant: usize,
+    /// The number of multiplications by a constant.
+    pub mul_constant: usize,
+    /// The number of assertions that a wire is zero.
+    pub assert_zero: usize,
+}
+
+/// A [`BackendT`] evaluating the gates over clear values of `F`.
+///
+/// `assert_zero` fails as soon as its wire is nonzero, and private inputs must
+/// be provided, as for the prover.
+pub struct PlaintextBackend<F: FiniteField> {
+    rng: AesRng,
+    stats: PlaintextStats,
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F: FiniteField> PlaintextBackend<F> {
+    /// Create a new plaintext backend.
+    pub fn new() -> Self {
+        Self {
+            rng: AesRng::new(),
+            stats: PlaintextStats::default(),
+            _field: std::marker::PhantomData,
+        }
+    }
+
+    /// Return the number of gates evaluated so far.
+    pub fn stats(&self) -> PlaintextStats {
+        self.stats
+    }
+}
+
+impl<F: FiniteField> Default for PlaintextBackend<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FiniteField> BackendT for PlaintextBackend<F> {
+    type Wire = F;
+    type FieldElement = F;
+
+    fn from_bytes_le(val: &[u8]) -> Result<Self::FieldElement> {
+        from_bytes_le(val)
+    }
+
+    fn one(&self) -> Result<Self::FieldElement> {
+        Ok(F::ONE)
+    }
+
+    fn zero(&self) -> Result<Self::FieldElement> {
+        Ok(F::ZERO)
+    }
+
+    fn copy(&mut self, wire: &Self::Wire) -> Result<Self::Wire> {
+        Ok(*wire)
+    }
+
+    fn challenge(&mut self) -> Result<Self::Wire> {
+        Ok(F::random(&mut self.rng))
+    }
+
+    fn constant(&mut self, val: Self::FieldElement) -> Result<Self::Wire> {
+        Ok(val)
+    }
+
+    fn assert_zero(&mut self, wire: &Self::Wire) -> Result<()> {
+        self.stats.assert_zero += 1;
+        if *wire != F::ZERO {
+            return Err(eyre!(
+                "assert_zero number {} fails on a nonzero value",
+                self.stats.assert_zero
+            ));
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.stats.add += 1;
+        Ok(*a + *b)
+    }
+
+    fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.stats.mul += 1;
+        Ok(*a * *b)
+    }
+
+    fn div(&mut self, a: &Self::Wire, b: &Self::Wire) -> Result<Self::Wire> {
+        self.stats.mul += 1;
+        if *b == F::ZERO {
+            return Err(DmcError::DivisionByZero.into());
+        }
+        Ok(*a * b.inverse())
+    }
+
+    fn add_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
+        self.stats.add_constant += 1;
+        Ok(*a + b)
+    }
+
+    fn mul_constant(&mut self, a: &Self::Wire, b: Self::FieldElement) -> Result<Self::Wire> {
+        self.stats.mul_constant += 1;
+        Ok(*a * b)
+    }
+
+    fn input_public(&mut self, val: Self::FieldElement) -> Result<Self::Wire> {
+        self.stats.public_inputs += 1;
+        Ok(val)
+    }
+
+    fn input_private(&mut self, val: Option<Self::FieldElement>) -> Result<Self::Wire> {
+        self.stats.private_inputs += 1;
+        val.ok_or_else(|| eyre!("The plaintext backend requires the value of private inputs"))
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn reset(&mut self) {}
+}
+
+impl<F: FiniteField> BackendGadgetsT for PlaintextBackend<F> {
+    fn wire_value(&self, wire: &F) -> Option<F> {
+        Some(*wire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlaintextBackend, PlaintextStats};
+    use crate::{
+        backend_trait::BackendT,
+        gadgets::BackendGadgetsT,
+        test_utils::{f61p, run},
+    };
+    use eyre::Result;
+    use scuttlebutt::field::F61p;
+
+    // Prove that the private `x` and `y` satisfy `x * y == 42` and `x < y`.
+    fn circuit<B: BackendGadgetsT<FieldElement = F61p>>(
+        backend: &mut B,
+        witness: Option<(u64, u64)>,
+    ) -> Result<()> {
+        let x = backend.input_private(witness.map(|(x, _)| f61p(x)))?;
+        let y = backend.input_private(witness.map(|(_, y)| f61p(y)))?;
+        let xy = backend.mul(&x, &y)?;
+        let diff = backend.add_constant(&xy, -f61p(42))?;
+        backend.assert_zero(&diff)?;
+        let lt = backend.less_than(&x, &y, 8)?;
+        let not_lt = backend.add_constant(&lt, -f61p(1))?;
+        backend.assert_zero(&not_lt)
+    }
+
+    #[test]
+    fn test_plaintext_matches_real_backend() {
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        circuit(&mut plaintext, Some((6, 7))).unwrap();
+        plaintext.finalize().unwrap();
+
+        let (prover, verifier) =
+            run::<F61p, _, _>(|dmc| circuit(dmc, Some((6, 7))), |dmc| circuit(dmc, None));
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_plaintext_fails_on_nonzero() {
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        assert!(circuit(&mut plaintext, Some((7, 6))).is_err());
+
+        // The failure is detected as soon as the product is asserted.
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        assert!(circuit(&mut plaintext, Some((6, 6))).is_err());
+        assert_eq!(
+            plaintext.stats(),
+            PlaintextStats {
+                private_inputs: 2,
+                mul: 1,
+                add_constant: 1,
+                assert_zero: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    // Prove that the private vectors `a` and `b` have an inner product of 32.
+    fn dot_product_circuit<B: BackendGadgetsT<FieldElement = F61p>>(
+        backend: &mut B,
+        witness: Option<([u64; 3], [u64; 3])>,
+    ) -> Result<B::Wire> {
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        for i in 0..3 {
+            a.push(backend.input_private(witness.map(|(a, _)| f61p(a[i])))?);
+            b.push(backend.input_private(witness.map(|(_, b)| f61p(b[i])))?);
+        }
+        let dot = backend.dot_product(&a, &b)?;
+        let zero = backend.add_constant(&dot, -f61p(32))?;
+        backend.assert_zero(&zero)?;
+        Ok(dot)
+    }
+
+    #[test]
+    fn test_dot_product_on_both_backends() {
+        let witness = Some(([1, 2, 3], [4, 5, 6]));
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        let dot = dot_product_circuit(&mut plaintext, witness).unwrap();
+        assert_eq!(dot, f61p(32));
+        assert!(plaintext.dot_product(&[dot], &[]).is_err());
+
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| dot_product_circuit(dmc, witness).map(|dot| dot.value()),
+            |dmc| dot_product_circuit(dmc, None).map(|_| ()),
+        );
+        assert_eq!(prover.unwrap(), dot);
+        verifier.unwrap();
+
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        assert!(dot_product_circuit(&mut plaintext, Some(([1, 2, 3], [4, 5, 7]))).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_requires_private_values() {
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        assert!(plaintext.input_private(None).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+//! Offline re-verification of a recorded interactive proof.
+//!
+//! A verifier running over a [`RecordingChannel`] records every message it
+//! receives from the prover. As the verifier is deterministic given its random
+//! generator, running a fresh verifier seeded identically against the recording
+//! replays the proof exactly: [`replay_verify`] lets an auditor re-verify a proof
+//! without the prover.
+//!
+//! When the verifier does not use a seeded generator, e.g., in production, it
+//! may instead draw its coins from a [`RecordingRng`], and the proof is replayed
+//! with [`replay_verify_coins`], which feeds the recorded coins back with a
+//! [`ReplayRng`]. Recording is opt-in: a verifier only pays for it when using a
+//! `RecordingRng`.
+//!
+//! There is no non-interactive mode deriving the challenges by hashing the
+//! transcript. The proofs are designated-verifier: the MACs are checked against
+//! the global key `delta` and the VOLE correlations of the verifier, which are
+//! produced by an interactive protocol and must stay secret. A transcript is thus
+//! only verifiable by the verifier that took part in the proof, which is what
+//! replaying with its seed achieves. Moreover, the challenge of the multiplication
+//! check is sent before the triples are pushed, so that they are accumulated in a
+//! streaming fashion, and hashing them instead would require buffering every
+//! triple until the check.
+//!
+//! [`RecordingChannel`]: scuttlebutt::RecordingChannel
+
+use crate::backend::DietMacAndCheeseVerifier;
+use eyre::{eyre, Result};
+use ocelot::svole::wykw::LpnParams;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use scuttlebutt::{field::FiniteField, AesRng, Block, Channel};
+use std::io::Sink;
+
+/// The channel a verifier is replayed over, reading the recording and
+/// discarding the messages of the verifier.
+pub type ReplayChannel<'a> = Channel<&'a [u8], Sink>;
+
+/// A random generator recording the coins drawn from another generator.
+///
+/// The verifier keeps its generator in its public `rng` field, from which the
+/// coins are read after the proof.
+pub struct RecordingRng<R> {
+    rng: R,
+    coins: Vec<u8>,
+}
+
+impl<R: RngCore> RecordingRng<R> {
+    /// Make a new `RecordingRng` recording the coins drawn from `rng`.
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng,
+            coins: Vec::new(),
+        }
+    }
+
+    /// Return the coins drawn so far.
+    pub fn coins(&self) -> &[u8] {
+        &self.coins
+    }
+}
+
+impl<R: RngCore> RngCore for RecordingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        let x = self.rng.next_u32();
+        self.coins.extend_from_slice(&x.to_le_bytes());
+        x
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let x = self.rng.next_u64();
+        self.coins.extend_from_slice(&x.to_le_bytes());
+        x
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        self.coins.extend_from_slice(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)?;
+        self.coins.extend_from_slice(dest);
+        Ok(())
+    }
+}
+
+impl<R: CryptoRng> CryptoRng for RecordingRng<R> {}
+
+/// A random generator replaying the coins recorded by a [`RecordingRng`].
+///
+/// Once the coins are exhausted, it produces zeros and records that it was
+/// overdrawn, which [`replay_verify_coins`] reports as an error. It must only be
+/// used to replay a proof, never to verify a live one.
+pub struct ReplayRng<'a> {
+    coins: &'a [u8],
+    overdrawn: bool,
+}
+
+impl<'a> ReplayRng<'a> {
+    /// Make a new `ReplayRng` replaying `coins`.
+    pub fn new(coins: &'a [u8]) -> Self {
+        Self {
+            coins,
+            overdrawn: false,
+        }
+    }
+
+    /// Return the number of coins left to replay.
+    pub fn remaining(&self) -> usize {
+        self.coins.len()
+    }
+
+    /// Return whether more coins were drawn than were recorded.
+    pub fn is_overdrawn(&self) -> bool {
+        self.overdrawn
+    }
+}
+
+impl RngCore for ReplayRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let n = dest.len().min(self.coins.len());
+        let (head, tail) = self.coins.split_at(n);
+        dest[..n].copy_from_slice(head);
+        self.coins = tail;
+        if n < dest.len() {
+            dest[n..].fill(0);
+            self.overdrawn = true;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ReplayRng<'_> {}
+
+/// Replay the verification of `circuit` against the messages of the prover
+/// in `recording`.
+///
+/// The verifier of the recorded proof must have used an [`AesRng`] seeded with
+/// `seed`, along with the same LPN parameters and batching setting. This fails
+/// if the proof fails, or if the recording holds more messages than the verifier
+/// reads, e.g., when it does not match `circuit`.
+pub fn replay_verify<FE: FiniteField, T>(
+    recording: &[u8],
+    seed: Block,
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    no_batching: bool,
+    circuit: impl FnOnce(&mut DietMacAndCheeseVerifier<FE, ReplayChannel, AesRng>) -> Result<T>,
+) -> Result<T> {
+    let rng = AesRng::from_seed(seed);
+    replay(
+        recording,
+        rng,
+        lpn_setup,
+        lpn_extend,
+        no_batching,
+        circuit,
+        |_| Ok(()),
+    )
+}
+
+/// Replay the verification of `circuit` against the messages of the prover
+/// in `recording`, and the coins of the verifier in `coins`.
+///
+/// This is [`replay_verify`] for a verifier which drew its coins from a
+/// [`RecordingRng`]. It also fails if the verifier does not draw exactly the
+/// recorded coins.
+pub fn replay_verify_coins<FE: FiniteField, T>(
+    recording: &[u8],
+    coins: &[u8],
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    no_batching: bool,
+    circuit: impl FnOnce(&mut DietMacAndCheeseVerifier<FE, ReplayChannel, ReplayRng>) -> Result<T>,
+) -> Result<T> {
+    let rng = ReplayRng::new(coins);
+    replay(
+        recording,
+        rng,
+        lpn_setup,
+        lpn_extend,
+        no_batching,
+        circuit,
+        |rng| {
+            if rng.is_overdrawn() {
+                return Err(eyre!("The verifier drew more coins than were recorded"));
+            }
+            if rng.remaining() != 0 {
+                return Err(eyre!("{} recorded coins were not drawn", rng.remaining()));
+            }
+            Ok(())
+        },
+    )
+}
+
+// Replay the verification of `circuit` with `rng`, and apply `check_rng` to
+// the generator once the verification succeeds.
+fn replay<FE: FiniteField, RNG: CryptoRng + RngCore, T>(
+    recording: &[u8],
+    rng: RNG,
+    lpn_setup: LpnParams,
+    lpn_extend: LpnParams,
+    no_batching: bool,
+    circuit: impl FnOnce(&mut DietMacAndCheeseVerifier<FE, ReplayChannel, RNG>) -> Result<T>,
+    check_rng: impl FnOnce(&RNG) -> Result<()>,
+) -> Result<T> {
+    let mut channel = Channel::new(recording, std::io::sink());
+    let mut dmc =
+        DietMacAndCheeseVerifier::init(&mut channel, rng, lpn_setup, lpn_extend, no_batching)?;
+    let out = circuit(&mut dmc)
+        .and_then(|t| dmc.finalize().map(|_| t))
+        .and_then(|t| check_rng(&dmc.rng).map(|_| t));
+    if out.is_err() {
+        dmc.reset();
+    }
+    drop(dmc);
+    let out = out?;
+    let unread = channel.reader().borrow().len();
+    if unread != 0 {
+        return Err(eyre!("{} bytes of the recording were not read", unread));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay_verify, replay_verify_coins, RecordingRng, ReplayChannel, ReplayRng};
+    use crate::{
+        backend::{DietMacAndCheeseProver, DietMacAndCheeseVerifier},
+        test_utils::{f61p, run_channels},
+    };
+    use eyre::Result;
+    use ocelot::svole::wykw::{LPN_EXTEND_SMALL, LPN_SETUP_SMALL};
+    use rand::{CryptoRng, RngCore, SeedableRng};
+    use scuttlebutt::{field::F61p, AbstractChannel, AesRng, Block, RecordingChannel};
+
+    // Prove `x * x == 49`.
+    fn circuit<C: AbstractChannel, RNG: CryptoRng + RngCore>(
+        dmc: &mut DietMacAndCheeseVerifier<F61p, C, RNG>,
+    ) -> Result<()> {
+        let x = dmc.input_private()?;
+        let xx = dmc.mul(&x, &x)?;
+        let zero = dmc.addc(&xx, -f61p(49))?;
+        dmc.assert_zero(&zero)
+    }
+
+    fn replay(recording: &[u8], seed: Block) -> Result<()> {
+        replay_verify::<F61p, _>(
+            recording,
+            seed,
+            LPN_SETUP_SMALL,
+            LPN_EXTEND_SMALL,
+            false,
+            |dmc: &mut DietMacAndCheeseVerifier<F61p, ReplayChannel, AesRng>| circuit(dmc),
+        )
+    }
+
+    // Run the proof and return the messages received by the verifier.
+    fn record(seed: Block) -> Vec<u8> {
+        record_with_rng(AesRng::from_seed(seed), |_| ()).0
+    }
+
+    // Run the proof with the verifier drawing its coins from `rng`, and return
+    // the messages received by the verifier along with `f` applied to `rng`.
+    fn record_with_rng<RNG: CryptoRng + RngCore, T>(
+        rng: RNG,
+        f: impl FnOnce(&RNG) -> T,
+    ) -> (Vec<u8>, T) {
+        let ((), recorded) = run_channels(
+            |channel, rng| {
+                let mut dmc = DietMacAndCheeseProver::<F61p, _, _>::init(
+                    channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                let x = dmc.input_private(f61p(7)).unwrap();
+                let xx = dmc.mul(&x, &x).unwrap();
+                let zero = dmc.addc(&xx, -f61p(49)).unwrap();
+                dmc.assert_zero(&zero).unwrap();
+                dmc.finalize().unwrap();
+            },
+            |channel, _| {
+                let mut channel = RecordingChannel::new(channel.clone(), Vec::new());
+                let mut dmc = DietMacAndCheeseVerifier::<F61p, _, _>::init(
+                    &mut channel,
+                    rng,
+                    LPN_SETUP_SMALL,
+                    LPN_EXTEND_SMALL,
+                    false,
+                )
+                .unwrap();
+                circuit(&mut dmc).unwrap();
+                dmc.finalize().unwrap();
+                let t = f(&dmc.rng);
+                drop(dmc);
+                (channel.with_sink(|s| s.clone()).unwrap(), t)
+            },
+        );
+        recorded
+    }
+
+    #[test]
+    fn test_replay_verify() {
+        let seed = Block::from(42u128);
+        let recording = record(seed);
+        replay(&recording, seed).unwrap();
+    }
+
+    #[test]
+    fn test_replay_tampered_recording_fails() {
+        let seed = Block::from(42u128);
+        let recording = record(seed);
+
+        let mut tampered = recording.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(replay(&tampered, seed).is_err());
+
+        let mut extended = recording.clone();
+        extended.push(0);
+        assert!(replay(&extended, seed).is_err());
+
+        assert!(replay(&recording, Block::from(43u128)).is_err());
+    }
+
+    fn replay_coins(recording: &[u8], coins: &[u8]) -> Result<()> {
+        replay_verify_coins::<F61p, _>(
+            recording,
+            coins,
+            LPN_SETUP_SMALL,
+            LPN_EXTEND_SMALL,
+            false,
+            |dmc: &mut DietMacAndCheeseVerifier<F61p, ReplayChannel, ReplayRng>| circuit(dmc),
+        )
+    }
+
+    #[test]
+    fn test_replay_verify_coins() {
+        let rng = RecordingRng::new(AesRng::new());
+        let (recording, coins) = record_with_rng(rng, |rng| rng.coins().to_vec());
+        assert!(!coins.is_empty());
+        replay_coins(&recording, &coins).unwrap();
+
+        let mut tampered = coins.clone();
+        tampered[0] ^= 1;
+        assert!(replay_coins(&recording, &tampered).is_err());
+        assert!(replay_coins(&recording, &coins[..coins.len() - 1]).is_err());
+        let mut extended = coins.clone();
+        extended.push(0);
+        assert!(replay_coins(&recording, &extended).is_err());
+    }
+}
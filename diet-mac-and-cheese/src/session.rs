@@ -0,0 +1,142 @@
+//! Proving many instances of the same circuit with shared private inputs.
+//!
+//! A typical use is zero-knowledge inference, where a model with private
+//! weights is evaluated over a batch of public inputs. The private inputs of
+//! the setup are authenticated once, and each instance only re-evaluates the
+//! part of the circuit depending on its public inputs.
+
+use crate::backend_trait::BackendT;
+use eyre::Result;
+
+/// A session proving several instances of a circuit sharing setup wires.
+///
+/// Despite its name, the session is used by both parties, each with its own
+/// backend: the prover provides the values of the setup inputs, and the
+/// verifier provides `None` for each of them.
+pub struct ProverSession<'a, B: BackendT> {
+    backend: &'a mut B,
+    setup: Vec<B::Wire>,
+    instances: usize,
+}
+
+impl<'a, B: BackendT> ProverSession<'a, B> {
+    /// Start a new session on `backend`.
+    pub fn new(backend: &'a mut B) -> Self {
+        Self {
+            backend,
+            setup: Vec::new(),
+            instances: 0,
+        }
+    }
+
+    /// Input a private value shared by all the instances, and return its index
+    /// in the setup wires.
+    pub fn input_setup(&mut self, value: Option<B::FieldElement>) -> Result<usize> {
+        let wire = self.backend.input_private(value)?;
+        self.setup.push(wire);
+        Ok(self.setup.len() - 1)
+    }
+
+    /// Return the setup wires, in the order in which they were input.
+    pub fn setup_wires(&self) -> &[B::Wire] {
+        &self.setup
+    }
+
+    /// Evaluate `circuit` on an instance given by its `public` inputs.
+    ///
+    /// `circuit` is given the backend, the setup wires and the public inputs of
+    /// the instance.
+    pub fn prove_instance<T>(
+        &mut self,
+        public: &[B::FieldElement],
+        circuit: impl FnOnce(&mut B, &[B::Wire], &[B::FieldElement]) -> Result<T>,
+    ) -> Result<T> {
+        let out = circuit(self.backend, &self.setup, public)?;
+        self.instances += 1;
+        Ok(out)
+    }
+
+    /// Return the number of instances evaluated so far.
+    pub fn instances(&self) -> usize {
+        self.instances
+    }
+
+    /// Finalize the proof of all the instances.
+    pub fn finalize(self) -> Result<()> {
+        self.backend.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProverSession;
+    use crate::{
+        backend_trait::BackendT,
+        test_utils::{f61p, run},
+    };
+    use eyre::Result;
+    use scuttlebutt::field::F61p;
+
+    const WEIGHTS: [u64; 3] = [3, 1, 4];
+    const BIAS: u64 = 15;
+    const INSTANCES: [[u64; 3]; 4] = [[0, 0, 0], [1, 2, 3], [10, 0, 7], [5, 9, 2]];
+
+    // Prove `w . x + b == y` for each public `x`, with private `w` and `b`.
+    fn linear_model<B: BackendT<FieldElement = F61p>>(
+        backend: &mut B,
+        weights: Option<([u64; 3], u64)>,
+        outputs: &[u64],
+    ) -> Result<usize> {
+        let mut session = ProverSession::new(backend);
+        for i in 0..WEIGHTS.len() {
+            session.input_setup(weights.map(|(w, _)| f61p(w[i])))?;
+        }
+        session.input_setup(weights.map(|(_, b)| f61p(b)))?;
+
+        for (x, y) in INSTANCES.iter().zip(outputs) {
+            let public: Vec<_> = x.iter().map(|x| f61p(*x)).collect();
+            session.prove_instance(&public, |backend, setup, public| {
+                let mut acc = backend.copy(&setup[WEIGHTS.len()])?;
+                for (w, x) in setup.iter().zip(public) {
+                    let wx = backend.mul_constant(w, *x)?;
+                    acc = backend.add(&acc, &wx)?;
+                }
+                let diff = backend.add_constant(&acc, -f61p(*y))?;
+                backend.assert_zero(&diff)
+            })?;
+        }
+        Ok(session.instances())
+    }
+
+    fn expected_outputs() -> Vec<u64> {
+        INSTANCES
+            .iter()
+            .map(|x| x.iter().zip(WEIGHTS).map(|(x, w)| x * w).sum::<u64>() + BIAS)
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_model() {
+        let outputs = expected_outputs();
+        let verifier_outputs = outputs.clone();
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| linear_model(dmc, Some((WEIGHTS, BIAS)), &outputs),
+            move |dmc| linear_model(dmc, None, &verifier_outputs),
+        );
+        assert_eq!(prover.unwrap(), INSTANCES.len());
+        assert_eq!(verifier.unwrap(), INSTANCES.len());
+    }
+
+    #[test]
+    fn test_linear_model_wrong_output_fails() {
+        let mut outputs = expected_outputs();
+        outputs[2] += 1;
+        let verifier_outputs = outputs.clone();
+        let (prover, verifier) = run::<F61p, _, _>(
+            move |dmc| linear_model(dmc, Some((WEIGHTS, BIAS)), &outputs),
+            move |dmc| linear_model(dmc, None, &verifier_outputs),
+        );
+        assert!(prover.is_err());
+        assert!(verifier.is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! Utilities shared by the tests of the crate.
+
+use crate::backend::{DietMacAndCheeseProver, DietMacAndCheeseVerifier};
+use eyre::Result;
+use ocelot::svole::wykw::{LPN_EXTEND_SMALL, LPN_SETUP_SMALL};
+use rand::SeedableRng;
+use scuttlebutt::{
+    field::{F61p, FiniteField},
+    lockstep_channel_pair, AesRng, Channel, LockstepChannel,
+};
+use std::{
+    io::{BufReader, BufWriter},
+    os::unix::net::UnixStream,
+};
+
+pub(crate) type TestChannel = Channel<BufReader<UnixStream>, BufWriter<UnixStream>>;
+
+/// Run `prover` and `verifier` on the two ends of a [`TestChannel`], each with
+/// a default-seeded [`AesRng`], and return their outcomes.
+///
+/// The verifier closes its channel before waiting on the prover, so that a
+/// prover blocked on the verifier fails instead.
+pub(crate) fn run_channels<T: Send + 'static, U>(
+    prover: impl FnOnce(&mut TestChannel, AesRng) -> T + Send + 'static,
+    verifier: impl FnOnce(&mut TestChannel, AesRng) -> U,
+) -> (T, U) {
+    let (sender, receiver) = UnixStream::pair().unwrap();
+    let handle = std::thread::spawn(move || {
+        let reader = BufReader::new(sender.try_clone().unwrap());
+        let writer = BufWriter::new(sender);
+        let mut channel = Channel::new(reader, writer);
+        prover(&mut channel, AesRng::from_seed(Default::default()))
+    });
+
+    let reader = BufReader::new(receiver.try_clone().unwrap());
+    let writer = BufWriter::new(receiver);
+    let mut channel = Channel::new(reader, writer);
+    let verifier_result = verifier(&mut channel, AesRng::from_seed(Default::default()));
+    drop(channel);
+
+    (handle.join().unwrap(), verifier_result)
+}
+
+/// Run `prover` and `verifier` against each other and return the outcome of
+/// the circuit followed by `finalize` for each party.
+///
+/// A party which fails is reset, so that it can be dropped with pending
+/// multiplication checks.
+pub(crate) fn run<FE: FiniteField, T: Send + 'static, U>(
+    prover: impl FnOnce(&mut DietMacAndCheeseProver<FE, TestChannel, AesRng>) -> Result<T>
+        + Send
+        + 'static,
+    verifier: impl FnOnce(&mut DietMacAndCheeseVerifier<FE, TestChannel, AesRng>) -> Result<U>,
+) -> (Result<T>, Result<U>) {
+    run_channels(
+        move |channel, rng| {
+            let mut dmc = DietMacAndCheeseProver::init(
+                channel,
+                rng,
+                LPN_SETUP_SMALL,
+                LPN_EXTEND_SMALL,
+                false,
+            )
+            .unwrap();
+            let result = prover(&mut dmc).and_then(|t| dmc.finalize().map(|_| t));
+            if result.is_err() {
+                dmc.reset();
+            }
+            result
+        },
+        |channel, rng| {
+            let mut dmc = DietMacAndCheeseVerifier::init(
+                channel,
+                rng,
+                LPN_SETUP_SMALL,
+                LPN_EXTEND_SMALL,
+                false,
+            )
+            .unwrap();
+            let result = verifier(&mut dmc).and_then(|u| dmc.finalize().map(|_| u));
+            if result.is_err() {
+                dmc.reset();
+            }
+            result
+        },
+    )
+}
+
+/// Run `prover` and `verifier` against each other over a [`LockstepChannel`],
+/// with the randomness of each party seeded from `seed`.
+///
+/// Unlike [`run`], the interleaving of the parties does not depend on the
+/// scheduling of their threads, so that a run is deterministic given `seed`.
+pub(crate) fn run_lockstep<FE: FiniteField, T: Send + 'static, U>(
+    seed: u128,
+    prover: impl FnOnce(&mut DietMacAndCheeseProver<FE, LockstepChannel, AesRng>) -> Result<T>
+        + Send
+        + 'static,
+    verifier: impl FnOnce(&mut DietMacAndCheeseVerifier<FE, LockstepChannel, AesRng>) -> Result<U>,
+) -> (Result<T>, Result<U>) {
+    let (mut prover_channel, mut verifier_channel) = lockstep_channel_pair();
+    let handle = std::thread::spawn(move || {
+        let rng = AesRng::from_seed(seed.into());
+        let mut dmc = DietMacAndCheeseProver::init(
+            &mut prover_channel,
+            rng,
+            LPN_SETUP_SMALL,
+            LPN_EXTEND_SMALL,
+            false,
+        )?;
+        let result = prover(&mut dmc).and_then(|t| dmc.finalize().map(|_| t));
+        if result.is_err() {
+            dmc.reset();
+        }
+        result
+    });
+
+    let rng = AesRng::from_seed((seed + 1).into());
+    let verifier_result = DietMacAndCheeseVerifier::init(
+        &mut verifier_channel,
+        rng,
+        LPN_SETUP_SMALL,
+        LPN_EXTEND_SMALL,
+        false,
+    )
+    .and_then(|mut dmc| {
+        let result = verifier(&mut dmc).and_then(|u| dmc.finalize().map(|_| u));
+        if result.is_err() {
+            dmc.reset();
+        }
+        result
+    });
+    drop(verifier_channel);
+
+    (handle.join().unwrap(), verifier_result)
+}
+
+pub(crate) fn f61p(x: u64) -> F61p {
+    F61p::try_from(u128::from(x)).unwrap()
+}
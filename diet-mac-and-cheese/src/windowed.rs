@@ -0,0 +1,218 @@
+//! Evaluation of long circuits in a sliding window of wires.
+//!
+//! A [`Windowed`] circuit numbers its wires in the order they are produced and
+//! keeps only the last `window` of them, so that the memory held by the wires
+//! does not grow with the length of the circuit. The window declares the
+//! lifetimes of the wires: a gate may only use one of the last `window` wires,
+//! and a wire can be dropped earlier, after its last use, with
+//! [`Windowed::drop_wire`].
+//!
+//! For the backend to verify the circuit incrementally, and thus to bound the
+//! memory of its own queues, a [`DietMacAndCheeseProver`] should use the zero
+//! check schedule `Batched { capacity: window }` and a verifier should call
+//! [`DietMacAndCheeseVerifier::set_streaming_window`], with both parties using
+//! a multiplication check interval of `window`.
+//!
+//! [`DietMacAndCheeseProver`]: crate::DietMacAndCheeseProver
+//! [`DietMacAndCheeseVerifier::set_streaming_window`]: crate::DietMacAndCheeseVerifier::set_streaming_window
+
+use crate::backend_trait::BackendT;
+use eyre::{eyre, Result};
+use std::collections::VecDeque;
+
+/// A circuit evaluated on a backend, keeping only the last `window` wires.
+///
+/// The gates take and return the identifiers of the wires, which are
+/// consecutive integers starting from zero.
+pub struct Windowed<'a, B: BackendT> {
+    backend: &'a mut B,
+    window: usize,
+    // The identifier of the first wire of `wires`.
+    first: usize,
+    wires: VecDeque<Option<B::Wire>>,
+}
+
+impl<'a, B: BackendT> Windowed<'a, B> {
+    /// Create a circuit evaluated on `backend` in a window of `window` wires.
+    ///
+    /// # Panics
+    /// This function will panic if `window` is zero.
+    pub fn new(backend: &'a mut B, window: usize) -> Self {
+        assert_ne!(window, 0, "window must be nonzero");
+        Self {
+            backend,
+            window,
+            first: 0,
+            wires: VecDeque::with_capacity(window + 1),
+        }
+    }
+
+    /// Return the underlying backend, e.g., to call `finalize`.
+    pub fn backend(&mut self) -> &mut B {
+        self.backend
+    }
+
+    /// Return the number of wires held in the window, which is at most `window`.
+    pub fn live_wires(&self) -> usize {
+        self.wires.iter().filter(|w| w.is_some()).count()
+    }
+
+    /// Return the wire `id`, which fails if it is outside the window or dropped.
+    pub fn get(&self, id: usize) -> Result<&B::Wire> {
+        live_wire(&self.wires, self.first, id)
+    }
+
+    /// Drop the wire `id` after its last use.
+    pub fn drop_wire(&mut self, id: usize) -> Result<()> {
+        self.get(id)?;
+        self.wires[id - self.first] = None;
+        Ok(())
+    }
+
+    fn push(&mut self, wire: B::Wire) -> usize {
+        let id = self.first + self.wires.len();
+        self.wires.push_back(Some(wire));
+        if self.wires.len() > self.window {
+            self.wires.pop_front();
+            self.first += 1;
+        }
+        id
+    }
+
+    /// Input a public value.
+    pub fn input_public(&mut self, val: B::FieldElement) -> Result<usize> {
+        let wire = self.backend.input_public(val)?;
+        Ok(self.push(wire))
+    }
+
+    /// Input a private value, which is `None` for the verifier.
+    pub fn input_private(&mut self, val: Option<B::FieldElement>) -> Result<usize> {
+        let wire = self.backend.input_private(val)?;
+        Ok(self.push(wire))
+    }
+
+    /// Add a constant wire.
+    pub fn constant(&mut self, val: B::FieldElement) -> Result<usize> {
+        let wire = self.backend.constant(val)?;
+        Ok(self.push(wire))
+    }
+
+    /// Add two wires.
+    pub fn add(&mut self, a: usize, b: usize) -> Result<usize> {
+        let wire = self.backend.add(
+            live_wire(&self.wires, self.first, a)?,
+            live_wire(&self.wires, self.first, b)?,
+        )?;
+        Ok(self.push(wire))
+    }
+
+    /// Multiply two wires.
+    pub fn mul(&mut self, a: usize, b: usize) -> Result<usize> {
+        let wire = self.backend.mul(
+            live_wire(&self.wires, self.first, a)?,
+            live_wire(&self.wires, self.first, b)?,
+        )?;
+        Ok(self.push(wire))
+    }
+
+    /// Add a constant to a wire.
+    pub fn add_constant(&mut self, a: usize, b: B::FieldElement) -> Result<usize> {
+        let wire = self
+            .backend
+            .add_constant(live_wire(&self.wires, self.first, a)?, b)?;
+        Ok(self.push(wire))
+    }
+
+    /// Multiply a wire by a constant.
+    pub fn mul_constant(&mut self, a: usize, b: B::FieldElement) -> Result<usize> {
+        let wire = self
+            .backend
+            .mul_constant(live_wire(&self.wires, self.first, a)?, b)?;
+        Ok(self.push(wire))
+    }
+
+    /// Assert that a wire is zero.
+    pub fn assert_zero(&mut self, a: usize) -> Result<()> {
+        self.backend
+            .assert_zero(live_wire(&self.wires, self.first, a)?)
+    }
+}
+
+// Return the wire `id` of `wires`, whose first wire is `first`.
+fn live_wire<W>(wires: &VecDeque<Option<W>>, first: usize, id: usize) -> Result<&W> {
+    id.checked_sub(first)
+        .and_then(|i| wires.get(i))
+        .and_then(|w| w.as_ref())
+        .ok_or_else(|| {
+            eyre!(
+                "Wire {} is not live, the window holds the wires from {}",
+                id,
+                first
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Windowed;
+    use crate::backend_trait::BackendT;
+    use crate::plaintext::PlaintextBackend;
+    use crate::test_utils::{f61p, run};
+    use crate::zero_check::Batched;
+    use eyre::Result;
+    use scuttlebutt::{field::F61p, ring::FiniteRing};
+
+    const WINDOW: usize = 4;
+    const STEPS: usize = 2000;
+
+    // Iterate `x -> x^2 + 1` from `x = 3` and check the result.
+    fn iterate<B: BackendT<FieldElement = F61p>>(
+        backend: &mut B,
+        witness: Option<F61p>,
+    ) -> Result<()> {
+        let mut circuit = Windowed::new(backend, WINDOW);
+        let mut x = circuit.input_private(witness)?;
+        let mut expected = f61p(3);
+        for _ in 0..STEPS {
+            let square = circuit.mul(x, x)?;
+            x = circuit.add_constant(square, F61p::ONE)?;
+            expected = expected * expected + F61p::ONE;
+            assert!(circuit.live_wires() <= WINDOW);
+        }
+        let diff = circuit.add_constant(x, -expected)?;
+        circuit.assert_zero(diff)
+    }
+
+    #[test]
+    fn test_windowed_long_computation() {
+        let (prover, verifier) = run::<F61p, _, _>(
+            |dmc| {
+                dmc.set_zero_check_schedule(Box::new(Batched { capacity: WINDOW }));
+                dmc.set_mult_check_interval(Some(WINDOW));
+                iterate(dmc, Some(f61p(3)))
+            },
+            |dmc| {
+                dmc.set_streaming_window(WINDOW);
+                dmc.set_mult_check_interval(Some(WINDOW));
+                iterate(dmc, None)
+            },
+        );
+        prover.unwrap();
+        verifier.unwrap();
+    }
+
+    #[test]
+    fn test_windowed_dropped_wire_fails() {
+        let mut plaintext = PlaintextBackend::<F61p>::new();
+        let mut circuit = Windowed::new(&mut plaintext, 2);
+        let a = circuit.input_private(Some(f61p(1))).unwrap();
+        let b = circuit.input_private(Some(f61p(2))).unwrap();
+        circuit.add(a, b).unwrap();
+        // `a` left the window.
+        assert!(circuit.add(a, b).is_err());
+        circuit.drop_wire(b).unwrap();
+        assert!(circuit.get(b).is_err());
+        assert!(circuit.drop_wire(b).is_err());
+        assert_eq!(circuit.live_wires(), 1);
+    }
+}
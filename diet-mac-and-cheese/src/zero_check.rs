@@ -0,0 +1,77 @@
+//! Schedules of the checks of the values asserted to be zero.
+//!
+//! Values asserted to be zero are queued, and checked in batches by taking a
+//! random linear combination of the queued values. A [`ZeroCheckSchedule`]
+//! decides when the queue is checked and how many values are combined in each
+//! check. Both parties must use the same schedule.
+//!
+//! A schedule is only a policy: the interaction of each check is the one of
+//! `FComProver::check_zero` and `FComVerifier::check_zero`, so a schedule
+//! cannot change the messages sent, only when they are sent and how many
+//! values each of them covers. [`Eager`], [`Batched`] and [`Chunked`]
+//! respectively check each value on its own, the whole queue with a single
+//! random linear combination, and the queue in chunks.
+
+/// A schedule for checking the queue of values asserted to be zero.
+///
+/// Schedules are shared between a prover and the configuration returned by
+/// its `config`, which may be sent to other threads.
+pub trait ZeroCheckSchedule: Send + Sync {
+    /// Return whether the `queued` values should be checked now.
+    ///
+    /// The queue is always checked on `finalize`.
+    fn should_check(&self, queued: usize) -> bool;
+
+    /// Return the number of values combined in a single check, when checking
+    /// `queued` values. Each check requires one round of interaction.
+    fn chunk_size(&self, queued: usize) -> usize {
+        queued
+    }
+}
+
+/// Check each value as soon as it is asserted to be zero.
+///
+/// This pinpoints a failing assertion at the cost of one round of interaction
+/// per assertion.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Eager;
+
+impl ZeroCheckSchedule for Eager {
+    fn should_check(&self, queued: usize) -> bool {
+        queued > 0
+    }
+}
+
+/// Check the queued values with a single random linear combination once
+/// `capacity` values are queued.
+#[derive(Clone, Copy, Debug)]
+pub struct Batched {
+    /// The number of queued values triggering a check.
+    pub capacity: usize,
+}
+
+impl ZeroCheckSchedule for Batched {
+    fn should_check(&self, queued: usize) -> bool {
+        queued >= self.capacity
+    }
+}
+
+/// Check the queued values once `capacity` values are queued, with one random
+/// linear combination per chunk of `chunk` values.
+#[derive(Clone, Copy, Debug)]
+pub struct Chunked {
+    /// The number of queued values triggering a check.
+    pub capacity: usize,
+    /// The number of values combined in a single check.
+    pub chunk: usize,
+}
+
+impl ZeroCheckSchedule for Chunked {
+    fn should_check(&self, queued: usize) -> bool {
+        queued >= self.capacity
+    }
+
+    fn chunk_size(&self, _queued: usize) -> usize {
+        self.chunk
+    }
+}
@@ -123,6 +123,32 @@ def build_and_test(
             )
 
 
+def test_aarch64_pmull():
+    "Run the F128b tests on AArch64 under QEMU, with its PMULL path enabled."
+    with gitlab_ci_section("F128b on aarch64 with PMULL"):
+        restore_cargo_config()
+        pretty_check_call(
+            [
+                "cargo",
+                "test",
+                "-p",
+                "scuttlebutt",
+                "--lib",
+                "--target",
+                "aarch64-unknown-linux-gnu",
+                "--verbose",
+                "--",
+                "f128b",
+            ],
+            extra_env={
+                "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER": "aarch64-unknown-linux-gnu-cc",
+                "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUNNER": "qemu-aarch64",
+                "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUSTFLAGS": "-C target-feature=+aes",
+                "CC_aarch64_unknown_linux_gnu": "aarch64-unknown-linux-gnu-cc",
+            },
+        )
+
+
 @app.command()
 def ci(nightly: bool = False):
     os.environ["CARGO_INCREMENTAL"] = "0"
@@ -326,6 +352,7 @@ def ci(nightly: bool = False):
                 pretty_check_call(["cargo", "test", "-p", "vectoreyes", "--verbose"])
         else:
             build_and_test(features=["serde"], cache_test_output=True)
+        test_aarch64_pmull()
 
 
 if __name__ == "__main__":
@@ -9,6 +9,8 @@ with import ./pkgs.nix {};
     cargo-nextest
     cargo-deny
     lld_16
+    qemu
+    pkgsCross.aarch64-multiplatform.stdenv.cc
     git
     (python310.withPackages (py: [
       py.toml
@@ -5,4 +5,6 @@ in
 rust-bin.fromRustupToolchain {
   channel = rust-toolchain-version;
   components = [ "rustfmt" "llvm-tools-preview" ];
+  # The F128b multiplication has a PMULL path on AArch64, tested under QEMU.
+  targets = [ "aarch64-unknown-linux-gnu" ];
 }
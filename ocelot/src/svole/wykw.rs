@@ -9,6 +9,7 @@ mod svole;
 mod utils;
 
 pub use svole::{
-    LpnParams, Receiver, Sender, LPN_EXTEND_EXTRASMALL, LPN_EXTEND_LARGE, LPN_EXTEND_MEDIUM,
-    LPN_EXTEND_SMALL, LPN_SETUP_EXTRASMALL, LPN_SETUP_LARGE, LPN_SETUP_MEDIUM, LPN_SETUP_SMALL,
+    recommended_lpn, LpnParams, Receiver, Sender, LPN_EXTEND_EXTRASMALL, LPN_EXTEND_LARGE,
+    LPN_EXTEND_MEDIUM, LPN_EXTEND_SMALL, LPN_SETUP_EXTRASMALL, LPN_SETUP_LARGE, LPN_SETUP_MEDIUM,
+    LPN_SETUP_SMALL,
 };
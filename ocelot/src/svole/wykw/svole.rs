@@ -95,6 +95,35 @@ pub const LPN_EXTEND_LARGE: LpnParams = LpnParams {
     weight: 1_319,
 };
 
+impl LpnParams {
+    // The number of VOLEs output by an extension with these parameters, up to
+    // the degree of the field which is negligible here.
+    const fn extension_size(&self) -> usize {
+        self.cols - self.rows - self.weight
+    }
+}
+
+/// The number of extensions with medium LPN parameters after which
+/// [`recommended_lpn`] switches to the large LPN parameters.
+const MEDIUM_EXTENSIONS_MAX: usize = 10;
+
+/// Return the setup and extend LPN parameters suited to generating about
+/// `expected_voles` VOLEs, e.g., the number of inputs and multiplications of a
+/// circuit.
+///
+/// The small parameters are returned when a single small extension is enough,
+/// the medium parameters when a few medium extensions are enough, and the large
+/// parameters otherwise.
+pub fn recommended_lpn(expected_voles: usize) -> (LpnParams, LpnParams) {
+    if expected_voles <= LPN_EXTEND_SMALL.extension_size() {
+        (LPN_SETUP_SMALL, LPN_EXTEND_SMALL)
+    } else if expected_voles <= MEDIUM_EXTENSIONS_MAX * LPN_EXTEND_MEDIUM.extension_size() {
+        (LPN_SETUP_MEDIUM, LPN_EXTEND_MEDIUM)
+    } else {
+        (LPN_SETUP_LARGE, LPN_EXTEND_LARGE)
+    }
+}
+
 // Constant `d` representing a `d`-local linear code, meaning that each column
 // of the LPN matrix contains exactly `d` non-zero entries.
 const LPN_PARAMS_D: usize = 10;
@@ -456,7 +485,10 @@ impl<FF: FiniteField> Malicious for Receiver<FF> {}
 
 #[cfg(test)]
 mod tests {
-    use super::{Receiver, SVoleReceiver, SVoleSender, Sender, LPN_EXTEND_SMALL, LPN_SETUP_SMALL};
+    use super::{
+        recommended_lpn, Receiver, SVoleReceiver, SVoleSender, Sender, LPN_EXTEND_LARGE,
+        LPN_EXTEND_MEDIUM, LPN_EXTEND_SMALL, LPN_SETUP_LARGE, LPN_SETUP_MEDIUM, LPN_SETUP_SMALL,
+    };
     use scuttlebutt::{
         field::{F128b, F40b, F61p, FiniteField as FF},
         AesRng, Channel,
@@ -567,4 +599,17 @@ mod tests {
     fn test_duplicate_svole_f40b() {
         test_duplicate_svole_::<F40b, Sender<F40b>, Receiver<F40b>>();
     }
+
+    #[test]
+    fn test_recommended_lpn() {
+        let small = (LPN_SETUP_SMALL, LPN_EXTEND_SMALL);
+        let medium = (LPN_SETUP_MEDIUM, LPN_EXTEND_MEDIUM);
+        let large = (LPN_SETUP_LARGE, LPN_EXTEND_LARGE);
+        assert!(recommended_lpn(0) == small);
+        assert!(recommended_lpn(100_000) == small);
+        assert!(recommended_lpn(LPN_EXTEND_SMALL.extension_size()) == small);
+        assert!(recommended_lpn(LPN_EXTEND_SMALL.extension_size() + 1) == medium);
+        assert!(recommended_lpn(10_000_000) == medium);
+        assert!(recommended_lpn(1_000_000_000) == large);
+    }
 }
@@ -82,6 +82,46 @@ finite_field_benchmarks!(f61p, scuttlebutt::field::F61p,);
 finite_field_benchmarks!(f64b, scuttlebutt::field::F64b,);
 finite_field_benchmarks!(f128b, scuttlebutt::field::F128b,);
 
+mod f128b_slices {
+    use criterion::{black_box, criterion_group, Criterion};
+    use scuttlebutt::field::F128b;
+    use scuttlebutt::ring::FiniteRing;
+
+    fn random_vec(n: usize) -> Vec<F128b> {
+        (0..n)
+            .map(|_| F128b::random(&mut rand::thread_rng()))
+            .collect()
+    }
+
+    fn mul_loop(c: &mut Criterion) {
+        c.bench_function("F128b::mul1024", |bench| {
+            let a = random_vec(1024);
+            let b = random_vec(1024);
+            let mut out = vec![F128b::ZERO; 1024];
+            bench.iter(|| {
+                for ((out, a), b) in out.iter_mut().zip(black_box(&a)).zip(black_box(&b)) {
+                    *out = *a * *b;
+                }
+                black_box(&out);
+            });
+        });
+    }
+
+    fn mul_slice(c: &mut Criterion) {
+        c.bench_function("F128b::mul_slice1024", |bench| {
+            let a = random_vec(1024);
+            let b = random_vec(1024);
+            let mut out = vec![F128b::ZERO; 1024];
+            bench.iter(|| {
+                F128b::mul_slice(&mut out, black_box(&a), black_box(&b));
+                black_box(&out);
+            });
+        });
+    }
+
+    criterion_group!(f128b_slices, mul_loop, mul_slice);
+}
+
 finite_field_benchmarks!(f40b, scuttlebutt::field::F40b,);
 finite_field_benchmarks!(f45b, scuttlebutt::field::F45b,);
 finite_field_benchmarks!(f56b, scuttlebutt::field::F56b,);
@@ -115,6 +155,7 @@ criterion::criterion_main!(
     f61p::f61p,
     f64b::f64b,
     f128b::f128b,
+    f128b_slices::f128b_slices,
     f40b::f40b,
     f45b::f45b,
     f56b::f56b,
@@ -126,6 +167,7 @@ criterion::criterion_main!(
     f61p::f61p,
     f64b::f64b,
     f128b::f128b,
+    f128b_slices::f128b_slices,
     f40b::f40b,
     f45b::f45b,
     f56b::f56b,
@@ -1,10 +1,18 @@
+mod backpressure_channel;
 mod hash_channel;
+mod lockstep_channel;
+mod pooled_channel;
+mod recording_channel;
 mod sync_channel;
 mod track_channel;
 #[cfg(unix)]
 mod unix_channel;
 
+pub use backpressure_channel::BackpressureChannel;
 pub use hash_channel::HashChannel;
+pub use lockstep_channel::{lockstep_channel_pair, LockstepChannel};
+pub use pooled_channel::{ChannelPool, PooledChannel};
+pub use recording_channel::RecordingChannel;
 pub use sync_channel::SyncChannel;
 pub use track_channel::TrackChannel;
 
@@ -34,6 +42,14 @@ pub trait AbstractChannel {
     fn clone(&self) -> Self
     where
         Self: Sized;
+    /// Return the number of bytes written to and read from the channel, in
+    /// that order, if the channel counts them.
+    ///
+    /// This is `None` by default, and is implemented by [`TrackChannel`]. The
+    /// channels wrapping another channel report the counts of the wrapped one.
+    fn comm_bytes(&self) -> Option<(usize, usize)> {
+        None
+    }
     /// Read `nbytes` from the channel, and return it as a `Vec`.
     fn read_vec(&mut self, nbytes: usize) -> Result<Vec<u8>> {
         let mut data = vec![0; nbytes];
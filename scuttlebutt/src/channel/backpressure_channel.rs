@@ -0,0 +1,134 @@
+use crate::AbstractChannel;
+use std::{
+    io::Result,
+    sync::{Arc, Mutex},
+};
+
+/// A channel wrapping another channel which forces a `flush` once a
+/// configurable number of bytes have been written since the last flush.
+///
+/// Buffered writers (such as a `BufWriter` inside a [`crate::Channel`]) only
+/// hand data to the underlying stream when their buffer is full or when
+/// `flush` is called explicitly. A fast writer talking to a slow reader can
+/// therefore get far ahead of its peer. Bounding the number of unflushed
+/// bytes keeps memory usage bounded and forces the writer to wait on the
+/// reader (backpressure).
+///
+/// When no threshold is set, the channel behaves exactly like the channel it
+/// wraps, and data is only flushed when `flush` is called explicitly.
+pub struct BackpressureChannel<C>(Arc<Mutex<InternalBackpressureChannel<C>>>);
+
+struct InternalBackpressureChannel<C> {
+    channel: C,
+    flush_threshold: Option<usize>,
+    nbytes_unflushed: usize,
+}
+
+impl<C: AbstractChannel> BackpressureChannel<C> {
+    /// Make a new `BackpressureChannel` from a `channel`.
+    ///
+    /// If `flush_threshold` is `Some(n)`, the channel is flushed each time at
+    /// least `n` bytes have been written since the last flush. If it is `None`,
+    /// flushing is left to the caller.
+    pub fn new(channel: C, flush_threshold: Option<usize>) -> Self {
+        assert_ne!(flush_threshold, Some(0), "flush threshold must be nonzero");
+        let internal = InternalBackpressureChannel {
+            channel,
+            flush_threshold,
+            nbytes_unflushed: 0,
+        };
+        Self(Arc::new(Mutex::new(internal)))
+    }
+
+    /// Return the flush threshold of the channel.
+    pub fn flush_threshold(&self) -> Option<usize> {
+        self.0.lock().unwrap().flush_threshold
+    }
+
+    /// Return the number of bytes written since the last flush.
+    pub fn bytes_unflushed(&self) -> usize {
+        self.0.lock().unwrap().nbytes_unflushed
+    }
+}
+
+impl<C: AbstractChannel> AbstractChannel for BackpressureChannel<C> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let mut int = self.0.lock().unwrap();
+        int.channel.write_bytes(bytes)?;
+        int.nbytes_unflushed += bytes.len();
+        if let Some(threshold) = int.flush_threshold {
+            if int.nbytes_unflushed >= threshold {
+                int.channel.flush()?;
+                int.nbytes_unflushed = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<()> {
+        self.0.lock().unwrap().channel.read_bytes(bytes)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let mut int = self.0.lock().unwrap();
+        int.nbytes_unflushed = 0;
+        int.channel.flush()
+    }
+
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+
+    fn comm_bytes(&self) -> Option<(usize, usize)> {
+        self.0.lock().unwrap().channel.comm_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BackpressureChannel;
+    use crate::{AbstractChannel, Channel, TrackChannel};
+    use std::io::{empty, sink};
+
+    fn write_chunks(threshold: Option<usize>, chunk: usize, nchunks: usize) -> usize {
+        let track = TrackChannel::new(Channel::new(empty(), sink()));
+        let mut channel = BackpressureChannel::new(track.clone(), threshold);
+        for _ in 0..nchunks {
+            channel.write_bytes(&vec![0u8; chunk]).unwrap();
+        }
+        assert_eq!(
+            track.kilobits_written(),
+            (chunk * nchunks * 8) as f64 / 1000.0
+        );
+        assert_eq!(channel.comm_bytes(), Some((chunk * nchunks, 0)));
+        track.flushes()
+    }
+
+    #[test]
+    fn test_lazy_flush_by_default() {
+        assert_eq!(write_chunks(None, 100, 100), 0);
+    }
+
+    #[test]
+    fn test_flush_at_threshold() {
+        // 10 chunks of 100 bytes each reach the 1000 bytes threshold exactly.
+        assert_eq!(write_chunks(Some(1000), 100, 100), 10);
+        // 3 chunks of 400 bytes exceed the 1000 bytes threshold.
+        assert_eq!(write_chunks(Some(1000), 400, 30), 10);
+        assert_eq!(write_chunks(Some(1), 7, 5), 5);
+    }
+
+    #[test]
+    fn test_explicit_flush_resets_count() {
+        let track = TrackChannel::new(Channel::new(empty(), sink()));
+        let mut channel = BackpressureChannel::new(track.clone(), Some(100));
+        channel.write_bytes(&[0u8; 60]).unwrap();
+        channel.flush().unwrap();
+        assert_eq!(channel.bytes_unflushed(), 0);
+        channel.write_bytes(&[0u8; 60]).unwrap();
+        assert_eq!(channel.bytes_unflushed(), 60);
+        assert_eq!(track.flushes(), 1);
+        channel.write_bytes(&[0u8; 40]).unwrap();
+        assert_eq!(track.flushes(), 2);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::AbstractChannel;
+use std::{
+    collections::VecDeque,
+    io::{Error, ErrorKind, Result},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+/// One end of an in-memory channel pair whose parties run one at a time.
+///
+/// The two parties of a [`lockstep_channel_pair`] take turns: party `0` runs
+/// first, and a party only hands over to its peer when it reads more bytes than
+/// it has received. Each party may run on its own thread, but only one of them
+/// touches the channel between two hand-overs, so the interleaving of the
+/// parties only depends on the messages they exchange. With seeded randomness,
+/// a run is fully deterministic, which makes the pair suitable for fuzzing.
+///
+/// Written bytes are delivered immediately, so `flush` does nothing. A read
+/// fails when the peer has dropped its end without sending enough bytes, or
+/// when both parties are waiting on each other. Each end counts the bytes it
+/// writes and reads, as reported by `comm_bytes`.
+pub struct LockstepChannel(Arc<Endpoint>);
+
+struct Endpoint {
+    shared: Arc<Shared>,
+    party: usize,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    turn_changed: Condvar,
+}
+
+struct State {
+    // The bytes received by each party and not read yet.
+    inboxes: [VecDeque<u8>; 2],
+    // The number of bytes each party is waiting for, if it is waiting.
+    waiting: [Option<usize>; 2],
+    // The number of bytes written and read by each party.
+    written: [usize; 2],
+    read: [usize; 2],
+    dropped: [bool; 2],
+    turn: usize,
+}
+
+/// Make a pair of [`LockstepChannel`]s connected to each other.
+///
+/// The first channel is party `0`, which runs first.
+pub fn lockstep_channel_pair() -> (LockstepChannel, LockstepChannel) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            inboxes: [VecDeque::new(), VecDeque::new()],
+            waiting: [None, None],
+            written: [0, 0],
+            read: [0, 0],
+            dropped: [false, false],
+            turn: 0,
+        }),
+        turn_changed: Condvar::new(),
+    });
+    let endpoint = |party| {
+        LockstepChannel(Arc::new(Endpoint {
+            shared: shared.clone(),
+            party,
+        }))
+    };
+    (endpoint(0), endpoint(1))
+}
+
+impl Endpoint {
+    // Lock the state once it is the turn of this party.
+    fn wait_turn(&self) -> MutexGuard<'_, State> {
+        let mut state = self.shared.state.lock().unwrap();
+        while state.turn != self.party && !state.dropped[1 - self.party] {
+            state = self.shared.turn_changed.wait(state).unwrap();
+        }
+        state
+    }
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.dropped[self.party] = true;
+        state.turn = 1 - self.party;
+        self.shared.turn_changed.notify_all();
+    }
+}
+
+impl AbstractChannel for LockstepChannel {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let mut state = self.0.wait_turn();
+        let peer = 1 - self.0.party;
+        if state.dropped[peer] {
+            return Err(Error::new(
+                ErrorKind::BrokenPipe,
+                "the peer dropped its lockstep channel",
+            ));
+        }
+        state.inboxes[peer].extend(bytes);
+        state.written[self.0.party] += bytes.len();
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<()> {
+        let party = self.0.party;
+        let peer = 1 - party;
+        let mut state = self.0.wait_turn();
+        while state.inboxes[party].len() < bytes.len() {
+            if state.dropped[peer] {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "the peer dropped its lockstep channel",
+                ));
+            }
+            if let Some(needed) = state.waiting[peer] {
+                if state.inboxes[peer].len() < needed {
+                    return Err(Error::new(
+                        ErrorKind::WouldBlock,
+                        "both parties of the lockstep channel are waiting on each other",
+                    ));
+                }
+            }
+            state.waiting[party] = Some(bytes.len());
+            state.turn = peer;
+            self.0.shared.turn_changed.notify_all();
+            while state.turn != party {
+                state = self.0.shared.turn_changed.wait(state).unwrap();
+            }
+            state.waiting[party] = None;
+        }
+        let n = bytes.len();
+        for (dst, src) in bytes.iter_mut().zip(state.inboxes[party].drain(..n)) {
+            *dst = src;
+        }
+        state.read[party] += n;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+
+    fn comm_bytes(&self) -> Option<(usize, usize)> {
+        let state = self.0.shared.state.lock().unwrap();
+        let party = self.0.party;
+        Some((state.written[party], state.read[party]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lockstep_channel_pair;
+    use crate::AbstractChannel;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_lockstep_ping_pong() {
+        let (mut a, mut b) = lockstep_channel_pair();
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let trace_b = trace.clone();
+        let handle = std::thread::spawn(move || {
+            for i in 0..10u8 {
+                let x = b.read_u8().unwrap();
+                trace_b.lock().unwrap().push(('b', x));
+                b.write_u8(x + i).unwrap();
+            }
+            // The peer runs again once `b` is dropped.
+            b.comm_bytes()
+        });
+        for i in 0..10u8 {
+            a.write_u8(i).unwrap();
+            trace.lock().unwrap().push(('a', i));
+            assert_eq!(a.read_u8().unwrap(), 2 * i);
+        }
+        let b_comm_bytes = handle.join().unwrap();
+        let expected: Vec<_> = (0..10).flat_map(|i| [('a', i), ('b', i)]).collect();
+        assert_eq!(*trace.lock().unwrap(), expected);
+        assert_eq!(a.comm_bytes(), Some((10, 10)));
+        assert_eq!(b_comm_bytes, Some((10, 10)));
+    }
+
+    #[test]
+    fn test_lockstep_errors() {
+        // Both parties waiting on each other fail instead of hanging.
+        let (mut a, mut b) = lockstep_channel_pair();
+        let handle = std::thread::spawn(move || b.read_u8());
+        let a_result = a.read_u8();
+        drop(a);
+        let b_result = handle.join().unwrap();
+        assert!(a_result.is_err() && b_result.is_err());
+
+        // Reading from a dropped peer fails once its messages are consumed.
+        let (mut a, mut b) = lockstep_channel_pair();
+        a.write_u8(1).unwrap();
+        drop(a);
+        assert_eq!(b.read_u8().unwrap(), 1);
+        assert!(b.read_u8().is_err());
+        assert!(b.write_u8(2).is_err());
+    }
+}
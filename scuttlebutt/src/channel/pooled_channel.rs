@@ -0,0 +1,215 @@
+use crate::AbstractChannel;
+use std::{
+    cell::{Cell, RefCell},
+    io::{Read, Result, Write},
+    rc::Rc,
+};
+
+/// A pool of read/write buffers shared by [`PooledChannel`]s.
+///
+/// Creating a `Channel` from a `BufReader` and a `BufWriter` allocates two new
+/// buffers. When proving many small statements in a row, the pool lets each
+/// channel borrow buffers which have been used by earlier channels instead.
+/// The buffers are returned to the pool, cleared but not deallocated, when the
+/// channel and all its clones are dropped.
+pub struct ChannelPool(Rc<InternalChannelPool>);
+
+struct InternalChannelPool {
+    capacity: usize,
+    free: RefCell<Vec<Buffers>>,
+    nallocations: Cell<usize>,
+}
+
+struct Buffers {
+    read: Vec<u8>,
+    read_start: usize,
+    read_end: usize,
+    write: Vec<u8>,
+}
+
+impl Buffers {
+    fn reset(&mut self) {
+        self.read_start = 0;
+        self.read_end = 0;
+        self.write.clear();
+    }
+}
+
+impl ChannelPool {
+    /// Make a new `ChannelPool` whose read and write buffers hold `capacity` bytes.
+    pub fn new(capacity: usize) -> Self {
+        assert_ne!(capacity, 0, "buffer capacity must be nonzero");
+        Self(Rc::new(InternalChannelPool {
+            capacity,
+            free: RefCell::new(Vec::new()),
+            nallocations: Cell::new(0),
+        }))
+    }
+
+    /// Make a new `PooledChannel` from a `reader` and a `writer`, reusing
+    /// buffers from the pool when available.
+    pub fn channel<R: Read, W: Write>(&self, reader: R, writer: W) -> PooledChannel<R, W> {
+        let buffers = self.0.free.borrow_mut().pop().unwrap_or_else(|| {
+            self.0.nallocations.set(self.0.nallocations.get() + 1);
+            Buffers {
+                read: vec![0; self.0.capacity],
+                read_start: 0,
+                read_end: 0,
+                write: Vec::with_capacity(self.0.capacity),
+            }
+        });
+        PooledChannel(Rc::new(RefCell::new(InternalPooledChannel {
+            reader,
+            writer,
+            buffers: Some(buffers),
+            pool: self.0.clone(),
+        })))
+    }
+
+    /// Return the number of buffers allocated by the pool.
+    pub fn allocations(&self) -> usize {
+        self.0.nallocations.get()
+    }
+}
+
+/// A buffered channel whose buffers are borrowed from a [`ChannelPool`].
+///
+/// Unlike a `BufWriter`, data which has not been flushed is discarded when the
+/// channel is dropped.
+pub struct PooledChannel<R, W>(Rc<RefCell<InternalPooledChannel<R, W>>>);
+
+struct InternalPooledChannel<R, W> {
+    reader: R,
+    writer: W,
+    // Only `None` while being returned to the pool.
+    buffers: Option<Buffers>,
+    pool: Rc<InternalChannelPool>,
+}
+
+impl<R, W> Drop for InternalPooledChannel<R, W> {
+    fn drop(&mut self) {
+        if let Some(mut buffers) = self.buffers.take() {
+            buffers.reset();
+            self.pool.free.borrow_mut().push(buffers);
+        }
+    }
+}
+
+impl<R: Read, W: Write> InternalPooledChannel<R, W> {
+    fn write_buffered(&mut self) -> Result<()> {
+        let buffers = self.buffers.as_mut().unwrap();
+        self.writer.write_all(&buffers.write)?;
+        buffers.write.clear();
+        Ok(())
+    }
+}
+
+impl<R: Read, W: Write> PooledChannel<R, W> {
+    /// Discard any buffered data, keeping the buffers allocated.
+    pub fn reset(&mut self) {
+        self.0.borrow_mut().buffers.as_mut().unwrap().reset();
+    }
+}
+
+impl<R: Read, W: Write> AbstractChannel for PooledChannel<R, W> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let mut int = self.0.borrow_mut();
+        let capacity = int.pool.capacity;
+        if int.buffers.as_ref().unwrap().write.len() + bytes.len() > capacity {
+            int.write_buffered()?;
+        }
+        if bytes.len() >= capacity {
+            int.writer.write_all(bytes)
+        } else {
+            int.buffers.as_mut().unwrap().write.extend_from_slice(bytes);
+            Ok(())
+        }
+    }
+
+    fn read_bytes(&mut self, mut bytes: &mut [u8]) -> Result<()> {
+        let mut guard = self.0.borrow_mut();
+        let int = &mut *guard;
+        let buffers = int.buffers.as_mut().unwrap();
+        while !bytes.is_empty() {
+            if buffers.read_start == buffers.read_end {
+                if bytes.len() >= buffers.read.len() {
+                    return int.reader.read_exact(bytes);
+                }
+                let n = int.reader.read(&mut buffers.read)?;
+                if n == 0 {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
+                buffers.read_start = 0;
+                buffers.read_end = n;
+            }
+            let n = bytes.len().min(buffers.read_end - buffers.read_start);
+            bytes[..n].copy_from_slice(&buffers.read[buffers.read_start..buffers.read_start + n]);
+            buffers.read_start += n;
+            bytes = &mut bytes[n..];
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let mut int = self.0.borrow_mut();
+        int.write_buffered()?;
+        int.writer.flush()
+    }
+
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelPool;
+    use crate::AbstractChannel;
+    use std::{io::empty, os::unix::net::UnixStream};
+
+    #[test]
+    fn test_round_trip() {
+        let pool = ChannelPool::new(16);
+        let (a, b) = UnixStream::pair().unwrap();
+        let mut sender = pool.channel(empty(), a);
+        let mut receiver = pool.channel(b, Vec::new());
+        let data: Vec<u8> = (0..100).collect();
+        for chunk in [&data[0..3], &data[3..40], &data[40..41], &data[41..]] {
+            sender.write_bytes(chunk).unwrap();
+        }
+        sender.flush().unwrap();
+        let mut out = vec![0u8; 100];
+        receiver.read_bytes(&mut out[0..10]).unwrap();
+        receiver.read_bytes(&mut out[10..70]).unwrap();
+        receiver.read_bytes(&mut out[70..]).unwrap();
+        assert_eq!(out, data);
+        assert_eq!(pool.allocations(), 2);
+    }
+
+    #[test]
+    fn test_buffers_are_reused() {
+        let pool = ChannelPool::new(16);
+        for _ in 0..10 {
+            let mut channel = pool.channel(empty(), Vec::new());
+            let clone = channel.clone();
+            channel.write_bytes(&[1, 2, 3]).unwrap();
+            drop(clone);
+        }
+        assert_eq!(pool.allocations(), 1);
+    }
+
+    #[test]
+    fn test_reset_discards_buffered_data() {
+        let pool = ChannelPool::new(16);
+        let (a, b) = UnixStream::pair().unwrap();
+        let mut sender = pool.channel(empty(), a);
+        let mut receiver = pool.channel(b, Vec::new());
+        sender.write_bytes(&[1, 2, 3]).unwrap();
+        sender.reset();
+        sender.write_bytes(&[4, 5]).unwrap();
+        sender.flush().unwrap();
+        let mut out = [0u8; 2];
+        receiver.read_bytes(&mut out).unwrap();
+        assert_eq!(out, [4, 5]);
+    }
+}
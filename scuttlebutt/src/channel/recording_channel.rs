@@ -0,0 +1,106 @@
+use crate::AbstractChannel;
+use std::{
+    cell::RefCell,
+    io::{Result, Write},
+    rc::Rc,
+};
+
+/// A channel wrapping another channel and copying all the bytes read to a sink.
+///
+/// The recording holds every message received by one party of an interactive
+/// protocol. Along with the randomness of that party, it lets the party be
+/// replayed offline by reading the recording back, e.g., with a [`Channel`]
+/// reading from the recording and writing to [`std::io::sink`].
+///
+/// [`Channel`]: crate::Channel
+pub struct RecordingChannel<C, W>(Rc<RefCell<InternalRecordingChannel<C, W>>>);
+
+struct InternalRecordingChannel<C, W> {
+    channel: C,
+    sink: W,
+}
+
+impl<C: AbstractChannel, W: Write> RecordingChannel<C, W> {
+    /// Make a new `RecordingChannel` copying the bytes read from `channel` to `sink`.
+    pub fn new(channel: C, sink: W) -> Self {
+        Self(Rc::new(RefCell::new(InternalRecordingChannel {
+            channel,
+            sink,
+        })))
+    }
+
+    /// Flush the sink and apply `f` to it.
+    pub fn with_sink<T>(&self, f: impl FnOnce(&mut W) -> T) -> Result<T> {
+        let mut int = self.0.borrow_mut();
+        int.sink.flush()?;
+        Ok(f(&mut int.sink))
+    }
+}
+
+impl<C: AbstractChannel, W: Write> AbstractChannel for RecordingChannel<C, W> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.0.borrow_mut().channel.write_bytes(bytes)
+    }
+
+    fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<()> {
+        let mut int = self.0.borrow_mut();
+        int.channel.read_bytes(bytes)?;
+        int.sink.write_all(bytes)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.0.borrow_mut().channel.flush()
+    }
+
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+
+    fn comm_bytes(&self) -> Option<(usize, usize)> {
+        self.0.borrow().channel.comm_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecordingChannel;
+    use crate::{AbstractChannel, Channel, TrackChannel};
+    use std::io::{empty, sink, Cursor};
+
+    #[test]
+    fn test_recording() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut channel =
+            RecordingChannel::new(Channel::new(Cursor::new(data.clone()), sink()), Vec::new());
+        let mut clone = channel.clone();
+        let mut out = vec![0u8; 100];
+        channel.read_bytes(&mut out[..40]).unwrap();
+        channel.write_bytes(&[1, 2, 3]).unwrap();
+        clone.read_bytes(&mut out[40..]).unwrap();
+        assert_eq!(out, data);
+        assert_eq!(channel.with_sink(|s| s.clone()).unwrap(), data);
+
+        // Replaying the recording gives back the same bytes.
+        let recording = channel.with_sink(|s| s.clone()).unwrap();
+        let mut replay = Channel::new(Cursor::new(recording), sink());
+        let mut replayed = vec![0u8; 100];
+        replay.read_bytes(&mut replayed).unwrap();
+        assert_eq!(replayed, data);
+        assert!(Channel::new(empty(), sink()).read_bytes(&mut [0]).is_err());
+    }
+
+    #[test]
+    fn test_recording_comm_bytes() {
+        let channel = Channel::new(Cursor::new(vec![0u8; 10]), sink());
+        let mut channel = RecordingChannel::new(TrackChannel::new(channel), Vec::new());
+        channel.read_bytes(&mut [0u8; 4]).unwrap();
+        channel.write_bytes(&[1, 2, 3]).unwrap();
+        assert_eq!(channel.comm_bytes(), Some((3, 4)));
+
+        let channel = Channel::new(Cursor::new(vec![0u8; 10]), sink());
+        assert_eq!(
+            RecordingChannel::new(channel, Vec::new()).comm_bytes(),
+            None
+        );
+    }
+}
@@ -5,12 +5,16 @@ use std::{
 };
 
 /// A channel wrapping another channel for tracking the number of bits read/written.
+///
+/// The counts are shared by the clones of the channel, and are reset with
+/// [`TrackChannel::clear`].
 pub struct TrackChannel<C>(Arc<Mutex<InternalTrackChannel<C>>>);
 
 struct InternalTrackChannel<C> {
     channel: C,
     nbits_read: usize,
     nbits_written: usize,
+    nflushes: usize,
 }
 
 impl<C: AbstractChannel> TrackChannel<C> {
@@ -20,15 +24,32 @@ impl<C: AbstractChannel> TrackChannel<C> {
             channel,
             nbits_read: 0,
             nbits_written: 0,
+            nflushes: 0,
         };
         Self(Arc::new(Mutex::new(internal)))
     }
 
-    /// Clear the number of bits read/written.
+    /// Clear the number of bits read/written and the number of flushes.
     pub fn clear(&mut self) {
         let mut int = self.0.lock().unwrap();
         int.nbits_read = 0;
         int.nbits_written = 0;
+        int.nflushes = 0;
+    }
+
+    /// Return the number of times the channel has been flushed.
+    pub fn flushes(&self) -> usize {
+        self.0.lock().unwrap().nflushes
+    }
+
+    /// Return the number of bytes written to the channel.
+    pub fn bytes_written(&self) -> usize {
+        self.0.lock().unwrap().nbits_written / 8
+    }
+
+    /// Return the number of bytes read from the channel.
+    pub fn bytes_read(&self) -> usize {
+        self.0.lock().unwrap().nbits_read / 8
     }
 
     /// Return the number of kilobits written to the channel.
@@ -77,10 +98,45 @@ impl<C: AbstractChannel> AbstractChannel for TrackChannel<C> {
     }
 
     fn flush(&mut self) -> Result<()> {
-        self.0.lock().unwrap().channel.flush()
+        let mut int = self.0.lock().unwrap();
+        int.nflushes += 1;
+        int.channel.flush()
     }
 
     fn clone(&self) -> Self {
         Self(self.0.clone())
     }
+
+    fn comm_bytes(&self) -> Option<(usize, usize)> {
+        Some((self.bytes_written(), self.bytes_read()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackChannel;
+    use crate::{AbstractChannel, Channel};
+    use std::io::{sink, Cursor};
+
+    #[test]
+    fn test_track() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut channel = TrackChannel::new(Channel::new(Cursor::new(data.clone()), sink()));
+        let mut clone = channel.clone();
+        let mut out = vec![0u8; 100];
+        channel.read_bytes(&mut out[..40]).unwrap();
+        channel.write_bytes(&[1, 2, 3]).unwrap();
+        clone.read_bytes(&mut out[40..]).unwrap();
+        clone.flush().unwrap();
+        assert_eq!(out, data);
+        assert_eq!(channel.bytes_read(), 100);
+        assert_eq!(channel.bytes_written(), 3);
+        assert_eq!(channel.flushes(), 1);
+        assert_eq!(channel.comm_bytes(), Some((3, 100)));
+
+        channel.clear();
+        assert_eq!(clone.bytes_read(), 0);
+        assert_eq!(clone.bytes_written(), 0);
+        assert_eq!(clone.flushes(), 0);
+    }
 }
@@ -60,6 +60,106 @@ pub trait FiniteField: FiniteRing + DivAssign<Self> + Div<Self, Output = Self> {
     /// This function will panic if `*self == Self::ZERO`
     fn inverse(&self) -> Self;
 
+    /// Return whether `self` and `other` are equal, in constant time.
+    ///
+    /// This is [`subtle::ConstantTimeEq`] returning a `bool`, so callers need not
+    /// depend on `subtle` themselves.
+    #[inline]
+    fn eq_bool(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+
+    /// Return the order of the multiplicative group and its distinct prime
+    /// factors, if known.
+    ///
+    /// This is `None` by default, for fields whose multiplicative group order
+    /// does not fit in a `u128` or has not been factored.
+    fn multiplicative_group_factorization() -> Option<(u128, &'static [u128])> {
+        None
+    }
+
+    /// Check with `debug_assert`s that [`FiniteRing::ZERO`], [`FiniteRing::ONE`]
+    /// and [`Self::GENERATOR`] satisfy basic axioms, to catch bugs in the
+    /// implementation of a field early.
+    ///
+    /// This checks that `ZERO` and `ONE` are the additive and multiplicative
+    /// identities on these three elements, that `GENERATOR` is nonzero and, when
+    /// [`Self::multiplicative_group_factorization`] is known, that `GENERATOR`
+    /// generates the whole multiplicative group. This does nothing in release builds.
+    fn self_check() {
+        debug_assert_ne!(Self::ZERO, Self::ONE, "ZERO and ONE must differ");
+        debug_assert_ne!(Self::GENERATOR, Self::ZERO, "GENERATOR must be nonzero");
+        for x in [Self::ZERO, Self::ONE, Self::GENERATOR] {
+            debug_assert_eq!(Self::ZERO + x, x, "ZERO is not the additive identity");
+            debug_assert_eq!(Self::ONE * x, x, "ONE is not the multiplicative identity");
+        }
+        if cfg!(debug_assertions) {
+            if let Some((order, _)) = Self::multiplicative_group_factorization() {
+                debug_assert_eq!(
+                    Self::GENERATOR.multiplicative_order(),
+                    Some(order),
+                    "GENERATOR does not generate the multiplicative group"
+                );
+            }
+        }
+    }
+
+    /// Return the constants of the Montgomery representation of this field, e.g.,
+    /// to generate matching GPU kernels.
+    ///
+    /// This is `None` by default, which is the case of binary fields and of the
+    /// prime fields whose modulus does not fit in a single 64-bit limb.
+    fn montgomery_params() -> Option<MontgomeryParams> {
+        None
+    }
+
+    /// Compute the multiplicative order of `self`.
+    ///
+    /// This returns `None` if `self` is zero, or if the factorization of the order
+    /// of the multiplicative group is unknown (see
+    /// [`Self::multiplicative_group_factorization`]).
+    fn multiplicative_order(&self) -> Option<u128> {
+        if *self == Self::ZERO {
+            return None;
+        }
+        let (mut order, factors) = Self::multiplicative_group_factorization()?;
+        for p in factors.iter() {
+            while order % p == 0 && self.pow_var_time(order / p) == Self::ONE {
+                order /= p;
+            }
+        }
+        Some(order)
+    }
+
+    /// Replace each nonzero element of `elements` by its inverse, leaving the
+    /// zero elements unchanged.
+    ///
+    /// This uses Montgomery's trick, which takes a single inversion and
+    /// `3(n - 1)` multiplications for `n` elements. The zero elements are
+    /// replaced by one during the computation, so that they don't affect the
+    /// other elements, and the elements are processed in constant time.
+    fn batch_inverse(elements: &mut [Self]) {
+        let n = elements.len();
+        if n == 0 {
+            return;
+        }
+        let mut prefix = Vec::with_capacity(n);
+        let mut acc = Self::ONE;
+        for x in elements.iter() {
+            acc *= Self::conditional_select(x, &Self::ONE, x.ct_eq(&Self::ZERO));
+            prefix.push(acc);
+        }
+        // `acc` is a product of nonzero elements.
+        let mut inv = acc.inverse();
+        for i in (0..n).rev() {
+            let x = elements[i];
+            let is_zero = x.ct_eq(&Self::ZERO);
+            let x_inv = if i == 0 { inv } else { inv * prefix[i - 1] };
+            inv *= Self::conditional_select(&x, &Self::ONE, is_zero);
+            elements[i] = Self::conditional_select(&x_inv, &Self::ZERO, is_zero);
+        }
+    }
+
     /// Decompose `self` into an array of `T` elements where `T` is a subfield of `Self`.
     ///
     /// See [`IsSubFieldOf`] for more info.
@@ -78,6 +178,43 @@ pub trait FiniteField: FiniteRing + DivAssign<Self> + Div<Self, Output = Self> {
     ) -> Self {
         T::form_superfield(arr)
     }
+    /// Create a field element from the coefficients of its polynomial
+    /// representation over [`Self::PrimeField`].
+    ///
+    /// This is [`Self::from_subfield`] for a slice, which fails instead of
+    /// panicking if `coeffs` does not have exactly [`Degree<Self>`] elements.
+    fn try_from_coefficients(
+        coeffs: &[Self::PrimeField],
+    ) -> Result<Self, WrongNumberOfCoefficients> {
+        let expected = <Degree<Self> as generic_array::typenum::Unsigned>::USIZE;
+        if coeffs.len() != expected {
+            return Err(WrongNumberOfCoefficients {
+                expected,
+                got: coeffs.len(),
+            });
+        }
+        Ok(Self::from_subfield(GenericArray::from_slice(coeffs)))
+    }
+}
+
+/// The error which occurs if the number of coefficients given to
+/// [`FiniteField::try_from_coefficients`] isn't the degree of the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrongNumberOfCoefficients {
+    /// The degree of the field.
+    pub expected: usize,
+    /// The number of coefficients given.
+    pub got: usize,
+}
+impl std::error::Error for WrongNumberOfCoefficients {}
+impl std::fmt::Display for WrongNumberOfCoefficients {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected {} coefficients, got {}",
+            self.expected, self.got
+        )
+    }
 }
 
 /// The degree, $`r`$ of a finite field.
@@ -137,6 +274,29 @@ pub trait PrimeFiniteField:
     + IsSubFieldOf<Self, DegreeModulo = generic_array::typenum::U1>
     + std::convert::TryFrom<u128>
 {
+    /// Return the integer in `[0, p)` representing this element.
+    #[cfg(feature = "biguint")]
+    fn to_biguint(&self) -> num_bigint::BigUint {
+        num_bigint::BigUint::from_bytes_le(&crate::serialization::CanonicalSerialize::to_bytes(
+            self,
+        ))
+    }
+
+    /// Return the element represented by `x`, which must be less than the modulus.
+    #[cfg(feature = "biguint")]
+    fn from_biguint(
+        x: &num_bigint::BigUint,
+    ) -> Result<Self, crate::serialization::BiggerThanModulus> {
+        use generic_array::typenum::Unsigned;
+        let bytes = x.to_bytes_le();
+        if bytes.len() > Self::ByteReprLen::USIZE {
+            return Err(crate::serialization::BiggerThanModulus);
+        }
+        let mut buf: GenericArray<u8, Self::ByteReprLen> = Default::default();
+        buf[..bytes.len()].copy_from_slice(&bytes);
+        <Self as crate::serialization::CanonicalSerialize>::from_bytes(&buf)
+            .map_err(|_| crate::serialization::BiggerThanModulus)
+    }
 }
 
 #[cfg(test)]
@@ -185,6 +345,47 @@ macro_rules! field_ops {
     };
 }
 
+/// The constants of the Montgomery representation modulo an odd prime `p` of
+/// at most 64 bits, with `R = 2^64`.
+///
+/// The Montgomery form of `x` is `x * R mod p`, and the Montgomery reduction of
+/// `T < p * R` is `T * R^-1 mod p`, computed from `n_prime`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MontgomeryParams {
+    /// The modulus `p`.
+    pub modulus: u64,
+    /// `R mod p`, which is the Montgomery form of one.
+    pub r: u64,
+    /// `R^2 mod p`, which maps `x` to its Montgomery form by a Montgomery multiplication.
+    pub r2: u64,
+    /// `-p^-1 mod R`.
+    pub n_prime: u64,
+}
+
+impl MontgomeryParams {
+    /// Compute the constants for the odd modulus `p`.
+    ///
+    /// # Panics
+    /// This function will panic if `p` is even.
+    pub fn new(p: u64) -> Self {
+        assert_eq!(p % 2, 1, "the Montgomery modulus must be odd");
+        // Newton's iteration doubles the number of correct low bits of `p^-1`,
+        // starting from `p`, which is its own inverse modulo 2^3.
+        let mut inv = p;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+        }
+        let r = ((1u128 << 64) % u128::from(p)) as u64;
+        let r2 = (u128::from(r) * u128::from(r) % u128::from(p)) as u64;
+        Self {
+            modulus: p,
+            r,
+            r2,
+            n_prime: inv.wrapping_neg(),
+        }
+    }
+}
+
 /// Bit decomposition of `bits` into an array.
 pub(crate) fn standard_bit_decomposition<L: ArrayLength<bool>>(
     bits: u128,
@@ -200,7 +401,7 @@ mod f2;
 pub use f2::F2;
 
 mod f128b;
-pub use f128b::F128b;
+pub use f128b::{F128b, F128bChecksum, ParseF128bError};
 
 mod f64b;
 pub use f64b::F64b;
@@ -222,6 +423,7 @@ mod f2e19x3e26;
 #[cfg(feature = "ff")]
 pub use f2e19x3e26::F2e19x3e26;
 
+pub mod axioms;
 pub mod polynomial;
 
 pub mod fft;
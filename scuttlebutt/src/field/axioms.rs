@@ -0,0 +1,112 @@
+//! Runtime checks of the invariants of a [`FiniteField`] implementation.
+//!
+//! The fields of this crate are tested with property tests. Crates defining
+//! their own fields can use [`check_field_axioms`] to run similar checks on
+//! random elements.
+
+use crate::{field::FiniteField, ring::FiniteRing};
+use rand::Rng;
+
+/// The number of random elements checked by [`check_field_axioms`].
+pub const FIELD_AXIOMS_ITERATIONS: usize = 100;
+
+/// Check the field axioms and the consistency of `FE` on random elements.
+///
+/// This checks the `ZERO` and `ONE` identities, associativity, commutativity
+/// and distributivity, additive and multiplicative inverses, serialization
+/// round trips, and the consistency of multiplying by an element of the prime
+/// subfield with multiplying by its lift into `FE`.
+///
+/// # Panics
+/// Panics with a description of the first invariant which does not hold.
+pub fn check_field_axioms<FE: FiniteField, R: Rng>(rng: &mut R) {
+    assert_ne!(FE::ZERO, FE::ONE, "ZERO and ONE must differ");
+    for _ in 0..FIELD_AXIOMS_ITERATIONS {
+        let a = FE::random(rng);
+        let b = FE::random(rng);
+        let c = FE::random(rng);
+
+        assert_eq!(a + FE::ZERO, a, "ZERO is not the additive identity");
+        assert_eq!(a * FE::ONE, a, "ONE is not the multiplicative identity");
+        assert_eq!(a * FE::ZERO, FE::ZERO, "multiplying by ZERO is not ZERO");
+        assert_eq!(a + b, b + a, "addition is not commutative");
+        assert_eq!(a * b, b * a, "multiplication is not commutative");
+        assert_eq!((a + b) + c, a + (b + c), "addition is not associative");
+        assert_eq!(
+            (a * b) * c,
+            a * (b * c),
+            "multiplication is not associative"
+        );
+        assert_eq!(
+            a * (b + c),
+            a * b + a * c,
+            "multiplication is not distributive"
+        );
+        assert_eq!(a + (-a), FE::ZERO, "negation is not the additive inverse");
+        assert_eq!(a - b, a + (-b), "subtraction is not adding the negation");
+        if a != FE::ZERO {
+            assert_eq!(
+                a * a.inverse(),
+                FE::ONE,
+                "inverse is not the multiplicative inverse"
+            );
+            assert_eq!(
+                b / a,
+                b * a.inverse(),
+                "division is not multiplying by the inverse"
+            );
+        }
+
+        assert_eq!(
+            FE::from_bytes(&a.to_bytes()).expect("deserializing a serialized element failed"),
+            a,
+            "from_bytes(to_bytes(x)) is not x"
+        );
+
+        let p = FE::PrimeField::random(rng);
+        let q = FE::PrimeField::random(rng);
+        let lift: FE = p.into();
+        let lift_pq: FE = (p * q).into();
+        let lift_q: FE = q.into();
+        assert_eq!(
+            p * a,
+            lift * a,
+            "multiplying by the prime subfield is inconsistent"
+        );
+        assert_eq!(
+            lift_pq,
+            lift * lift_q,
+            "lifting the prime subfield is not a homomorphism"
+        );
+        assert_eq!(
+            FE::from_subfield(&a.decompose::<FE::PrimeField>()),
+            a,
+            "decomposing into the prime subfield does not round trip"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_field_axioms;
+    use crate::{
+        field::{F128b, F40b, F61p},
+        AesRng,
+    };
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_field_axioms_f128b() {
+        check_field_axioms::<F128b, _>(&mut AesRng::from_seed(Default::default()));
+    }
+
+    #[test]
+    fn test_field_axioms_f61p() {
+        check_field_axioms::<F61p, _>(&mut AesRng::from_seed(Default::default()));
+    }
+
+    #[test]
+    fn test_field_axioms_f40b() {
+        check_field_axioms::<F40b, _>(&mut AesRng::from_seed(Default::default()));
+    }
+}
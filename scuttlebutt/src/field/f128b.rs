@@ -44,7 +44,236 @@ impl<'a> SubAssign<&'a F128b> for F128b {
     }
 }
 
+impl F128b {
+    /// Return the integer whose bits are the coefficients of this element, with
+    /// the constant term as the least significant bit.
+    #[cfg(feature = "biguint")]
+    pub fn to_biguint(&self) -> num_bigint::BigUint {
+        num_bigint::BigUint::from_bytes_le(&self.0.to_le_bytes())
+    }
+
+    /// Return the element whose coefficients are the bits of `x`, which must be
+    /// less than `2^128`.
+    #[cfg(feature = "biguint")]
+    pub fn from_biguint(
+        x: &num_bigint::BigUint,
+    ) -> Result<Self, crate::serialization::BiggerThanModulus> {
+        u128::try_from(x)
+            .map(F128b)
+            .map_err(|_| crate::serialization::BiggerThanModulus)
+    }
+
+    /// Set `out[i] = a[i] + b[i]` for each `i`.
+    ///
+    /// Pairs of elements are XORed in 256-bit vectors, which compile to
+    /// `_mm256_xor_si256` on AVX2 and fall back to scalar XORs elsewhere.
+    ///
+    /// # Panics
+    /// Panics if the slices don't all have the same length.
+    pub fn add_slice(out: &mut [F128b], a: &[F128b], b: &[F128b]) {
+        use vectoreyes::U64x4;
+        assert_eq!(out.len(), a.len());
+        assert_eq!(out.len(), b.len());
+        let mut out_chunks = out.chunks_exact_mut(2);
+        let mut a_chunks = a.chunks_exact(2);
+        let mut b_chunks = b.chunks_exact(2);
+        for ((out, a), b) in (&mut out_chunks).zip(&mut a_chunks).zip(&mut b_chunks) {
+            let a: U64x4 = bytemuck::cast([a[0].0, a[1].0]);
+            let b: U64x4 = bytemuck::cast([b[0].0, b[1].0]);
+            let sum: [u128; 2] = bytemuck::cast(a ^ b);
+            out[0] = F128b(sum[0]);
+            out[1] = F128b(sum[1]);
+        }
+        for ((out, a), b) in out_chunks
+            .into_remainder()
+            .iter_mut()
+            .zip(a_chunks.remainder())
+            .zip(b_chunks.remainder())
+        {
+            *out = *a + *b;
+        }
+    }
+
+    /// Set `out[i] = a[i] * b[i]` for each `i`, see [`Self::mul_slice_assign`].
+    ///
+    /// # Panics
+    /// Panics if the slices don't all have the same length.
+    pub fn mul_slice(out: &mut [F128b], a: &[F128b], b: &[F128b]) {
+        assert_eq!(out.len(), a.len());
+        out.copy_from_slice(a);
+        Self::mul_slice_assign(out, b);
+    }
+
+    /// Set `a[i] *= b[i]` for each `i`.
+    ///
+    /// Four products are computed per iteration, with their carryless
+    /// multiplications ahead of their reductions, so that the latencies of the
+    /// multiplications overlap.
+    ///
+    /// # Panics
+    /// Panics if the slices don't have the same length.
+    pub fn mul_slice_assign(a: &mut [F128b], b: &[F128b]) {
+        assert_eq!(a.len(), b.len());
+        let mut a_chunks = a.chunks_exact_mut(4);
+        let mut b_chunks = b.chunks_exact(4);
+        for (a, b) in (&mut a_chunks).zip(&mut b_chunks) {
+            let wide: [(u128, u128); 4] =
+                std::array::from_fn(|i| multiply::mul_wide(a[i].0, b[i].0));
+            for (a, (upper, lower)) in a.iter_mut().zip(wide) {
+                *a = F128b(multiply::reduce(upper, lower));
+            }
+        }
+        for (a, b) in a_chunks
+            .into_remainder()
+            .iter_mut()
+            .zip(b_chunks.remainder())
+        {
+            *a *= *b;
+        }
+    }
+
+    /// Return the name of the carryless multiplication used by `Mul`, which is
+    /// chosen at compile time.
+    ///
+    /// This is `"pclmulqdq"` on x86-64 when `vectoreyes` uses AVX2, `"pmull"` on
+    /// AArch64 with the `aes` target feature, and otherwise `"software"`, the
+    /// portable constant-time multiplication of the scalar backend of
+    /// `vectoreyes`.
+    pub fn multiply_backend() -> &'static str {
+        multiply::BACKEND
+    }
+
+    /// Return the checksum of `data`, see [`F128bChecksum`].
+    pub fn checksum(data: &[u8]) -> F128b {
+        let mut checksum = F128bChecksum::new();
+        checksum.update(data);
+        checksum.finish()
+    }
+}
+
+/// Format the element as the hexadecimal integer whose bits are its
+/// coefficients, with the constant term as the least significant bit.
+impl std::fmt::LowerHex for F128b {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+/// See the [`LowerHex`](std::fmt::LowerHex) implementation.
+impl std::fmt::UpperHex for F128b {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+/// Parse an element from at most 32 hexadecimal digits, optionally prefixed by
+/// `0x`, as written by the [`LowerHex`](std::fmt::LowerHex) implementation.
+impl std::str::FromStr for F128b {
+    type Err = ParseF128bError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if digits.is_empty() || digits.len() > 32 || !digits.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return Err(ParseF128bError);
+        }
+        u128::from_str_radix(digits, 16)
+            .map(F128b)
+            .map_err(|_| ParseF128bError)
+    }
+}
+
+/// The error which occurs if a string isn't an [`F128b`] in hexadecimal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseF128bError;
+impl std::error::Error for ParseF128bError {}
+impl std::fmt::Display for ParseF128bError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Expected at most 32 hexadecimal digits")
+    }
+}
+
+/// An incremental checksum of a byte stream in $\textsf{GF}(2^{128})$.
+///
+/// The stream is split into 16-byte little-endian blocks, the last one padded
+/// with zeros, followed by a block holding the length of the stream in bytes.
+/// The checksum is the evaluation at a fixed point of the polynomial whose
+/// coefficients are these blocks, as for GHASH but without a key.
+///
+/// Since the point is public, this detects accidental corruption of the data,
+/// such as transmission errors, but is not a MAC: anyone can forge data with a
+/// given checksum.
+#[derive(Debug, Clone)]
+pub struct F128bChecksum {
+    acc: F128b,
+    buffer: [u8; 16],
+    buffered: usize,
+    len: u64,
+}
+
+impl F128bChecksum {
+    // The binary expansion of the golden ratio, an arbitrary fixed point.
+    const POINT: F128b = F128b(0x9e3779b97f4a7c15f39cc0605cedc834);
+
+    /// Create the checksum of the empty stream.
+    pub fn new() -> Self {
+        Self {
+            acc: F128b::ZERO,
+            buffer: [0; 16],
+            buffered: 0,
+            len: 0,
+        }
+    }
+
+    fn absorb(&mut self, block: u128) {
+        self.acc = (self.acc + F128b(block)) * Self::POINT;
+    }
+
+    /// Append `data` to the stream.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len += data.len() as u64;
+        if self.buffered > 0 {
+            let n = data.len().min(16 - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+            if self.buffered < 16 {
+                return;
+            }
+            self.absorb(u128::from_le_bytes(self.buffer));
+            self.buffered = 0;
+        }
+        let mut blocks = data.chunks_exact(16);
+        for block in &mut blocks {
+            self.absorb(u128::from_le_bytes(block.try_into().unwrap()));
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Return the checksum of the stream.
+    pub fn finish(mut self) -> F128b {
+        if self.buffered > 0 {
+            self.buffer[self.buffered..].fill(0);
+            self.absorb(u128::from_le_bytes(self.buffer));
+        }
+        self.absorb(u128::from(self.len));
+        self.acc
+    }
+}
+
+impl Default for F128bChecksum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 mod multiply {
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "aes")))]
     use vectoreyes::{SimdBase8, U64x2, U8x16};
 
     // TODO: this implements a simple algorithm that works. There are faster algorithms.
@@ -58,16 +287,28 @@ mod multiply {
     // See: https://blog.quarkslab.com/reversing-a-finite-field-multiplication-optimization.html
     // See: https://tools.ietf.org/html/rfc8452
 
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "aes")))]
+    pub(crate) const BACKEND: &str = match vectoreyes::VECTOR_BACKEND {
+        vectoreyes::VectorBackend::Scalar => "software",
+        _ => "pclmulqdq",
+    };
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "aes"))]
+    pub(crate) const BACKEND: &str = "pmull";
+
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "aes")))]
     #[inline(always)]
     fn upper_bits_made_lower(a: U64x2) -> U64x2 {
         U64x2::from(U8x16::from(a).shift_bytes_right::<8>())
     }
 
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "aes")))]
     #[inline(always)]
     fn lower_bits_made_upper(a: U64x2) -> U64x2 {
         U64x2::from(U8x16::from(a).shift_bytes_left::<8>())
     }
 
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "aes")))]
     #[inline(always)]
     pub(crate) fn mul_wide(a: u128, b: u128) -> (u128, u128) {
         // The constants determine
@@ -91,6 +332,30 @@ mod multiply {
         )
     }
 
+    // On AArch64, vectoreyes only has its scalar backend, so we use the PMULL
+    // instruction, which is part of the `aes` target feature, directly.
+    #[cfg(all(target_arch = "aarch64", target_feature = "aes"))]
+    #[inline(always)]
+    pub(crate) fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+        use std::arch::aarch64::vmull_p64;
+        // SAFETY: the `aes` target feature, which provides PMULL, is enabled.
+        karatsuba(a, b, |x, y| unsafe { vmull_p64(x, y) })
+    }
+
+    // The same Karatsuba multiplication as above, with 64-bit halves multiplied
+    // by `clmul`. This is built for tests on every target, so that it is checked
+    // with a software `clmul` even where PMULL is not available.
+    #[cfg(any(test, all(target_arch = "aarch64", target_feature = "aes")))]
+    #[inline(always)]
+    fn karatsuba(a: u128, b: u128, clmul: impl Fn(u64, u64) -> u128) -> (u128, u128) {
+        let (a1, a0) = ((a >> 64) as u64, a as u64);
+        let (b1, b0) = ((b >> 64) as u64, b as u64);
+        let lower = clmul(a0, b0);
+        let upper = clmul(a1, b1);
+        let middle = clmul(a0 ^ a1, b0 ^ b1) ^ lower ^ upper;
+        (upper ^ (middle >> 64), lower ^ (middle << 64))
+    }
+
     #[inline(always)]
     pub(crate) fn reduce(upper: u128, lower: u128) -> u128 {
         // Page 15 of https://is.gd/tOd246
@@ -157,6 +422,16 @@ mod multiply {
                     product
                 );
             }
+
+            #[test]
+            fn karatsuba_matches_mul_wide(a in any::<u128>(), b in any::<u128>()) {
+                fn clmul(x: u64, y: u64) -> u128 {
+                    (0..64)
+                        .filter(|i| (y >> i) & 1 == 1)
+                        .fold(0, |acc, i| acc ^ (u128::from(x) << i))
+                }
+                assert_eq!(karatsuba(a, b, clmul), mul_wide(a, b));
+            }
         }
 
         fn assert_div_mod(
@@ -255,7 +530,31 @@ impl FiniteField for F128b {
         if *self == Self::ZERO {
             panic!("Zero cannot be inverted");
         }
-        self.pow_var_time(u128::MAX - 1)
+        // Itoh-Tsujii: the inverse is `self^(2^128 - 2) = (self^(2^127 - 1))^2`,
+        // and `beta = self^(2^k - 1)` satisfies `self^(2^(2k) - 1) = beta^(2^k) * beta`
+        // and `self^(2^(k + 1) - 1) = beta^2 * self`. Following the bits of 127,
+        // this takes 127 squarings and 12 multiplications.
+        fn square_n(mut x: F128b, n: usize) -> F128b {
+            for _ in 0..n {
+                x = x * x;
+            }
+            x
+        }
+        let mut beta = *self;
+        let mut k = 1;
+        while k < 127 {
+            beta = square_n(beta, k) * beta;
+            beta = beta * beta * *self;
+            k = 2 * k + 1;
+        }
+        beta * beta
+    }
+
+    fn multiplicative_group_factorization() -> Option<(u128, &'static [u128])> {
+        Some((
+            u128::MAX,
+            &[3, 5, 17, 257, 641, 65537, 274177, 6700417, 67280421310721],
+        ))
     }
 }
 
@@ -297,15 +596,148 @@ field_ops!(F128b);
 #[cfg(test)]
 mod tests {
     test_field!(test_field, crate::field::F128b);
+
+    use super::{F128b, F128bChecksum, ParseF128bError};
+    use crate::field::{FiniteField, WrongNumberOfCoefficients, F2};
+    use crate::ring::FiniteRing;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn add_slice(pairs in proptest::collection::vec(any::<(u128, u128)>(), 0..20)) {
+            let a: Vec<_> = pairs.iter().map(|(a, _)| F128b(*a)).collect();
+            let b: Vec<_> = pairs.iter().map(|(_, b)| F128b(*b)).collect();
+            let mut out = vec![F128b(0); pairs.len()];
+            F128b::add_slice(&mut out, &a, &b);
+            for ((out, a), b) in out.iter().zip(&a).zip(&b) {
+                prop_assert_eq!(*out, *a + *b);
+            }
+        }
+
+        #[test]
+        fn hex_roundtrip(x in any::<u128>()) {
+            let x = F128b(x);
+            prop_assert_eq!(format!("{:x}", x).parse::<F128b>(), Ok(x));
+            prop_assert_eq!(format!("{:#X}", x).parse::<F128b>(), Ok(x));
+        }
+
+        #[test]
+        fn inverse_matches_fermat(x in any::<u128>()) {
+            prop_assume!(x != 0);
+            let x = F128b(x);
+            prop_assert_eq!(x.inverse(), x.pow_var_time(u128::MAX - 1));
+        }
+
+        #[test]
+        fn mul_slice(pairs in proptest::collection::vec(any::<(u128, u128)>(), 0..20)) {
+            let a: Vec<_> = pairs.iter().map(|(a, _)| F128b(*a)).collect();
+            let b: Vec<_> = pairs.iter().map(|(_, b)| F128b(*b)).collect();
+            let mut out = vec![F128b(0); pairs.len()];
+            F128b::mul_slice(&mut out, &a, &b);
+            for ((out, a), b) in out.iter().zip(&a).zip(&b) {
+                prop_assert_eq!(*out, *a * *b);
+            }
+            let mut a = a;
+            F128b::mul_slice_assign(&mut a, &b);
+            prop_assert_eq!(a, out);
+        }
+
+        #[test]
+        fn checksum_incremental(
+            data in proptest::collection::vec(any::<u8>(), 0..100),
+            splits in proptest::collection::vec(0..100usize, 0..5),
+        ) {
+            let mut checksum = F128bChecksum::new();
+            let mut rest = &data[..];
+            for split in splits {
+                let (head, tail) = rest.split_at(split.min(rest.len()));
+                checksum.update(head);
+                rest = tail;
+            }
+            checksum.update(rest);
+            prop_assert_eq!(checksum.finish(), F128b::checksum(&data));
+        }
+
+        #[test]
+        fn try_from_coefficients(x in any::<u128>()) {
+            let x = F128b(x);
+            let coeffs = x.decompose::<F2>();
+            prop_assert_eq!(F128b::try_from_coefficients(&coeffs).unwrap(), x);
+        }
+    }
+
+    #[test]
+    fn hex_parse() {
+        assert_eq!("0x87".parse(), Ok(F128b(0x87)));
+        assert_eq!("abc".parse(), Ok(F128b(0xabc)));
+        assert_eq!(format!("{:032x}", F128b(1)).parse(), Ok(F128b::ONE));
+        assert_eq!("f".repeat(32).parse(), Ok(F128b(u128::MAX)));
+        for s in ["", "0x", "+1", "0xg", &"0".repeat(33)] {
+            assert_eq!(s.parse::<F128b>(), Err(ParseF128bError), "{:?}", s);
+        }
+        assert_eq!(format!("{:X}", F128b(0xabc)), "ABC");
+    }
+
+    #[test]
+    #[should_panic(expected = "Zero cannot be inverted")]
+    fn inverse_of_zero_panics() {
+        F128b::ZERO.inverse();
+    }
+
+    #[test]
+    fn multiply_backend() {
+        assert!(["pclmulqdq", "pmull", "software"].contains(&F128b::multiply_backend()));
+    }
+
+    #[test]
+    fn checksum_known_values() {
+        let data: Vec<u8> = (0..40).collect();
+        for (input, expected) in [
+            (&b""[..], 0),
+            (&b"abc"[..], 0x56602df478b2e459e9f71ad21a074c7e),
+            (&data[..], 0xe7eb50bedd957f7a822da917b0e50940),
+        ] {
+            assert_eq!(F128b::checksum(input), F128b(expected), "{:?}", input);
+        }
+        // Trailing zeros change the length block.
+        assert_ne!(F128b::checksum(b"abc"), F128b::checksum(b"abc\0"));
+    }
+
+    #[test]
+    fn try_from_coefficients_wrong_length_fails() {
+        for len in [0, 127, 129] {
+            let coeffs = vec![F2::ONE; len];
+            assert_eq!(
+                F128b::try_from_coefficients(&coeffs),
+                Err(WrongNumberOfCoefficients {
+                    expected: 128,
+                    got: len
+                })
+            );
+        }
+    }
 }
 
 #[test]
 fn test_generator() {
-    let n = u128::MAX;
-    let prime_factors: Vec<u128> = vec![67280421310721, 274177, 6700417, 641, 65537, 257, 17, 5, 3];
-    let x = F128b::GENERATOR;
-    for p in prime_factors.iter() {
-        let p = *p;
-        assert_ne!(F128b::ONE, x.pow(n / p));
+    assert_eq!(F128b::GENERATOR.multiplicative_order(), Some(u128::MAX));
+    assert_eq!(
+        F128b::GENERATOR.pow(3).multiplicative_order(),
+        Some(u128::MAX / 3)
+    );
+    assert_eq!(F128b::ONE.multiplicative_order(), Some(1));
+    assert_eq!(F128b::ZERO.multiplicative_order(), None);
+}
+
+#[test]
+fn test_eq_bool() {
+    use rand::SeedableRng;
+    let mut rng = crate::AesRng::from_seed(Default::default());
+    for _ in 0..100 {
+        let a = F128b::random(&mut rng);
+        let b = F128b::random(&mut rng);
+        assert!(a.eq_bool(&a));
+        assert_eq!(a.eq_bool(&b), a == b);
+        assert!(!a.eq_bool(&(a + F128b::ONE)));
     }
 }
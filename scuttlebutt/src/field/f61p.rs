@@ -1,4 +1,4 @@
-use crate::field::{polynomial::Polynomial, FiniteField, PrimeFiniteField};
+use crate::field::{polynomial::Polynomial, FiniteField, MontgomeryParams, PrimeFiniteField};
 use crate::ring::FiniteRing;
 use crate::serialization::{BiggerThanModulus, CanonicalSerialize};
 use generic_array::GenericArray;
@@ -90,6 +90,17 @@ impl FiniteField for F61p {
         }
         self.pow_var_time(u128::from(MODULUS) - 2)
     }
+
+    fn multiplicative_group_factorization() -> Option<(u128, &'static [u128])> {
+        Some((
+            u128::from(MODULUS) - 1,
+            &[2, 3, 5, 7, 11, 13, 31, 41, 61, 151, 331, 1321],
+        ))
+    }
+
+    fn montgomery_params() -> Option<MontgomeryParams> {
+        Some(MontgomeryParams::new(MODULUS))
+    }
 }
 
 #[inline]
@@ -189,10 +200,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_multiplicative_order() {
+        let order = u128::from(MODULUS) - 1;
+        assert_eq!(F61p::GENERATOR.multiplicative_order(), Some(order));
+        let square = F61p::GENERATOR * F61p::GENERATOR;
+        assert_eq!(square.multiplicative_order(), Some(order / 2));
+        assert_eq!((-F61p::ONE).multiplicative_order(), Some(2));
+        assert_eq!(F61p::ZERO.multiplicative_order(), None);
+    }
+
+    // Montgomery reduction of `t < p * 2^64`.
+    fn redc(params: &MontgomeryParams, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(params.n_prime);
+        let t = (t + u128::from(m) * u128::from(params.modulus)) >> 64;
+        (t % u128::from(params.modulus)) as u64
+    }
+
+    proptest! {
+        #[test]
+        fn test_montgomery_params(a in 0..MODULUS, b in 0..MODULUS) {
+            let params = F61p::montgomery_params().unwrap();
+            prop_assert_eq!(params.modulus.wrapping_mul(params.n_prime), u64::MAX);
+            let to_montgomery = |x: u64| redc(&params, u128::from(x) * u128::from(params.r2));
+            let a_mont = to_montgomery(a);
+            let b_mont = to_montgomery(b);
+            prop_assert_eq!(u128::from(a_mont), (u128::from(a) << 64) % u128::from(MODULUS));
+            let product = F61p(a) * F61p(b);
+            prop_assert_eq!(
+                redc(&params, u128::from(a_mont) * u128::from(b_mont)),
+                to_montgomery(product.0)
+            );
+            prop_assert_eq!(redc(&params, u128::from(to_montgomery(product.0))), product.0);
+            prop_assert_eq!(redc(&params, u128::from(params.r)), 1);
+        }
+    }
+
     #[test]
     fn test_sum_overflow() {
         let neg1 = F61p::ZERO - F61p::ONE;
         let x = [neg1; 2];
         assert_eq!(x.iter().map(|x| *x).sum::<F61p>(), neg1 + neg1);
     }
+
+    #[cfg(feature = "biguint")]
+    proptest! {
+        #[test]
+        fn test_biguint_roundtrip(x in 0..MODULUS) {
+            let x = F61p(x);
+            let big = x.to_biguint();
+            prop_assert_eq!(big.clone(), num_bigint::BigUint::from(x.0));
+            prop_assert_eq!(F61p::from_biguint(&big).unwrap(), x);
+        }
+    }
+
+    #[cfg(feature = "biguint")]
+    #[test]
+    fn test_from_biguint_rejects_modulus() {
+        use num_bigint::BigUint;
+        assert!(F61p::from_biguint(&BigUint::from(MODULUS)).is_err());
+        assert!(F61p::from_biguint(&(BigUint::from(1u8) << 64)).is_err());
+        assert_eq!(
+            F61p::from_biguint(&BigUint::from(MODULUS - 1)).unwrap(),
+            -F61p::ONE
+        );
+    }
 }
@@ -109,6 +109,31 @@ macro_rules! test_field {
                 }
             }
 
+            proptest! {
+                #[test]
+                fn batch_inverse(
+                    elements in proptest::collection::vec(
+                        prop_oneof![1 => Just(<$f>::ZERO), 4 => any_fe()],
+                        0..20,
+                    ),
+                ) {
+                    let mut inverses = elements.clone();
+                    <$f>::batch_inverse(&mut inverses);
+                    for (x, x_inv) in elements.iter().zip(&inverses) {
+                        if *x == <$f>::ZERO {
+                            prop_assert_eq!(*x_inv, <$f>::ZERO);
+                        } else {
+                            prop_assert_eq!(*x_inv, x.inverse());
+                        }
+                    }
+                }
+            }
+
+            #[test]
+            fn self_check() {
+                <$f>::self_check();
+            }
+
             #[test]
             fn polynomial_constants() {
                 assert_eq!(
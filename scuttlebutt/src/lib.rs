@@ -31,7 +31,11 @@ pub use crate::{
     },
     block::Block,
     block512::Block512,
-    channel::{AbstractChannel, Channel, HashChannel, SymChannel, SyncChannel, TrackChannel},
+    channel::{
+        lockstep_channel_pair, AbstractChannel, BackpressureChannel, Channel, ChannelPool,
+        HashChannel, LockstepChannel, PooledChannel, RecordingChannel, SymChannel, SyncChannel,
+        TrackChannel,
+    },
     hash_aes::{AesHash, AES_HASH},
     rand_aes::{vectorized::UniformIntegersUnderBound, AesRng},
 };
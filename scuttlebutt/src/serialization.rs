@@ -225,6 +225,10 @@ macro_rules! serde_implementation {
         impl serde::Serialize for $f {
             fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
                 let bytes = <Self as $crate::serialization::CanonicalSerialize>::to_bytes(&self);
+                #[cfg(feature = "serde-hex")]
+                if serializer.is_human_readable() {
+                    return serializer.serialize_str(&$crate::serialization::hex_encode(&bytes));
+                }
                 serializer.serialize_bytes(&bytes)
             }
         }
@@ -246,10 +250,7 @@ macro_rules! serde_implementation {
                         )
                     }
 
-                    fn visit_borrowed_bytes<E: serde::de::Error>(
-                        self,
-                        v: &'de [u8],
-                    ) -> Result<Self::Value, E> {
+                    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
                         use generic_array::typenum::Unsigned;
                         if v.len()
                             != <$f as $crate::serialization::CanonicalSerialize>::ByteReprLen::USIZE
@@ -261,6 +262,14 @@ macro_rules! serde_implementation {
                             .map_err(serde::de::Error::custom)
                     }
 
+                    #[cfg(feature = "serde-hex")]
+                    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                        let bytes = $crate::serialization::hex_decode(v).ok_or_else(|| {
+                            E::invalid_value(serde::de::Unexpected::Str(v), &self)
+                        })?;
+                        self.visit_bytes(&bytes)
+                    }
+
                     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
                     where
                         A: serde::de::SeqAccess<'de>,
@@ -284,6 +293,10 @@ macro_rules! serde_implementation {
                     }
                 }
 
+                #[cfg(feature = "serde-hex")]
+                if deserializer.is_human_readable() {
+                    return deserializer.deserialize_str(FieldVisitor);
+                }
                 deserializer.deserialize_bytes(FieldVisitor)
             }
         }
@@ -292,6 +305,30 @@ macro_rules! serde_implementation {
 
 pub(crate) use serde_implementation;
 
+/// Encode `bytes` as lowercase hexadecimal, for the human-readable form of the
+/// `serde` implementation of field elements.
+#[cfg(feature = "serde-hex")]
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    let mut out = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        write!(out, "{:02x}", byte).unwrap();
+    }
+    out
+}
+
+/// Decode the hexadecimal string `s`, returning `None` if it is malformed.
+#[cfg(feature = "serde-hex")]
+pub(crate) fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
 /// Serialization tests.
 /// XXX: Currently this assumes `$f` is a `FiniteRing`.
 #[cfg(test)]
@@ -330,6 +367,18 @@ macro_rules! test_serialization {
                     assert_eq!(a, b);
                 }
             }
+            #[cfg(feature = "serde-hex")]
+            proptest! {
+                #[test]
+                fn serde_serialize_serde_json_hex(a in any_element()) {
+                    let ser = serde_json::to_string(&a).unwrap();
+                    prop_assert_eq!(&ser, &format!("\"{}\"", hex::encode(a.to_bytes())));
+                    let b: $f = serde_json::from_str(&ser).unwrap();
+                    prop_assert_eq!(a, b);
+                    let malformed = format!("\"zz{}", &ser[1..]);
+                    prop_assert!(serde_json::from_str::<$f>(&malformed).is_err());
+                }
+            }
             proptest! {
                 #[test]
                 fn serde_serialize_bincode(a in any_element()) {